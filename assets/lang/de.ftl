@@ -4,17 +4,69 @@ native-lang-name = Deutsch
 file-menu-item = Datei
 new-menu-item = Neu
 open-menu-item = Öffnen
+import-netlist-menu-item = Netzliste importieren
+open-recent-menu-item = Zuletzt geöffnet
 save-menu-item = Speichern
 save-as-menu-item = Speichern unter
+save-all-menu-item = Alle speichern
+export-image-menu-item = Bild exportieren
+export-netlist-menu-item = Netzliste exportieren
+circuit-info-menu-item = Schaltungsinfo
+duplicate-circuit-menu-item = Schaltung duplizieren
+close-all-menu-item = Alle schließen
 language-menu-item = Sprache
+settings-menu-item = Einstellungen
+json-file-filter = Schaltung (JSON)
+binary-file-filter = Schaltung (binär)
+duplicate-circuit-name = { $name } (Kopie)
+
+unsaved-changes-title = Ungespeicherte Änderungen
+close-unsaved-message = Diese Schaltung hat ungespeicherte Änderungen. Trotzdem schließen?
+close-all-unsaved-message = Es gibt Schaltungen mit ungespeicherten Änderungen. Trotzdem alle schließen?
+quit-unsaved-message = Es gibt Schaltungen mit ungespeicherten Änderungen. Trotzdem beenden?
+discard-action = Änderungen verwerfen
+cancel-action = Abbrechen
+
+open-error-title = Datei konnte nicht geöffnet werden
+save-error-title = Datei konnte nicht gespeichert werden
+export-error-title = Datei konnte nicht exportiert werden
+import-error-title = Netzliste konnte nicht importiert werden
+ok-action = OK
 
 light-theme-name = Hell
 dark-theme-name = Dunkel
+system-theme-name = System
+
+settings-title = Einstellungen
+grid-header = Raster
+grid-style-setting-name = Stil:
+grid-dots-style-name = Punkte
+grid-lines-style-name = Linien
+grid-crosses-style-name = Kreuze
+grid-minor-spacing-setting-name = Abstand:
+grid-major-spacing-setting-name = Hauptraster alle:
+grid-opacity-setting-name = Deckkraft:
+simulation-header = Simulation
+max-steps-setting-name = Max. Schritte:
+
+import-netlist-title = Netzliste importieren
+import-netlist-message = Eine Komponente pro Zeile: Art, optional die Bitbreite nach einem Doppelpunkt, dann die Netze ihrer Anschlüsse. Beispiel: and:4 a b y
+import-action = Importieren
+circuit-info-title = Schaltungsinfo
+components-stat-name = Komponenten:
+wire-segments-stat-name = Leitungssegmente:
+wire-length-stat-name = Gesamte Leitungslänge:
+nets-stat-name = Netze:
 
 ports-header = Anschlüsse
 input-tool-tip = Eingang
 clock-input-tool-tip = Takt-Eingang
+push-button-tool-tip = Taster
 output-tool-tip = Ausgang
+port-tool-tip = Bidirektionaler Anschluss
+seven-segment-tool-tip = Siebensegmentanzeige
+led-tool-tip = LED
+constant-tool-tip = Konstante
 
 logic-header = Logik
 and-gate-tool-tip = AND-Gatter
@@ -25,10 +77,64 @@ xor-gate-tool-tip = XOR-Gatter
 xnor-gate-tool-tip = XNOR-Gatter
 buffer-tool-tip = Puffer
 not-gate-tool-tip = NOT-Gatter
+tri-state-buffer-tool-tip = Tri-State-Puffer
+
+memory-header = Speicher
+d-flip-flop-tool-tip = D-Flipflop
+
+wiring-header = Verdrahtung
+splitter-tool-tip = Splitter
+tunnel-tool-tip = Tunnel
+
+paste-here-menu-item = Hier einfügen
+delete-menu-item = Löschen
+rotate-cw-menu-item = Im Uhrzeigersinn drehen
+rotate-ccw-menu-item = Gegen den Uhrzeigersinn drehen
+mirror-menu-item = Spiegeln
+auto-route-menu-item = Automatisch verlegen
+align-menu-item = Ausrichten
+align-left-menu-item = Links ausrichten
+align-center-menu-item = Zentriert ausrichten
+align-right-menu-item = Rechts ausrichten
+align-top-menu-item = Oben ausrichten
+align-middle-menu-item = Mittig ausrichten
+align-bottom-menu-item = Unten ausrichten
+distribute-horizontally-menu-item = Horizontal verteilen
+distribute-vertically-menu-item = Vertikal verteilen
+edit-properties-menu-item = Eigenschaften bearbeiten
 
 properties-header = Eigenschaften
 name-property-name = Name:
 bit-width-property-name = Bit-Breite:
 rotation-property-name = Rotation:
 mirrored-property-name = Gespiegelt
+input-count-property-name = Eingänge:
+inverted-property-name = Ausgang invertiert
+bit-ranges-property-name = Bit-Bereiche:
+value-property-name = Wert:
+driven-property-name = Treibt das Netz
+radix-property-name = Zahlensystem:
+binary-radix-name = Bin
+decimal-radix-name = Dez
+hexadecimal-radix-name = Hex
 reset-to-default-action = Auf Standard zurücksetzen
+mixed-property-value = (gemischt)
+
+probes-header = Messpunkte
+
+width-conflict-error = Komponenten unterschiedlicher Bit-Breite sind verbunden
+unsupported-splitter-error = Splitter können noch nicht simuliert werden
+sim-unstable-warning = Simulation hat sich nicht stabilisiert, max. Schritte:
+
+zoom-fit-action = Einpassen
+cursor-position-status = X: { $x }  Y: { $y }
+sim-idle-status = Inaktiv
+sim-running-status = Läuft
+sim-conflict-status = Konflikt
+sim-unstable-status = Instabil
+sim-steps-status = Schritte: { $steps }
+selection-count-status = { $count } ausgewählt
+
+splitter-range-bounds-error = Bereiche müssen innerhalb der Bit-Breite liegen
+splitter-range-overlap-error = Bereiche dürfen sich nicht überlappen
+splitter-range-width-error = Bereiche dürfen die Bit-Breite insgesamt nicht überschreiten
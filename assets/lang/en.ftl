@@ -4,17 +4,69 @@ native-lang-name = English
 file-menu-item = File
 new-menu-item = New
 open-menu-item = Open
+import-netlist-menu-item = Import netlist
+open-recent-menu-item = Open recent
 save-menu-item = Save
 save-as-menu-item = Save as
+save-all-menu-item = Save all
+export-image-menu-item = Export image
+export-netlist-menu-item = Export netlist
+circuit-info-menu-item = Circuit info
+duplicate-circuit-menu-item = Duplicate circuit
+close-all-menu-item = Close all
 language-menu-item = Language
+settings-menu-item = Settings
+json-file-filter = Circuit (JSON)
+binary-file-filter = Circuit (binary)
+duplicate-circuit-name = { $name } (copy)
+
+unsaved-changes-title = Unsaved changes
+close-unsaved-message = This circuit has unsaved changes. Close it anyway?
+close-all-unsaved-message = Some circuits have unsaved changes. Close them anyway?
+quit-unsaved-message = There are circuits with unsaved changes. Quit anyway?
+discard-action = Discard changes
+cancel-action = Cancel
+
+open-error-title = Could not open file
+save-error-title = Could not save file
+export-error-title = Could not export file
+import-error-title = Could not import netlist
+ok-action = OK
 
 light-theme-name = Light
 dark-theme-name = Dark
+system-theme-name = System
+
+settings-title = Settings
+grid-header = Grid
+grid-style-setting-name = Style:
+grid-dots-style-name = Dots
+grid-lines-style-name = Lines
+grid-crosses-style-name = Crosses
+grid-minor-spacing-setting-name = Spacing:
+grid-major-spacing-setting-name = Major grid every:
+grid-opacity-setting-name = Opacity:
+simulation-header = Simulation
+max-steps-setting-name = Max. steps:
+
+import-netlist-title = Import netlist
+import-netlist-message = One component per line: kind, optional bit width after a colon, then the nets of its anchors. Example: and:4 a b y
+import-action = Import
+circuit-info-title = Circuit info
+components-stat-name = Components:
+wire-segments-stat-name = Wire segments:
+wire-length-stat-name = Total wire length:
+nets-stat-name = Nets:
 
 ports-header = Ports
 input-tool-tip = Input port
 clock-input-tool-tip = Clock input port
+push-button-tool-tip = Push-button
 output-tool-tip = Output port
+port-tool-tip = Bidirectional port
+seven-segment-tool-tip = Seven-segment display
+led-tool-tip = LED
+constant-tool-tip = Constant
 
 logic-header = Logic
 and-gate-tool-tip = AND-Gate
@@ -25,10 +77,64 @@ xor-gate-tool-tip = XOR-Gate
 xnor-gate-tool-tip = XNOR-Gate
 buffer-tool-tip = Buffer
 not-gate-tool-tip = NOT-Gate
+tri-state-buffer-tool-tip = Tri-state buffer
+
+memory-header = Memory
+d-flip-flop-tool-tip = D-Flip-Flop
+
+wiring-header = Wiring
+splitter-tool-tip = Splitter
+tunnel-tool-tip = Tunnel
+
+paste-here-menu-item = Paste here
+delete-menu-item = Delete
+rotate-cw-menu-item = Rotate clockwise
+rotate-ccw-menu-item = Rotate counterclockwise
+mirror-menu-item = Mirror
+auto-route-menu-item = Auto-route
+align-menu-item = Align
+align-left-menu-item = Align left
+align-center-menu-item = Align center
+align-right-menu-item = Align right
+align-top-menu-item = Align top
+align-middle-menu-item = Align middle
+align-bottom-menu-item = Align bottom
+distribute-horizontally-menu-item = Distribute horizontally
+distribute-vertically-menu-item = Distribute vertically
+edit-properties-menu-item = Edit properties
 
 properties-header = Properties
 name-property-name = Name:
 bit-width-property-name = Bit-width:
 rotation-property-name = Rotation:
 mirrored-property-name = Mirrored
+input-count-property-name = Inputs:
+inverted-property-name = Inverted output
+bit-ranges-property-name = Bit-ranges:
+value-property-name = Value:
+driven-property-name = Drives the net
+radix-property-name = Radix:
+binary-radix-name = Bin
+decimal-radix-name = Dec
+hexadecimal-radix-name = Hex
 reset-to-default-action = Reset to default
+mixed-property-value = (mixed)
+
+probes-header = Probes
+
+width-conflict-error = Components of different bit-widths are connected
+unsupported-splitter-error = Splitters cannot be simulated yet
+sim-unstable-warning = Simulation did not settle within max. steps:
+
+zoom-fit-action = Fit
+cursor-position-status = X: { $x }  Y: { $y }
+sim-idle-status = Idle
+sim-running-status = Running
+sim-conflict-status = Conflict
+sim-unstable-status = Unstable
+sim-steps-status = Steps: { $steps }
+selection-count-status = { $count } selected
+
+splitter-range-bounds-error = Ranges must lie within the bit-width
+splitter-range-overlap-error = Ranges must not overlap
+splitter-range-width-error = Ranges must not exceed the bit-width in total
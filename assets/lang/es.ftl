@@ -0,0 +1,140 @@
+english-lang-name = Spanish
+native-lang-name = Español
+
+file-menu-item = Archivo
+new-menu-item = Nuevo
+open-menu-item = Abrir
+import-netlist-menu-item = Importar netlist
+open-recent-menu-item = Abrir reciente
+save-menu-item = Guardar
+save-as-menu-item = Guardar como
+save-all-menu-item = Guardar todo
+export-image-menu-item = Exportar imagen
+export-netlist-menu-item = Exportar netlist
+circuit-info-menu-item = Información del circuito
+duplicate-circuit-menu-item = Duplicar circuito
+close-all-menu-item = Cerrar todo
+language-menu-item = Idioma
+settings-menu-item = Ajustes
+json-file-filter = Circuito (JSON)
+binary-file-filter = Circuito (binario)
+duplicate-circuit-name = { $name } (copia)
+
+unsaved-changes-title = Cambios sin guardar
+close-unsaved-message = Este circuito tiene cambios sin guardar. ¿Cerrarlo de todos modos?
+close-all-unsaved-message = Algunos circuitos tienen cambios sin guardar. ¿Cerrarlos de todos modos?
+quit-unsaved-message = Hay circuitos con cambios sin guardar. ¿Salir de todos modos?
+discard-action = Descartar cambios
+cancel-action = Cancelar
+
+open-error-title = No se pudo abrir el archivo
+save-error-title = No se pudo guardar el archivo
+export-error-title = No se pudo exportar el archivo
+import-error-title = No se pudo importar la netlist
+ok-action = Aceptar
+
+light-theme-name = Claro
+dark-theme-name = Oscuro
+system-theme-name = Sistema
+
+settings-title = Ajustes
+grid-header = Cuadrícula
+grid-style-setting-name = Estilo:
+grid-dots-style-name = Puntos
+grid-lines-style-name = Líneas
+grid-crosses-style-name = Cruces
+grid-minor-spacing-setting-name = Espaciado:
+grid-major-spacing-setting-name = Cuadrícula principal cada:
+grid-opacity-setting-name = Opacidad:
+simulation-header = Simulación
+max-steps-setting-name = Pasos máx.:
+
+import-netlist-title = Importar netlist
+import-netlist-message = Un componente por línea: tipo, ancho de bits opcional tras dos puntos y luego las redes de sus conexiones. Ejemplo: and:4 a b y
+import-action = Importar
+circuit-info-title = Información del circuito
+components-stat-name = Componentes:
+wire-segments-stat-name = Segmentos de cable:
+wire-length-stat-name = Longitud total de cable:
+nets-stat-name = Redes:
+
+ports-header = Puertos
+input-tool-tip = Puerto de entrada
+clock-input-tool-tip = Entrada de reloj
+push-button-tool-tip = Pulsador
+output-tool-tip = Puerto de salida
+port-tool-tip = Puerto bidireccional
+seven-segment-tool-tip = Display de siete segmentos
+led-tool-tip = LED
+constant-tool-tip = Constante
+
+logic-header = Lógica
+and-gate-tool-tip = Puerta AND
+nand-gate-tool-tip = Puerta NAND
+or-gate-tool-tip = Puerta OR
+nor-gate-tool-tip = Puerta NOR
+xor-gate-tool-tip = Puerta XOR
+xnor-gate-tool-tip = Puerta XNOR
+buffer-tool-tip = Búfer
+not-gate-tool-tip = Puerta NOT
+tri-state-buffer-tool-tip = Búfer triestado
+
+memory-header = Memoria
+d-flip-flop-tool-tip = Biestable D
+
+wiring-header = Cableado
+splitter-tool-tip = Divisor
+tunnel-tool-tip = Túnel
+
+paste-here-menu-item = Pegar aquí
+delete-menu-item = Eliminar
+rotate-cw-menu-item = Girar en sentido horario
+rotate-ccw-menu-item = Girar en sentido antihorario
+mirror-menu-item = Reflejar
+auto-route-menu-item = Enrutar automáticamente
+align-menu-item = Alinear
+align-left-menu-item = Alinear a la izquierda
+align-center-menu-item = Centrar horizontalmente
+align-right-menu-item = Alinear a la derecha
+align-top-menu-item = Alinear arriba
+align-middle-menu-item = Centrar verticalmente
+align-bottom-menu-item = Alinear abajo
+distribute-horizontally-menu-item = Distribuir horizontalmente
+distribute-vertically-menu-item = Distribuir verticalmente
+edit-properties-menu-item = Editar propiedades
+
+properties-header = Propiedades
+name-property-name = Nombre:
+bit-width-property-name = Ancho de bits:
+rotation-property-name = Rotación:
+mirrored-property-name = Reflejado
+input-count-property-name = Entradas:
+inverted-property-name = Salida invertida
+bit-ranges-property-name = Rangos de bits:
+value-property-name = Valor:
+driven-property-name = Excita la red
+radix-property-name = Base:
+binary-radix-name = Bin
+decimal-radix-name = Dec
+hexadecimal-radix-name = Hex
+reset-to-default-action = Restablecer valores predeterminados
+mixed-property-value = (mixto)
+
+probes-header = Sondas
+
+width-conflict-error = Hay componentes conectados con anchos de bits distintos
+unsupported-splitter-error = Los divisores aún no se pueden simular
+sim-unstable-warning = La simulación no se estabilizó dentro del máx. de pasos:
+
+zoom-fit-action = Ajustar
+cursor-position-status = X: { $x }  Y: { $y }
+sim-idle-status = Inactiva
+sim-running-status = En ejecución
+sim-conflict-status = Conflicto
+sim-unstable-status = Inestable
+sim-steps-status = Pasos: { $steps }
+selection-count-status = { $count } seleccionado(s)
+
+splitter-range-bounds-error = Los rangos deben estar dentro del ancho de bits
+splitter-range-overlap-error = Los rangos no deben solaparse
+splitter-range-width-error = Los rangos no deben superar el ancho de bits en total
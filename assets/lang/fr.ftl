@@ -0,0 +1,140 @@
+english-lang-name = French
+native-lang-name = Français
+
+file-menu-item = Fichier
+new-menu-item = Nouveau
+open-menu-item = Ouvrir
+import-netlist-menu-item = Importer une netlist
+open-recent-menu-item = Ouvrir un fichier récent
+save-menu-item = Enregistrer
+save-as-menu-item = Enregistrer sous
+save-all-menu-item = Tout enregistrer
+export-image-menu-item = Exporter l'image
+export-netlist-menu-item = Exporter la netlist
+circuit-info-menu-item = Informations du circuit
+duplicate-circuit-menu-item = Dupliquer le circuit
+close-all-menu-item = Tout fermer
+language-menu-item = Langue
+settings-menu-item = Paramètres
+json-file-filter = Circuit (JSON)
+binary-file-filter = Circuit (binaire)
+duplicate-circuit-name = { $name } (copie)
+
+unsaved-changes-title = Modifications non enregistrées
+close-unsaved-message = Ce circuit contient des modifications non enregistrées. Le fermer quand même ?
+close-all-unsaved-message = Certains circuits contiennent des modifications non enregistrées. Les fermer quand même ?
+quit-unsaved-message = Certains circuits contiennent des modifications non enregistrées. Quitter quand même ?
+discard-action = Abandonner les modifications
+cancel-action = Annuler
+
+open-error-title = Impossible d'ouvrir le fichier
+save-error-title = Impossible d'enregistrer le fichier
+export-error-title = Impossible d'exporter le fichier
+import-error-title = Impossible d'importer la netlist
+ok-action = OK
+
+light-theme-name = Clair
+dark-theme-name = Sombre
+system-theme-name = Système
+
+settings-title = Paramètres
+grid-header = Grille
+grid-style-setting-name = Style :
+grid-dots-style-name = Points
+grid-lines-style-name = Lignes
+grid-crosses-style-name = Croix
+grid-minor-spacing-setting-name = Espacement :
+grid-major-spacing-setting-name = Grille principale tous les :
+grid-opacity-setting-name = Opacité :
+simulation-header = Simulation
+max-steps-setting-name = Étapes max. :
+
+import-netlist-title = Importer une netlist
+import-netlist-message = Un composant par ligne : type, largeur en bits facultative après deux-points, puis les réseaux de ses connecteurs. Exemple : and:4 a b y
+import-action = Importer
+circuit-info-title = Informations du circuit
+components-stat-name = Composants :
+wire-segments-stat-name = Segments de fil :
+wire-length-stat-name = Longueur totale des fils :
+nets-stat-name = Réseaux :
+
+ports-header = Ports
+input-tool-tip = Port d'entrée
+clock-input-tool-tip = Entrée d'horloge
+push-button-tool-tip = Bouton-poussoir
+output-tool-tip = Port de sortie
+port-tool-tip = Port bidirectionnel
+seven-segment-tool-tip = Afficheur sept segments
+led-tool-tip = LED
+constant-tool-tip = Constante
+
+logic-header = Logique
+and-gate-tool-tip = Porte ET
+nand-gate-tool-tip = Porte NON-ET
+or-gate-tool-tip = Porte OU
+nor-gate-tool-tip = Porte NON-OU
+xor-gate-tool-tip = Porte OU exclusif
+xnor-gate-tool-tip = Porte NON-OU exclusif
+buffer-tool-tip = Tampon
+not-gate-tool-tip = Porte NON
+tri-state-buffer-tool-tip = Tampon trois états
+
+memory-header = Mémoire
+d-flip-flop-tool-tip = Bascule D
+
+wiring-header = Câblage
+splitter-tool-tip = Répartiteur
+tunnel-tool-tip = Tunnel
+
+paste-here-menu-item = Coller ici
+delete-menu-item = Supprimer
+rotate-cw-menu-item = Pivoter dans le sens horaire
+rotate-ccw-menu-item = Pivoter dans le sens antihoraire
+mirror-menu-item = Miroir
+auto-route-menu-item = Routage automatique
+align-menu-item = Aligner
+align-left-menu-item = Aligner à gauche
+align-center-menu-item = Centrer horizontalement
+align-right-menu-item = Aligner à droite
+align-top-menu-item = Aligner en haut
+align-middle-menu-item = Centrer verticalement
+align-bottom-menu-item = Aligner en bas
+distribute-horizontally-menu-item = Répartir horizontalement
+distribute-vertically-menu-item = Répartir verticalement
+edit-properties-menu-item = Modifier les propriétés
+
+properties-header = Propriétés
+name-property-name = Nom :
+bit-width-property-name = Largeur en bits :
+rotation-property-name = Rotation :
+mirrored-property-name = En miroir
+input-count-property-name = Entrées :
+inverted-property-name = Sortie inversée
+bit-ranges-property-name = Plages de bits :
+value-property-name = Valeur :
+driven-property-name = Pilote le réseau
+radix-property-name = Base :
+binary-radix-name = Bin
+decimal-radix-name = Déc
+hexadecimal-radix-name = Hex
+reset-to-default-action = Réinitialiser
+mixed-property-value = (mixte)
+
+probes-header = Sondes
+
+width-conflict-error = Des composants de largeurs en bits différentes sont connectés
+unsupported-splitter-error = Les répartiteurs ne peuvent pas encore être simulés
+sim-unstable-warning = La simulation ne s'est pas stabilisée dans le nombre max. d'étapes :
+
+zoom-fit-action = Ajuster
+cursor-position-status = X : { $x }  Y : { $y }
+sim-idle-status = Inactive
+sim-running-status = En cours
+sim-conflict-status = Conflit
+sim-unstable-status = Instable
+sim-steps-status = Étapes : { $steps }
+selection-count-status = { $count } sélectionné(s)
+
+splitter-range-bounds-error = Les plages doivent être comprises dans la largeur en bits
+splitter-range-overlap-error = Les plages ne doivent pas se chevaucher
+splitter-range-width-error = Les plages ne doivent pas dépasser la largeur en bits au total
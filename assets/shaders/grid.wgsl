@@ -1,38 +1,101 @@
 struct Globals {
-    color: vec4<f32>,
+    background_color: vec4<f32>,
+    minor_color: vec4<f32>,
+    major_color: vec4<f32>,
     resolution: vec2<f32>,
     offset: vec2<f32>,
     zoom: f32,
+    step: f32,
+    major_step: f32,
+    style: u32,
 };
 
 @group(0)
 @binding(0)
 var<uniform> globals: Globals;
 
-struct VertexInput {
-    @location(0) position: vec2<f32>,
-};
+const STYLE_DOTS: u32 = 0u;
+const STYLE_LINES: u32 = 1u;
+const STYLE_CROSSES: u32 = 2u;
 
-struct InstanceInput {
-    @location(1) offset: vec2<f32>,
-};
+// Sizes in pixels
+const MINOR_DOT_SIZE: f32 = 3.0;
+const MAJOR_DOT_SIZE: f32 = 5.0;
+const LINE_WIDTH: f32 = 1.0;
 
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
 };
 
 @vertex
-fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
-    let local_position = vertex.position + instance.offset;
-    let world_position = (local_position - globals.offset) * globals.zoom;
-    let clip_position = (world_position / globals.resolution) * vec2<f32>(2.0, 2.0);
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var positions = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, 1.0),
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(-1.0, 1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+    );
 
     var result: VertexOutput;
-    result.position = vec4<f32>(clip_position, 0.0, 1.0);
+    result.position = vec4<f32>(positions[vertex_index], 0.0, 1.0);
     return result;
 }
 
+// Distance from the nearest multiple of `step` in pixels, per axis
+fn grid_distance(world_position: vec2<f32>, step: f32) -> vec2<f32> {
+    let nearest = round(world_position / step) * step;
+    return abs(world_position - nearest) * globals.zoom;
+}
+
+// Antialiased coverage of a shape reaching `half_size` pixels from its center
+fn coverage(distance: vec2<f32>, half_size: vec2<f32>) -> f32 {
+    let c = clamp(half_size - distance + 0.5, vec2<f32>(0.0), vec2<f32>(1.0));
+    return c.x * c.y;
+}
+
+fn point_coverage(distance: vec2<f32>, size: f32) -> f32 {
+    if globals.style == STYLE_CROSSES {
+        let arm = size * 1.5;
+        let half_width = LINE_WIDTH * 0.5;
+        let horizontal = coverage(distance, vec2<f32>(arm, half_width));
+        let vertical = coverage(distance, vec2<f32>(half_width, arm));
+        return max(horizontal, vertical);
+    } else {
+        return coverage(distance, vec2<f32>(size * 0.5));
+    }
+}
+
+fn line_coverage(distance: vec2<f32>) -> f32 {
+    let c = clamp(LINE_WIDTH * 0.5 - distance + 0.5, vec2<f32>(0.0), vec2<f32>(1.0));
+    return max(c.x, c.y);
+}
+
 @fragment
-fn fs_main() -> @location(0) vec4<f32> {
-    return globals.color;
+fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    // Screen space has its origin in the top left corner and Y pointing down
+    let screen_position = vertex.position.xy - (globals.resolution * 0.5);
+    let world_position = globals.offset + (vec2<f32>(screen_position.x, -screen_position.y) / globals.zoom);
+
+    let minor_distance = grid_distance(world_position, globals.step);
+
+    var minor_coverage: f32;
+    var major_coverage = 0.0;
+    if globals.style == STYLE_LINES {
+        minor_coverage = line_coverage(minor_distance);
+        if globals.major_step > 0.0 {
+            major_coverage = line_coverage(grid_distance(world_position, globals.major_step));
+        }
+    } else {
+        minor_coverage = point_coverage(minor_distance, MINOR_DOT_SIZE);
+        if globals.major_step > 0.0 {
+            major_coverage = point_coverage(grid_distance(world_position, globals.major_step), MAJOR_DOT_SIZE);
+        }
+    }
+
+    var color = globals.background_color.rgb;
+    color = mix(color, globals.minor_color.rgb, globals.minor_color.a * minor_coverage);
+    color = mix(color, globals.major_color.rgb, globals.major_color.a * major_coverage);
+    return vec4<f32>(color, 1.0);
 }
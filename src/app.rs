@@ -3,10 +3,13 @@ use egui::*;
 use serde::{Deserialize, Serialize};
 use std::cell::OnceCell;
 use std::fmt::Display;
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 
 mod math;
 use math::*;
+pub use math::{Vec2f, Vec2i};
 
 #[macro_use]
 mod theme;
@@ -17,18 +20,117 @@ use locale::*;
 
 mod component;
 use component::*;
+pub use component::{Component, ComponentKind, Rotation};
 
 mod circuit;
 use circuit::*;
+pub use circuit::{Circuit, ImportNetlistError, LoadCircuitError, StartSimError, WireSegment};
 
 mod viewport;
+#[cfg(not(target_arch = "wasm32"))]
+pub use viewport::render_circuit_png;
 use viewport::*;
+pub use viewport::{Color, GridSettings, GridStyle, ViewportColors};
 
 mod file_dialog;
 use file_dialog::*;
 
-const DEFAULT_MAX_STEPS: u64 = 10_000;
+const DEFAULT_MAX_STEPS: NonZeroU64 = match NonZeroU64::new(10_000) {
+    Some(max_steps) => max_steps,
+    None => unreachable!(),
+};
+const DEFAULT_TICK_RATE: f64 = 10.0;
+const MIN_TICK_RATE: f64 = 0.1;
+const MAX_TICK_RATE: f64 = 1000.0;
+const MAX_TICKS_PER_FRAME: u32 = 16;
+const DEFAULT_SNAP_STEP: i32 = 1;
+const COARSE_SNAP_STEP: i32 = 2;
+const LARGE_MOVE_STEP: i32 = 10;
+const DUPLICATE_OFFSET: i32 = 2;
+const MOVE_REPEAT_DELAY: f64 = 0.4;
+const MOVE_REPEAT_INTERVAL: f64 = 0.05;
+const RENAME_TAB_ID: &str = "rename_tab";
+
+/// Number keys that add a component to the selected circuit, like clicking its palette button
+const PALETTE_HOTKEYS: [(Key, fn() -> ComponentKind); 9] = [
+    (Key::Num1, ComponentKind::new_input),
+    (Key::Num2, ComponentKind::new_output),
+    (Key::Num3, ComponentKind::new_and_gate),
+    (Key::Num4, ComponentKind::new_or_gate),
+    (Key::Num5, ComponentKind::new_xor_gate),
+    (Key::Num6, ComponentKind::new_nand_gate),
+    (Key::Num7, ComponentKind::new_nor_gate),
+    (Key::Num8, ComponentKind::new_xnor_gate),
+    (Key::Num9, ComponentKind::new_not_gate),
+];
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_RECENT_FILES: usize = 10;
+/// In screen pixels per second
+const KEYBOARD_PAN_SPEED: f32 = 600.0;
+const WAVEFORM_NAME_WIDTH: f32 = 100.0;
+const WAVEFORM_ROW_HEIGHT: f32 = 24.0;
+const WAVEFORM_SAMPLE_WIDTH: f32 = 12.0;
+const WAVEFORM_AXIS_STEP: usize = 10;
+const MINIMAP_SIZE: Vec2 = Vec2::new(200.0, 150.0);
+const MINIMAP_MARGIN: f32 = 8.0;
+
+/// Parses an integer that is either prefixed with `0x` or `0b`, or written in `default_radix`
+fn parse_radix_prefixed(text: &str, default_radix: u32) -> Option<i128> {
+    let text = text.trim().replace('_', "");
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.as_str()),
+    };
+
+    let (radix, digits) = if let Some(digits) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        (16, digits)
+    } else if let Some(digits) = digits
+        .strip_prefix("0b")
+        .or_else(|| digits.strip_prefix("0B"))
+    {
+        (2, digits)
+    } else {
+        (default_radix, digits)
+    };
+
+    // `from_str_radix` accepts a sign of its own
+    if digits.starts_with(['+', '-']) {
+        return None;
+    }
+
+    let value = i128::from_str_radix(digits, radix).ok()?;
+    Some(if negative { -value } else { value })
+}
+
+/// Parses a value typed into a numeric text field.
+/// Integers can also be entered in hexadecimal or binary using `0x` and `0b` prefixes.
+fn parse_numeric<T: FromStr>(text: &str) -> Option<T> {
+    text.trim()
+        .parse()
+        .ok()
+        .or_else(|| parse_radix_prefixed(text, 10)?.to_string().parse().ok())
+}
+
+/// The value a clamped numeric text field commits: the typed value clamped into `range`,
+/// or `previous` if the text can't be parsed
+fn clamped_numeric_value<T: FromStr + PartialOrd + Copy>(
+    text: &str,
+    previous: T,
+    range: &RangeInclusive<T>,
+) -> T {
+    match parse_numeric(text) {
+        Some(new_value) if new_value < *range.start() => *range.start(),
+        Some(new_value) if new_value > *range.end() => *range.end(),
+        Some(new_value) => new_value,
+        // Revert to the previous value
+        None => previous,
+    }
+}
 
+#[derive(Clone)]
 pub struct NumericTextValue<T: FromStr + Display> {
     buffer: String,
     value: T,
@@ -81,6 +183,14 @@ trait UiExt {
         &mut self,
         value: &mut NumericTextValue<T>,
     ) -> Response;
+
+    /// Like `numeric_text_edit`, but clamps the value into `range` when it is committed
+    /// and highlights input outside of it while typing
+    fn numeric_text_edit_clamped<T: FromStr + Display + PartialOrd + Copy>(
+        &mut self,
+        value: &mut NumericTextValue<T>,
+        range: RangeInclusive<T>,
+    ) -> Response;
 }
 
 impl UiExt for Ui {
@@ -96,7 +206,7 @@ impl UiExt for Ui {
 
         let response = self.text_edit_singleline(&mut value.buffer);
         if response.lost_focus() {
-            if let Ok(new_value) = value.buffer.parse() {
+            if let Some(new_value) = parse_numeric(&value.buffer) {
                 value.value = new_value;
             } else {
                 value.buffer.clear();
@@ -106,6 +216,196 @@ impl UiExt for Ui {
 
         response
     }
+
+    fn numeric_text_edit_clamped<T: FromStr + Display + PartialOrd + Copy>(
+        &mut self,
+        value: &mut NumericTextValue<T>,
+        range: RangeInclusive<T>,
+    ) -> Response {
+        let in_range =
+            parse_numeric(&value.buffer).is_some_and(|new_value| range.contains(&new_value));
+
+        let mut text_edit = TextEdit::singleline(&mut value.buffer);
+        if !in_range {
+            text_edit = text_edit.text_color(self.visuals().error_fg_color);
+        }
+
+        let response = self.add(text_edit);
+        if response.lost_focus() {
+            let new_value = clamped_numeric_value(&value.buffer, value.value, &range);
+            value.set(new_value);
+        }
+
+        response
+    }
+}
+
+/// Draws a scaled-down overview of the circuit with the visible area outlined.
+/// Returns the logical position the user clicked or dragged to, if any.
+fn minimap(ui: &mut Ui, circuit: &Circuit, viewport_size: Vec2) -> Option<Vec2f> {
+    let (rect, response) = ui.allocate_exact_size(MINIMAP_SIZE, Sense::click_and_drag());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+    painter.rect_stroke(rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+
+    let half_view_size = Vec2f::from(viewport_size) / (circuit.zoom() * BASE_ZOOM * 2.0);
+    let view = Rectangle::from_points(
+        circuit.offset() - half_view_size,
+        circuit.offset() + half_view_size,
+    );
+
+    // Always keep the visible area on the map so there is something to drag back
+    let bounds = circuit
+        .content_bounds()
+        .map_or(view, |bounds| bounds.union(&view));
+    let center = bounds.center();
+    let scale = (rect.width() / bounds.width()).min(rect.height() / bounds.height()) * 0.9;
+
+    let to_screen = |p: Vec2f| rect.center() + Vec2::new(p.x - center.x, center.y - p.y) * scale;
+    let to_screen_rect = |r: &Rectangle| {
+        Rect::from_two_pos(
+            to_screen(Vec2f::new(r.left, r.bottom)),
+            to_screen(Vec2f::new(r.right, r.top)),
+        )
+    };
+
+    let component_color = ui.visuals().text_color();
+    for component in circuit.components() {
+        painter.rect_filled(
+            to_screen_rect(&component.bounding_box()),
+            0.0,
+            component_color,
+        );
+    }
+
+    let wire_stroke = Stroke::new(1.0, ui.visuals().hyperlink_color);
+    for segment in circuit.wire_segments() {
+        let points = std::iter::once(segment.endpoint_a)
+            .chain(segment.midpoints.iter().copied())
+            .chain(std::iter::once(segment.endpoint_b))
+            .map(|p| to_screen(p.to_vec2f()))
+            .collect();
+        painter.add(Shape::line(points, wire_stroke));
+    }
+
+    painter.rect_stroke(to_screen_rect(&view), 0.0, ui.visuals().selection.stroke);
+
+    if response.clicked() || response.dragged() {
+        response.interact_pointer_pos().map(|pos| {
+            let delta = (pos - rect.center()) / scale;
+            Vec2f::new(center.x + delta.x, center.y - delta.y)
+        })
+    } else {
+        None
+    }
+}
+
+/// Draws the recorded signals as a timing diagram, one row per signal
+fn waveform_view(ui: &mut Ui, traces: &[SignalTrace], sample_count: usize) {
+    let first_sample = sample_count.saturating_sub(MAX_SIGNAL_SAMPLES);
+    let diagram_width = (sample_count - first_sample) as f32 * WAVEFORM_SAMPLE_WIDTH;
+    let stroke = ui.visuals().widgets.noninteractive.fg_stroke;
+    let font = FontId::monospace(10.0);
+
+    ScrollArea::both().stick_to_right(true).show(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.add_space(WAVEFORM_NAME_WIDTH + ui.spacing().item_spacing.x);
+            let (rect, _) = ui.allocate_exact_size(
+                vec2(diagram_width, WAVEFORM_ROW_HEIGHT * 0.5),
+                Sense::hover(),
+            );
+            let painter = ui.painter_at(rect);
+
+            let first_label = first_sample.next_multiple_of(WAVEFORM_AXIS_STEP);
+            for sample in (first_label..sample_count).step_by(WAVEFORM_AXIS_STEP) {
+                let x = rect.left() + ((sample - first_sample) as f32 * WAVEFORM_SAMPLE_WIDTH);
+                painter.text(
+                    pos2(x, rect.center().y),
+                    Align2::LEFT_CENTER,
+                    sample,
+                    font.clone(),
+                    ui.visuals().weak_text_color(),
+                );
+            }
+        });
+
+        for trace in traces {
+            ui.horizontal(|ui| {
+                ui.add_sized(
+                    [WAVEFORM_NAME_WIDTH, WAVEFORM_ROW_HEIGHT],
+                    Label::new(&trace.name).truncate(true),
+                );
+                let (rect, _) = ui
+                    .allocate_exact_size(vec2(diagram_width, WAVEFORM_ROW_HEIGHT), Sense::hover());
+                let painter = ui.painter_at(rect);
+
+                let high = rect.top() + 4.0;
+                let low = rect.bottom() - 4.0;
+                let mut prev_y = None;
+                let mut prev_text = None;
+
+                for (i, state) in trace.samples.iter().enumerate() {
+                    // Samples from before the visible range can't be drawn
+                    let Some(offset) = (trace.first_sample + i).checked_sub(first_sample) else {
+                        continue;
+                    };
+
+                    let x0 = rect.left() + (offset as f32 * WAVEFORM_SAMPLE_WIDTH);
+                    let x1 = x0 + WAVEFORM_SAMPLE_WIDTH;
+
+                    if trace.width.get() == 1 {
+                        let y = match state.get_bit_state(0) {
+                            gsim::LogicBitState::Logic0 => low,
+                            gsim::LogicBitState::Logic1 => high,
+                            gsim::LogicBitState::HighZ | gsim::LogicBitState::Undefined => {
+                                rect.center().y
+                            }
+                        };
+
+                        if let Some(prev_y) = prev_y.filter(|&prev_y| prev_y != y) {
+                            painter.line_segment([pos2(x0, prev_y), pos2(x0, y)], stroke);
+                        }
+                        painter.line_segment([pos2(x0, y), pos2(x1, y)], stroke);
+                        prev_y = Some(y);
+                    } else {
+                        let text = format_logic_state(state, trace.width, trace.radix);
+
+                        if prev_text.as_ref() != Some(&text) {
+                            painter.line_segment([pos2(x0, high), pos2(x0, low)], stroke);
+                            painter.text(
+                                pos2(x0 + 2.0, rect.center().y),
+                                Align2::LEFT_CENTER,
+                                &text,
+                                font.clone(),
+                                ui.visuals().text_color(),
+                            );
+                        }
+                        painter.line_segment([pos2(x0, high), pos2(x1, high)], stroke);
+                        painter.line_segment([pos2(x0, low), pos2(x1, low)], stroke);
+                        prev_text = Some(text);
+                    }
+                }
+            });
+        }
+    });
+}
+
+/// Combines the arrow keys matching `query` into a direction
+fn arrow_key_dir(input: &InputState, query: fn(&InputState, Key) -> bool) -> Vec2i {
+    let mut dir = Vec2i::ZERO;
+    if query(input, Key::ArrowUp) {
+        dir.y += 1;
+    }
+    if query(input, Key::ArrowDown) {
+        dir.y -= 1;
+    }
+    if query(input, Key::ArrowLeft) {
+        dir.x -= 1;
+    }
+    if query(input, Key::ArrowRight) {
+        dir.x += 1;
+    }
+    dir
 }
 
 #[derive(Serialize, Deserialize)]
@@ -113,7 +413,17 @@ impl UiExt for Ui {
 struct AppState {
     theme: Theme,
     lang: LangId,
-    max_steps: u64,
+    /// Upper bound on simulation steps per update before the circuit is considered unstable
+    max_steps: NumericTextValue<NonZeroU64>,
+    /// Clock ticks per second while the simulation is free-running
+    tick_rate: f64,
+    /// Grid units that dragged and nudged selections move by
+    snap_step: i32,
+    grid: GridSettings,
+    show_minimap: bool,
+    /// Most recently opened or saved files, newest first
+    #[cfg(not(target_arch = "wasm32"))]
+    recent_files: Vec<std::path::PathBuf>,
 }
 
 impl Default for AppState {
@@ -121,7 +431,42 @@ impl Default for AppState {
         Self {
             theme: Theme::default(),
             lang: DEFAULT_LANG,
-            max_steps: DEFAULT_MAX_STEPS,
+            max_steps: NumericTextValue::new(DEFAULT_MAX_STEPS),
+            tick_rate: DEFAULT_TICK_RATE,
+            snap_step: DEFAULT_SNAP_STEP,
+            grid: GridSettings::default(),
+            show_minimap: true,
+            #[cfg(not(target_arch = "wasm32"))]
+            recent_files: Vec::new(),
+        }
+    }
+}
+
+impl AppState {
+    #[inline]
+    fn max_steps(&self) -> u64 {
+        self.max_steps.get().get()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn add_recent_file(&mut self, path: &std::path::Path) {
+        self.recent_files.retain(|recent| recent != path);
+        self.recent_files.insert(0, path.to_owned());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+}
+
+/// An error that is shown to the user until dismissed
+struct ErrorDialog {
+    title_key: &'static str,
+    message: String,
+}
+
+impl ErrorDialog {
+    fn new(title_key: &'static str, message: impl Display) -> Self {
+        Self {
+            title_key,
+            message: message.to_string(),
         }
     }
 }
@@ -129,7 +474,8 @@ impl Default for AppState {
 pub struct App {
     state: AppState,
     locale_manager: LocaleManager,
-    next_visuals: Option<Visuals>,
+    /// The concrete light or dark theme currently applied
+    resolved_theme: Theme,
     file_dialog: OnceCell<FileDialog>,
 
     theme_image: &'static ThemedImage,
@@ -143,11 +489,38 @@ pub struct App {
     buffer_image: &'static ThemedImage,
 
     viewport: Option<Viewport>,
+    clipboard: Option<ClipboardData>,
+    start_sim_error: Option<(usize, StartSimError)>,
+    sim_running: bool,
+    next_tick_time: f64,
+    next_move_repeat_time: f64,
+    input_editor_pos: Pos2,
+    /// Component currently being dragged from the palette into the viewport
+    palette_drag: Option<fn() -> ComponentKind>,
+    /// Grid point under the mouse cursor in the viewport
+    cursor_grid_pos: Option<Vec2i>,
+    dragged_tab: Option<usize>,
+    /// Tab whose name is being edited, and the name entered so far
+    renamed_tab: Option<(usize, String)>,
+    pending_close: Option<usize>,
+    show_quit_dialog: bool,
+    show_close_all_dialog: bool,
+    show_settings: bool,
+    show_circuit_info: bool,
+    /// Text in the netlist import window, `None` if the window is closed
+    netlist_import_text: Option<String>,
+    error_dialog: Option<ErrorDialog>,
+    allow_quit: bool,
 
     circuits: Vec<Circuit>,
     selected_circuit: Option<usize>,
     drag_mode: DragMode,
+    /// Redraws everything in the viewport, at most once per frame
     requires_redraw: bool,
+    /// Only redraws the selection box on top of the last frame
+    requires_overlay_redraw: bool,
+    /// The view was panned, zoomed or resized, but the circuit itself is unchanged
+    view_changed: bool,
 }
 
 impl App {
@@ -157,17 +530,15 @@ impl App {
             .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
             .unwrap_or_default();
 
-        match state.theme {
-            Theme::Light => cc.egui_ctx.set_visuals(Visuals::light()),
-            Theme::Dark => cc.egui_ctx.set_visuals(Visuals::dark()),
-        }
+        let resolved_theme = state.theme.resolve(cc.integration_info.system_theme);
+        cc.egui_ctx.set_visuals(resolved_theme.visuals());
 
         egui_extras::install_image_loaders(&cc.egui_ctx);
 
         Self {
             state,
             locale_manager: LocaleManager::init(),
-            next_visuals: None,
+            resolved_theme,
             file_dialog: OnceCell::new(),
 
             theme_image: themed_image!(SwitchTheme.svg),
@@ -181,374 +552,1244 @@ impl App {
             buffer_image: themed_image!(Buffer.svg),
 
             viewport: None,
+            clipboard: None,
+            start_sim_error: None,
+            sim_running: false,
+            next_tick_time: 0.0,
+            next_move_repeat_time: 0.0,
+            input_editor_pos: Pos2::ZERO,
+            palette_drag: None,
+            cursor_grid_pos: None,
+            dragged_tab: None,
+            renamed_tab: None,
+            pending_close: None,
+            show_quit_dialog: false,
+            show_close_all_dialog: false,
+            show_settings: false,
+            show_circuit_info: false,
+            netlist_import_text: None,
+            error_dialog: None,
+            allow_quit: false,
 
             circuits: vec![],
             selected_circuit: None,
             drag_mode: DragMode::default(),
             requires_redraw: true,
+            requires_overlay_redraw: false,
+            view_changed: false,
         }
     }
-}
 
-impl eframe::App for App {
-    fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, &self.state);
-    }
+    fn close_circuit(&mut self, index: usize) {
+        self.circuits.remove(index);
 
-    fn update(&mut self, ctx: &Context, frame: &mut eframe::Frame) {
-        if let Some(visuals) = self.next_visuals.take() {
-            ctx.set_visuals(visuals);
-            self.requires_redraw = true;
+        if self.selected_circuit == Some(index) {
+            self.sim_running = false;
         }
 
-        let Some(file_dialog) = self.file_dialog.get_mut() else {
-            if let Some(file_dialog) = FileDialog::new() {
-                let _ = self.file_dialog.set(file_dialog);
+        self.selected_circuit = match self.selected_circuit {
+            Some(selected) if selected == index => {
+                (!self.circuits.is_empty()).then(|| index.min(self.circuits.len() - 1))
             }
+            Some(selected) if selected > index => Some(selected - 1),
+            selected => selected,
+        };
+
+        self.start_sim_error = match self.start_sim_error.take() {
+            Some((i, _)) if i == index => None,
+            Some((i, err)) if i > index => Some((i - 1, err)),
+            error => error,
+        };
+
+        self.renamed_tab = match self.renamed_tab.take() {
+            Some((i, _)) if i == index => None,
+            Some((i, name)) if i > index => Some((i - 1, name)),
+            renamed_tab => renamed_tab,
+        };
+
+        self.requires_redraw = true;
+    }
+
+    fn close_all_circuits(&mut self) {
+        self.circuits.clear();
+        self.selected_circuit = None;
+        self.pending_close = None;
+        self.start_sim_error = None;
+        self.renamed_tab = None;
+        self.sim_running = false;
+        self.requires_redraw = true;
+    }
+
+    /// Writes a circuit to its file, asking for a path first if it has none or `save_as` is set.
+    /// The format is chosen by the extension of the file.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_circuit(&mut self, index: usize, save_as: bool) {
+        let Some(file_dialog) = self.file_dialog.get() else {
             return;
         };
 
-        #[cfg(not(target_arch = "wasm32"))]
-        if let Some((file_name, data)) = file_dialog.get() {
-            let mut circuit = Circuit::deserialize(&data).expect("error opening file");
-            circuit.set_file_name(file_name);
+        let circuit = &mut self.circuits[index];
+
+        let pick_save_path = |file_name: Option<&std::path::Path>| {
+            let json_filter = self
+                .locale_manager
+                .get(&self.state.lang, "json-file-filter");
+            let binary_filter = self
+                .locale_manager
+                .get(&self.state.lang, "binary-file-filter");
+
+            file_dialog.pick_save_path(
+                file_name,
+                &[
+                    (&json_filter, &[JSON_EXTENSION]),
+                    (&binary_filter, &[BINARY_EXTENSION]),
+                ],
+            )
+        };
 
-            self.selected_circuit = Some(self.circuits.len());
-            self.circuits.push(circuit);
-            self.requires_redraw = true;
+        let path = if save_as {
+            pick_save_path(circuit.file_name())
+        } else {
+            circuit.file_name().map(ToOwned::to_owned).or_else(|| {
+                let default_name =
+                    std::path::PathBuf::from(format!("{}.{JSON_EXTENSION}", circuit.name()));
+                pick_save_path(Some(&default_name))
+            })
+        };
+
+        let Some(path) = path else {
+            return;
+        };
+
+        match std::fs::write(&path, circuit.serialize_for_path(&path)) {
+            Ok(()) => {
+                self.state.add_recent_file(&path);
+                circuit.set_file_name(path);
+                circuit.mark_saved();
+            }
+            Err(err) => {
+                self.error_dialog = Some(ErrorDialog::new("save-error-title", err));
+            }
         }
+    }
 
-        #[cfg(target_arch = "wasm32")]
-        if let Some(data) = file_dialog.get() {
-            let circuit = Circuit::deserialize(&data).expect("error opening file");
+    fn move_circuit(&mut self, from: usize, to: usize) {
+        let circuit = self.circuits.remove(from);
+        self.circuits.insert(to, circuit);
+
+        let remap = |i: usize| {
+            if i == from {
+                to
+            } else if (from < to) && (i > from) && (i <= to) {
+                i - 1
+            } else if (to < from) && (i >= to) && (i < from) {
+                i + 1
+            } else {
+                i
+            }
+        };
 
-            self.selected_circuit = Some(self.circuits.len());
-            self.circuits.push(circuit);
-            self.requires_redraw = true;
+        self.selected_circuit = self.selected_circuit.map(remap);
+        self.pending_close = self.pending_close.map(remap);
+        if let Some((i, _)) = &mut self.start_sim_error {
+            *i = remap(*i);
+        }
+        if let Some((i, _)) = &mut self.renamed_tab {
+            *i = remap(*i);
         }
+    }
 
-        TopBottomPanel::top("main_menu").show(ctx, |ui| {
-            menu::bar(ui, |ui| {
-                ui.menu_button(
-                    self.locale_manager.get(&self.state.lang, "file-menu-item"),
-                    |ui| {
-                        if ui
-                            .button(self.locale_manager.get(&self.state.lang, "new-menu-item"))
-                            .clicked()
-                        {
-                            self.selected_circuit = Some(self.circuits.len());
-                            self.circuits.push(Circuit::new());
-                            self.requires_redraw = true;
-                        }
+    /// Clicking a palette button adds the component to the selected circuit,
+    /// dragging it places the component where it is dropped in the viewport
+    fn palette_button(&mut self, response: Response, new_kind: fn() -> ComponentKind) {
+        if response.clicked() {
+            if let Some(selected_circuit) = self.selected_circuit {
+                self.circuits[selected_circuit].add_component(new_kind());
+                self.requires_redraw = true;
+            }
+        }
 
-                        if ui
-                            .button(self.locale_manager.get(&self.state.lang, "open-menu-item"))
-                            .clicked()
-                        {
-                            file_dialog.open();
-                        }
+        if response.interact(Sense::drag()).drag_started() {
+            self.palette_drag = Some(new_kind);
+        }
 
-                        if let Some(circuit) = self.selected_circuit.map(|i| &mut self.circuits[i])
-                        {
-                            #[cfg(not(target_arch = "wasm32"))]
-                            {
-                                if ui
-                                    .button(
-                                        self.locale_manager.get(&self.state.lang, "save-menu-item"),
-                                    )
-                                    .clicked()
-                                {
-                                    if let Some(file_name) = circuit.file_name() {
-                                        std::fs::write(file_name, Circuit::serialize(circuit))
-                                            .expect("error saving file");
-                                        circuit.set_file_name(file_name.to_owned());
-                                    } else if let Some(file_name) = file_dialog
-                                        .save(None, &Circuit::serialize(circuit))
-                                        .expect("error saving file")
-                                    {
-                                        circuit.set_file_name(file_name);
-                                    }
-                                }
+        // Show the hotkey in the corner of the button
+        let kind = std::mem::discriminant(&new_kind());
+        let hotkey = PALETTE_HOTKEYS
+            .iter()
+            .position(|(_, hotkey_kind)| std::mem::discriminant(&hotkey_kind()) == kind);
+        if let Some(hotkey) = hotkey {
+            let color = response.ctx.style().visuals.weak_text_color();
+            response.ctx.layer_painter(response.layer_id).text(
+                response.rect.right_bottom() - Vec2::splat(2.0),
+                Align2::RIGHT_BOTTOM,
+                (hotkey + 1).to_string(),
+                FontId::proportional(10.0),
+                color,
+            );
+        }
+    }
 
-                                if ui
-                                    .button(
-                                        self.locale_manager
-                                            .get(&self.state.lang, "save-as-menu-item"),
-                                    )
-                                    .clicked()
-                                {
-                                    if let Some(file_name) = file_dialog
-                                        .save(circuit.file_name(), &Circuit::serialize(circuit))
-                                        .expect("error saving file")
-                                    {
-                                        circuit.set_file_name(file_name);
-                                    }
-                                }
-                            }
+    fn run_simulation_ticks(&mut self, ctx: &Context) {
+        let Some(circuit) = self.selected_circuit.map(|i| &mut self.circuits[i]) else {
+            self.sim_running = false;
+            return;
+        };
 
-                            #[cfg(target_arch = "wasm32")]
-                            {
-                                if ui
-                                    .button(
-                                        self.locale_manager.get(&self.state.lang, "save-menu-item"),
-                                    )
-                                    .clicked()
-                                {
-                                    file_dialog.save(circuit.name(), &Circuit::serialize(circuit));
-                                }
-                            }
-                        }
-                    },
-                );
+        let now = ctx.input(|state| state.time);
+        let tick_period = 1.0 / self.state.tick_rate.clamp(MIN_TICK_RATE, MAX_TICK_RATE);
 
-                ui.menu_button(
-                    self.locale_manager
-                        .get(&self.state.lang, "language-menu-item"),
-                    |ui| {
-                        for lang in self.locale_manager.langs() {
-                            let english_name = self.locale_manager.get(lang, "english-lang-name");
-                            let native_name = self.locale_manager.get(lang, "native-lang-name");
+        let mut ticks = 0;
+        while (self.next_tick_time <= now) && (ticks < MAX_TICKS_PER_FRAME) {
+            if !is_discriminant!(circuit.sim_state(), SimState::Active) {
+                self.sim_running = false;
+                return;
+            }
+
+            circuit.step_simulation(self.state.max_steps());
+            self.next_tick_time += tick_period;
+            self.requires_redraw = true;
+            ticks += 1;
+        }
+
+        // Drop ticks instead of trying to catch up if the simulation is too slow
+        if self.next_tick_time < now {
+            self.next_tick_time = now + tick_period;
+        }
+
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(
+            self.next_tick_time - now,
+        ));
+    }
+
+    fn settings_window(&mut self, ctx: &Context) {
+        let mut keep_open = true;
+        let prev_grid = self.state.grid;
 
+        Window::new(self.locale_manager.get(&self.state.lang, "settings-title"))
+            .open(&mut keep_open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.strong(self.locale_manager.get(&self.state.lang, "grid-header"));
+
+                Grid::new("grid_settings").num_columns(2).show(ui, |ui| {
+                    ui.label(
+                        self.locale_manager
+                            .get(&self.state.lang, "grid-style-setting-name"),
+                    );
+                    ui.horizontal(|ui| {
+                        for (style, key) in [
+                            (GridStyle::Dots, "grid-dots-style-name"),
+                            (GridStyle::Lines, "grid-lines-style-name"),
+                            (GridStyle::Crosses, "grid-crosses-style-name"),
+                        ] {
                             ui.radio_value(
-                                &mut self.state.lang,
-                                lang.clone(),
-                                format!("{native_name} ({english_name})"),
+                                &mut self.state.grid.style,
+                                style,
+                                self.locale_manager.get(&self.state.lang, key),
                             );
                         }
-                    },
-                );
-            });
-        });
-
-        TopBottomPanel::top("tool_bar").show(ctx, |ui| {
-            menu::bar(ui, |ui| {
-                let selected_circuit = self.selected_circuit.map(|i| &mut self.circuits[i]);
+                    });
+                    ui.end_row();
 
-                if let Some(selected_circuit) = selected_circuit {
-                    // TODO: use icon buttons
+                    ui.label(
+                        self.locale_manager
+                            .get(&self.state.lang, "grid-minor-spacing-setting-name"),
+                    );
+                    ui.add(
+                        DragValue::new(&mut self.state.grid.minor_spacing)
+                            .clamp_range(MIN_GRID_SPACING_UNITS..=MAX_GRID_SPACING_UNITS),
+                    );
+                    ui.end_row();
 
-                    if !is_discriminant!(selected_circuit.sim_state(), SimState::None) {
-                        if ui.button("stop sim").clicked() {
-                            selected_circuit.stop_simulation();
-                            self.requires_redraw = true;
-                        }
-                    } else if ui.button("start sim").clicked() {
-                        // TODO: display error
-                        let _result = selected_circuit.start_simulation(self.state.max_steps);
-                        self.requires_redraw = true;
-                    }
+                    ui.label(
+                        self.locale_manager
+                            .get(&self.state.lang, "grid-major-spacing-setting-name"),
+                    );
+                    ui.add(
+                        DragValue::new(&mut self.state.grid.major_spacing)
+                            .clamp_range(0..=MAX_GRID_SPACING_UNITS),
+                    );
+                    ui.end_row();
 
-                    if ui
-                        .add_enabled(
-                            is_discriminant!(selected_circuit.sim_state(), SimState::Active),
-                            Button::new("step sim"),
-                        )
-                        .clicked()
-                    {
-                        // TODO: display error
-                        let _result = selected_circuit.step_simulation(self.state.max_steps);
-                        self.requires_redraw = true;
-                    }
+                    ui.label(
+                        self.locale_manager
+                            .get(&self.state.lang, "grid-opacity-setting-name"),
+                    );
+                    ui.add(Slider::new(&mut self.state.grid.opacity, 0.0..=1.0));
+                    ui.end_row();
+                });
 
-                    // TODO: free-run simulation
+                if ui
+                    .button(
+                        self.locale_manager
+                            .get(&self.state.lang, "reset-to-default-action"),
+                    )
+                    .clicked()
+                {
+                    self.state.grid = GridSettings::default();
                 }
 
-                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                    let target_theme_name = match self.state.theme {
-                        Theme::Light => {
-                            self.locale_manager.get(&self.state.lang, "dark-theme-name")
-                        }
-                        Theme::Dark => self
-                            .locale_manager
-                            .get(&self.state.lang, "light-theme-name"),
-                    };
+                ui.separator();
+                ui.strong(
+                    self.locale_manager
+                        .get(&self.state.lang, "simulation-header"),
+                );
+
+                ui.horizontal(|ui| {
+                    ui.label(
+                        self.locale_manager
+                            .get(&self.state.lang, "max-steps-setting-name"),
+                    );
+                    ui.numeric_text_edit(&mut self.state.max_steps);
 
                     if ui
-                        .themed_image_button(&self.theme_image, self.state.theme)
-                        .on_hover_text(target_theme_name)
+                        .button(
+                            self.locale_manager
+                                .get(&self.state.lang, "reset-to-default-action"),
+                        )
                         .clicked()
                     {
-                        match self.state.theme {
-                            Theme::Light => {
-                                self.state.theme = Theme::Dark;
-                                self.next_visuals = Some(Visuals::dark());
-                            }
-                            Theme::Dark => {
-                                self.state.theme = Theme::Light;
-                                self.next_visuals = Some(Visuals::light());
-                            }
-                        }
+                        self.state.max_steps.set(DEFAULT_MAX_STEPS);
                     }
                 });
             });
-        });
 
-        SidePanel::left("component_picker").show(ctx, |ui| {
-            ui.set_enabled(self.selected_circuit.is_some());
+        self.show_settings = keep_open;
+        self.requires_redraw |= self.state.grid != prev_grid;
+    }
 
-            ui.horizontal(|ui| {
-                // TODO: use icon buttons
-                ui.radio_value(&mut self.drag_mode, DragMode::BoxSelection, "Select");
-                ui.radio_value(&mut self.drag_mode, DragMode::DrawWire, "Draw Wires");
+    fn circuit_info_window(&mut self, ctx: &Context) {
+        let Some(circuit) = self.selected_circuit.map(|i| &self.circuits[i]) else {
+            self.show_circuit_info = false;
+            return;
+        };
+
+        let stats = circuit.stats();
+        let mut keep_open = true;
+
+        Window::new(
+            self.locale_manager
+                .get(&self.state.lang, "circuit-info-title"),
+        )
+        .open(&mut keep_open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.strong(circuit.name());
+
+            Grid::new("circuit_info").num_columns(2).show(ui, |ui| {
+                for (key, value) in [
+                    ("components-stat-name", stats.component_count as u64),
+                    ("wire-segments-stat-name", stats.wire_segment_count as u64),
+                    ("wire-length-stat-name", stats.wire_length),
+                    ("nets-stat-name", stats.net_count as u64),
+                ] {
+                    ui.label(self.locale_manager.get(&self.state.lang, key));
+                    ui.label(value.to_string());
+                    ui.end_row();
+                }
             });
 
-            ui.heading(self.locale_manager.get(&self.state.lang, "ports-header"));
+            if !stats.component_counts.is_empty() {
+                ui.separator();
+
+                Grid::new("circuit_info_components")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for (key, count) in &stats.component_counts {
+                            ui.label(self.locale_manager.get(&self.state.lang, key));
+                            ui.label(count.to_string());
+                            ui.end_row();
+                        }
+                    });
+            }
+        });
+
+        self.show_circuit_info = keep_open;
+    }
+
+    fn netlist_import_window(&mut self, ctx: &Context) {
+        let Some(text) = &mut self.netlist_import_text else {
+            return;
+        };
+
+        let mut keep_open = true;
+        let mut import = false;
+        let mut cancel = false;
+
+        Window::new(
+            self.locale_manager
+                .get(&self.state.lang, "import-netlist-title"),
+        )
+        .open(&mut keep_open)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.label(
+                self.locale_manager
+                    .get(&self.state.lang, "import-netlist-message"),
+            );
+
+            ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                ui.add(
+                    TextEdit::multiline(text)
+                        .code_editor()
+                        .desired_rows(12)
+                        .desired_width(f32::INFINITY),
+                );
+            });
 
             ui.horizontal(|ui| {
                 if ui
-                    .themed_image_button(&self.and_gate_image, self.state.theme)
-                    .on_hover_text(self.locale_manager.get(&self.state.lang, "input-tool-tip"))
+                    .button(self.locale_manager.get(&self.state.lang, "import-action"))
                     .clicked()
                 {
-                    if let Some(selected_circuit) = self.selected_circuit {
-                        self.circuits[selected_circuit].add_component(ComponentKind::new_input());
-                        self.requires_redraw = true;
-                    }
+                    import = true;
                 }
 
                 if ui
-                    .themed_image_button(&self.nand_gate_image, self.state.theme)
-                    .on_hover_text(self.locale_manager.get(&self.state.lang, "output-tool-tip"))
+                    .button(self.locale_manager.get(&self.state.lang, "cancel-action"))
                     .clicked()
                 {
-                    if let Some(selected_circuit) = self.selected_circuit {
-                        self.circuits[selected_circuit].add_component(ComponentKind::new_output());
-                        self.requires_redraw = true;
+                    cancel = true;
+                }
+            });
+        });
+
+        keep_open &= !cancel;
+
+        if import {
+            match Circuit::import_netlist(text) {
+                Ok(circuit) => {
+                    self.selected_circuit = Some(self.circuits.len());
+                    self.sim_running = false;
+                    self.circuits.push(circuit);
+                    self.requires_redraw = true;
+                    keep_open = false;
+                }
+                Err(err) => {
+                    self.error_dialog = Some(ErrorDialog::new("import-error-title", err));
+                }
+            }
+        }
+
+        if !keep_open {
+            self.netlist_import_text = None;
+        }
+    }
+}
+
+impl eframe::App for App {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.state);
+    }
+
+    fn on_close_event(&mut self) -> bool {
+        if self.allow_quit || !self.circuits.iter().any(Circuit::is_modified) {
+            true
+        } else {
+            self.show_quit_dialog = true;
+            false
+        }
+    }
+
+    fn update(&mut self, ctx: &Context, frame: &mut eframe::Frame) {
+        // The OS theme may change at any time while following it, and eframe switches the
+        // visuals to the OS theme on its own even if a theme was picked explicitly
+        let resolved_theme = self.state.theme.resolve(frame.info().system_theme);
+        let dark_mode = resolved_theme == Theme::Dark;
+        if (resolved_theme != self.resolved_theme) || (ctx.style().visuals.dark_mode != dark_mode) {
+            self.resolved_theme = resolved_theme;
+            ctx.set_visuals(resolved_theme.visuals());
+            self.requires_redraw = true;
+        }
+
+        let Some(file_dialog) = self.file_dialog.get_mut() else {
+            if let Some(file_dialog) = FileDialog::new() {
+                let _ = self.file_dialog.set(file_dialog);
+            }
+            return;
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        for (file_name, data) in file_dialog.get() {
+            match Circuit::load(&data) {
+                Ok(mut circuit) => {
+                    self.state.add_recent_file(&file_name);
+                    circuit.set_file_name(file_name);
+
+                    self.selected_circuit = Some(self.circuits.len());
+                    self.sim_running = false;
+                    self.circuits.push(circuit);
+                    self.requires_redraw = true;
+                }
+                Err(err) => {
+                    self.error_dialog = Some(ErrorDialog::new(
+                        "open-error-title",
+                        format!("{}: {err}", file_name.display()),
+                    ));
+                }
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if let Some(data) = file_dialog.get() {
+            match Circuit::load(&data) {
+                Ok(circuit) => {
+                    self.selected_circuit = Some(self.circuits.len());
+                    self.sim_running = false;
+                    self.circuits.push(circuit);
+                    self.requires_redraw = true;
+                }
+                Err(err) => {
+                    self.error_dialog = Some(ErrorDialog::new("open-error-title", err));
+                }
+            }
+        }
+
+        if self.sim_running {
+            self.run_simulation_ticks(ctx);
+        }
+
+        if let Some(pending_close) = self.pending_close {
+            let mut keep_open = true;
+            let mut discard = false;
+
+            Window::new(
+                self.locale_manager
+                    .get(&self.state.lang, "unsaved-changes-title"),
+            )
+            .open(&mut keep_open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{}: {}",
+                    self.circuits[pending_close].name(),
+                    self.locale_manager
+                        .get(&self.state.lang, "close-unsaved-message")
+                ));
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(self.locale_manager.get(&self.state.lang, "discard-action"))
+                        .clicked()
+                    {
+                        discard = true;
+                    }
+
+                    if ui
+                        .button(self.locale_manager.get(&self.state.lang, "cancel-action"))
+                        .clicked()
+                    {
+                        self.pending_close = None;
+                    }
+                });
+            });
+
+            if discard {
+                self.pending_close = None;
+                self.close_circuit(pending_close);
+            } else if !keep_open {
+                self.pending_close = None;
+            }
+        }
+
+        if self.show_quit_dialog {
+            let mut keep_open = true;
+
+            Window::new(
+                self.locale_manager
+                    .get(&self.state.lang, "unsaved-changes-title"),
+            )
+            .open(&mut keep_open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(
+                    self.locale_manager
+                        .get(&self.state.lang, "quit-unsaved-message"),
+                );
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(self.locale_manager.get(&self.state.lang, "discard-action"))
+                        .clicked()
+                    {
+                        self.allow_quit = true;
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        frame.close();
                     }
+
+                    if ui
+                        .button(self.locale_manager.get(&self.state.lang, "cancel-action"))
+                        .clicked()
+                    {
+                        self.show_quit_dialog = false;
+                    }
+                });
+            });
+
+            self.show_quit_dialog &= keep_open;
+        }
+
+        if self.show_close_all_dialog {
+            let mut keep_open = true;
+            let mut discard = false;
+
+            Window::new(
+                self.locale_manager
+                    .get(&self.state.lang, "unsaved-changes-title"),
+            )
+            .open(&mut keep_open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(
+                    self.locale_manager
+                        .get(&self.state.lang, "close-all-unsaved-message"),
+                );
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(self.locale_manager.get(&self.state.lang, "discard-action"))
+                        .clicked()
+                    {
+                        discard = true;
+                    }
+
+                    if ui
+                        .button(self.locale_manager.get(&self.state.lang, "cancel-action"))
+                        .clicked()
+                    {
+                        self.show_close_all_dialog = false;
+                    }
+                });
+            });
+
+            if discard {
+                self.show_close_all_dialog = false;
+                self.close_all_circuits();
+            } else {
+                self.show_close_all_dialog &= keep_open;
+            }
+        }
+
+        if let Some(error_dialog) = &self.error_dialog {
+            let mut keep_open = true;
+            let mut dismissed = false;
+
+            Window::new(
+                self.locale_manager
+                    .get(&self.state.lang, error_dialog.title_key),
+            )
+            .open(&mut keep_open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(&error_dialog.message);
+
+                if ui
+                    .button(self.locale_manager.get(&self.state.lang, "ok-action"))
+                    .clicked()
+                {
+                    dismissed = true;
                 }
             });
 
-            ui.horizontal(|ui| {
+            if dismissed || !keep_open {
+                self.error_dialog = None;
+            }
+        }
+
+        if self.show_settings {
+            self.settings_window(ctx);
+        }
+
+        if self.show_circuit_info {
+            self.circuit_info_window(ctx);
+        }
+
+        self.netlist_import_window(ctx);
+
+        // These need the whole app, so they happen after the menu has been drawn
+        #[cfg(not(target_arch = "wasm32"))]
+        let mut save_requests = Vec::new();
+        #[cfg(not(target_arch = "wasm32"))]
+        let mut save_all = false;
+        let mut close_all = false;
+
+        TopBottomPanel::top("main_menu").show(ctx, |ui| {
+            menu::bar(ui, |ui| {
+                ui.menu_button(
+                    self.locale_manager.get(&self.state.lang, "file-menu-item"),
+                    |ui| {
+                        if ui
+                            .button(self.locale_manager.get(&self.state.lang, "new-menu-item"))
+                            .clicked()
+                        {
+                            self.selected_circuit = Some(self.circuits.len());
+                            self.sim_running = false;
+                            self.circuits.push(Circuit::new());
+                            self.requires_redraw = true;
+                        }
+
+                        if ui
+                            .button(self.locale_manager.get(&self.state.lang, "open-menu-item"))
+                            .clicked()
+                        {
+                            file_dialog.open();
+                        }
+
+                        if ui
+                            .button(
+                                self.locale_manager
+                                    .get(&self.state.lang, "import-netlist-menu-item"),
+                            )
+                            .clicked()
+                        {
+                            self.netlist_import_text.get_or_insert_with(String::new);
+                            ui.close_menu();
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        {
+                            self.state.recent_files.retain(|path| path.exists());
+
+                            ui.add_enabled_ui(!self.state.recent_files.is_empty(), |ui| {
+                                ui.menu_button(
+                                    self.locale_manager
+                                        .get(&self.state.lang, "open-recent-menu-item"),
+                                    |ui| {
+                                        for path in &self.state.recent_files {
+                                            if ui.button(path.display().to_string()).clicked() {
+                                                file_dialog.open_path(path.clone());
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    },
+                                );
+                            });
+                        }
+
+                        if let Some(selected_circuit) = self.selected_circuit {
+                            let circuit = &mut self.circuits[selected_circuit];
+
+                            #[cfg(not(target_arch = "wasm32"))]
+                            {
+                                if ui
+                                    .button(
+                                        self.locale_manager.get(&self.state.lang, "save-menu-item"),
+                                    )
+                                    .clicked()
+                                {
+                                    save_requests.push((selected_circuit, false));
+                                }
+
+                                if ui
+                                    .button(
+                                        self.locale_manager
+                                            .get(&self.state.lang, "save-as-menu-item"),
+                                    )
+                                    .clicked()
+                                {
+                                    save_requests.push((selected_circuit, true));
+                                }
+
+                                if ui
+                                    .button(
+                                        self.locale_manager
+                                            .get(&self.state.lang, "save-all-menu-item"),
+                                    )
+                                    .clicked()
+                                {
+                                    save_all = true;
+                                }
+
+                                if ui
+                                    .button(
+                                        self.locale_manager
+                                            .get(&self.state.lang, "export-image-menu-item"),
+                                    )
+                                    .clicked()
+                                {
+                                    if let (Some(viewport), Some(render_state)) =
+                                        (&self.viewport, frame.wgpu_render_state())
+                                    {
+                                        let image_data = viewport.capture_png(render_state);
+                                        let file_name = std::path::PathBuf::from(format!(
+                                            "{}.png",
+                                            circuit.name()
+                                        ));
+                                        if let Err(err) =
+                                            file_dialog.save(Some(file_name.as_path()), &image_data)
+                                        {
+                                            self.error_dialog =
+                                                Some(ErrorDialog::new("export-error-title", err));
+                                        }
+                                    }
+                                }
+
+                                if ui
+                                    .button(
+                                        self.locale_manager
+                                            .get(&self.state.lang, "export-netlist-menu-item"),
+                                    )
+                                    .clicked()
+                                {
+                                    match circuit.export_netlist() {
+                                        Ok(netlist) => {
+                                            let file_name = std::path::PathBuf::from(format!(
+                                                "{}.netlist.json",
+                                                circuit.name()
+                                            ));
+                                            if let Err(err) = file_dialog
+                                                .save(Some(file_name.as_path()), netlist.as_bytes())
+                                            {
+                                                self.error_dialog = Some(ErrorDialog::new(
+                                                    "export-error-title",
+                                                    err,
+                                                ));
+                                            }
+                                        }
+                                        Err(err) => {
+                                            self.start_sim_error = self
+                                                .selected_circuit
+                                                .map(|selected_circuit| (selected_circuit, err));
+                                            self.requires_redraw = true;
+                                        }
+                                    }
+                                }
+                            }
+
+                            #[cfg(target_arch = "wasm32")]
+                            {
+                                if ui
+                                    .button(
+                                        self.locale_manager.get(&self.state.lang, "save-menu-item"),
+                                    )
+                                    .clicked()
+                                {
+                                    file_dialog.save(circuit.name(), &Circuit::serialize(circuit));
+                                    circuit.mark_saved();
+                                }
+                            }
+
+                            if ui
+                                .button(
+                                    self.locale_manager
+                                        .get(&self.state.lang, "circuit-info-menu-item"),
+                                )
+                                .clicked()
+                            {
+                                self.show_circuit_info = true;
+                                ui.close_menu();
+                            }
+
+                            if ui
+                                .button(
+                                    self.locale_manager
+                                        .get(&self.state.lang, "duplicate-circuit-menu-item"),
+                                )
+                                .clicked()
+                            {
+                                let mut args = FluentArgs::new();
+                                args.set("name", self.circuits[selected_circuit].name());
+                                let name = self.locale_manager.get_args(
+                                    &self.state.lang,
+                                    "duplicate-circuit-name",
+                                    &args,
+                                );
+
+                                let duplicate =
+                                    self.circuits[selected_circuit].duplicate(name.into_owned());
+                                self.selected_circuit = Some(self.circuits.len());
+                                self.sim_running = false;
+                                self.circuits.push(duplicate);
+                                self.requires_redraw = true;
+                                ui.close_menu();
+                            }
+
+                            if ui
+                                .button(
+                                    self.locale_manager
+                                        .get(&self.state.lang, "close-all-menu-item"),
+                                )
+                                .clicked()
+                            {
+                                close_all = true;
+                                ui.close_menu();
+                            }
+                        }
+                    },
+                );
+
+                ui.menu_button(
+                    self.locale_manager
+                        .get(&self.state.lang, "language-menu-item"),
+                    |ui| {
+                        for lang in self.locale_manager.langs() {
+                            let english_name = self.locale_manager.get(lang, "english-lang-name");
+                            let native_name = self.locale_manager.get(lang, "native-lang-name");
+
+                            ui.radio_value(
+                                &mut self.state.lang,
+                                lang.clone(),
+                                format!("{native_name} ({english_name})"),
+                            );
+                        }
+                    },
+                );
+
                 if ui
-                    .themed_image_button(&self.and_gate_image, self.state.theme)
-                    .on_hover_text(
+                    .button(
                         self.locale_manager
-                            .get(&self.state.lang, "clock-input-tool-tip"),
+                            .get(&self.state.lang, "settings-menu-item"),
                     )
                     .clicked()
                 {
-                    if let Some(selected_circuit) = self.selected_circuit {
-                        self.circuits[selected_circuit]
-                            .add_component(ComponentKind::new_clock_input());
+                    self.show_settings = true;
+                }
+            });
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if save_all {
+                save_requests.extend(
+                    (0..self.circuits.len())
+                        .filter(|&i| self.circuits[i].is_modified())
+                        .map(|i| (i, false)),
+                );
+            }
+
+            for (index, save_as) in save_requests {
+                self.save_circuit(index, save_as);
+            }
+        }
+
+        if close_all {
+            if self.circuits.iter().any(Circuit::is_modified) {
+                self.show_close_all_dialog = true;
+            } else {
+                self.close_all_circuits();
+            }
+        }
+
+        TopBottomPanel::top("tool_bar").show(ctx, |ui| {
+            menu::bar(ui, |ui| {
+                if let Some(selected_circuit_index) = self.selected_circuit {
+                    let selected_circuit = &mut self.circuits[selected_circuit_index];
+
+                    // TODO: use icon buttons
+
+                    if ui.button("fit view").clicked() {
+                        if let Some(viewport) = &self.viewport {
+                            self.view_changed |= selected_circuit.fit_to_view(viewport.size());
+                        }
+                    }
+
+                    let mut coarse_snap = self.state.snap_step == COARSE_SNAP_STEP;
+                    if ui.toggle_value(&mut coarse_snap, "coarse snap").changed() {
+                        self.state.snap_step = if coarse_snap {
+                            COARSE_SNAP_STEP
+                        } else {
+                            DEFAULT_SNAP_STEP
+                        };
+                    }
+
+                    ui.toggle_value(&mut self.state.show_minimap, "minimap");
+
+                    if !is_discriminant!(selected_circuit.sim_state(), SimState::None) {
+                        if ui.button("stop sim").clicked() {
+                            selected_circuit.stop_simulation();
+                            self.sim_running = false;
+                            self.requires_redraw = true;
+                        }
+                    } else if ui.button("start sim").clicked() {
+                        self.start_sim_error = selected_circuit
+                            .start_simulation(self.state.max_steps())
+                            .err()
+                            .map(|err| (selected_circuit_index, err));
                         self.requires_redraw = true;
                     }
+
+                    if ui
+                        .add_enabled(
+                            is_discriminant!(selected_circuit.sim_state(), SimState::Active)
+                                || is_discriminant!(
+                                    selected_circuit.sim_state(),
+                                    SimState::Unstable
+                                ),
+                            Button::new("step sim"),
+                        )
+                        .clicked()
+                    {
+                        selected_circuit.step_simulation(self.state.max_steps());
+                        self.requires_redraw = true;
+                    }
+
+                    let run_label = if self.sim_running {
+                        "pause sim"
+                    } else {
+                        "run sim"
+                    };
+
+                    if ui
+                        .add_enabled(
+                            is_discriminant!(selected_circuit.sim_state(), SimState::Active),
+                            Button::new(run_label),
+                        )
+                        .clicked()
+                    {
+                        self.sim_running = !self.sim_running;
+                        self.next_tick_time = ui.input(|state| state.time);
+                    }
+
+                    ui.add(
+                        DragValue::new(&mut self.state.tick_rate)
+                            .clamp_range(MIN_TICK_RATE..=MAX_TICK_RATE)
+                            .speed(0.1)
+                            .suffix(" Hz"),
+                    );
+
+                    if let Some((i, err)) = &self.start_sim_error {
+                        if *i == selected_circuit_index {
+                            let message_key = match err {
+                                StartSimError::WidthConflict { .. } => "width-conflict-error",
+                                StartSimError::UnsupportedSplitter => "unsupported-splitter-error",
+                            };
+
+                            ui.colored_label(
+                                ui.visuals().error_fg_color,
+                                format!(
+                                    "⚠ {}",
+                                    self.locale_manager.get(&self.state.lang, message_key)
+                                ),
+                            );
+                        }
+                    }
+
+                    if let SimState::Unstable { max_steps, .. } = selected_circuit.sim_state() {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!(
+                                "⚠ {} {max_steps}",
+                                self.locale_manager
+                                    .get(&self.state.lang, "sim-unstable-warning")
+                            ),
+                        );
+                    }
                 }
 
-                // TODO: bidirectional port
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    let target_theme = match self.state.theme {
+                        Theme::Light => Theme::Dark,
+                        Theme::Dark => Theme::System,
+                        Theme::System => Theme::Light,
+                    };
+                    let target_theme_name = match target_theme {
+                        Theme::Light => self
+                            .locale_manager
+                            .get(&self.state.lang, "light-theme-name"),
+                        Theme::Dark => self.locale_manager.get(&self.state.lang, "dark-theme-name"),
+                        Theme::System => self
+                            .locale_manager
+                            .get(&self.state.lang, "system-theme-name"),
+                    };
+
+                    if ui
+                        .themed_image_button(&self.theme_image, self.resolved_theme)
+                        .on_hover_text(target_theme_name)
+                        .clicked()
+                    {
+                        // The new visuals are applied at the start of the next frame
+                        self.state.theme = target_theme;
+                        ui.ctx().request_repaint();
+                    }
+                });
+            });
+        });
+
+        SidePanel::left("component_picker").show(ctx, |ui| {
+            ui.set_enabled(self.selected_circuit.is_some());
+
+            ui.horizontal(|ui| {
+                // TODO: use icon buttons
+                ui.radio_value(&mut self.drag_mode, DragMode::BoxSelection, "Select");
+                ui.radio_value(&mut self.drag_mode, DragMode::DrawWire, "Draw Wires");
+            });
+
+            ui.heading(self.locale_manager.get(&self.state.lang, "ports-header"));
+
+            ui.horizontal(|ui| {
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
+                    .on_hover_text(self.locale_manager.get(&self.state.lang, "input-tool-tip"));
+                self.palette_button(response, ComponentKind::new_input);
+
+                let response = ui
+                    .themed_image_button(&self.nand_gate_image, self.resolved_theme)
+                    .on_hover_text(self.locale_manager.get(&self.state.lang, "output-tool-tip"));
+                self.palette_button(response, ComponentKind::new_output);
+            });
+
+            ui.horizontal(|ui| {
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
+                    .on_hover_text(
+                        self.locale_manager
+                            .get(&self.state.lang, "clock-input-tool-tip"),
+                    );
+                self.palette_button(response, ComponentKind::new_clock_input);
+
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
+                    .on_hover_text(
+                        self.locale_manager
+                            .get(&self.state.lang, "push-button-tool-tip"),
+                    );
+                self.palette_button(response, ComponentKind::new_push_button);
+
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
+                    .on_hover_text(
+                        self.locale_manager
+                            .get(&self.state.lang, "constant-tool-tip"),
+                    );
+                self.palette_button(response, ComponentKind::new_constant);
+
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
+                    .on_hover_text(self.locale_manager.get(&self.state.lang, "led-tool-tip"));
+                self.palette_button(response, ComponentKind::new_led);
+
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
+                    .on_hover_text(
+                        self.locale_manager
+                            .get(&self.state.lang, "seven-segment-tool-tip"),
+                    );
+                self.palette_button(response, ComponentKind::new_seven_segment);
+
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
+                    .on_hover_text(self.locale_manager.get(&self.state.lang, "port-tool-tip"));
+                self.palette_button(response, ComponentKind::new_port);
             });
 
             ui.heading(self.locale_manager.get(&self.state.lang, "logic-header"));
 
             ui.horizontal(|ui| {
-                if ui
-                    .themed_image_button(&self.and_gate_image, self.state.theme)
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
                     .on_hover_text(
                         self.locale_manager
                             .get(&self.state.lang, "and-gate-tool-tip"),
-                    )
-                    .clicked()
-                {
-                    if let Some(selected_circuit) = self.selected_circuit {
-                        self.circuits[selected_circuit]
-                            .add_component(ComponentKind::new_and_gate());
-                        self.requires_redraw = true;
-                    }
-                }
+                    );
+                self.palette_button(response, ComponentKind::new_and_gate);
 
-                if ui
-                    .themed_image_button(&self.nand_gate_image, self.state.theme)
+                let response = ui
+                    .themed_image_button(&self.nand_gate_image, self.resolved_theme)
                     .on_hover_text(
                         self.locale_manager
                             .get(&self.state.lang, "nand-gate-tool-tip"),
-                    )
-                    .clicked()
-                {
-                    if let Some(selected_circuit) = self.selected_circuit {
-                        self.circuits[selected_circuit]
-                            .add_component(ComponentKind::new_nand_gate());
-                        self.requires_redraw = true;
-                    }
-                }
+                    );
+                self.palette_button(response, ComponentKind::new_nand_gate);
             });
 
             ui.horizontal(|ui| {
-                if ui
-                    .themed_image_button(&self.or_gate_image, self.state.theme)
+                let response = ui
+                    .themed_image_button(&self.or_gate_image, self.resolved_theme)
                     .on_hover_text(
                         self.locale_manager
                             .get(&self.state.lang, "or-gate-tool-tip"),
-                    )
-                    .clicked()
-                {
-                    if let Some(selected_circuit) = self.selected_circuit {
-                        self.circuits[selected_circuit].add_component(ComponentKind::new_or_gate());
-                        self.requires_redraw = true;
-                    }
-                }
+                    );
+                self.palette_button(response, ComponentKind::new_or_gate);
 
-                if ui
-                    .themed_image_button(&self.nor_gate_image, self.state.theme)
+                let response = ui
+                    .themed_image_button(&self.nor_gate_image, self.resolved_theme)
                     .on_hover_text(
                         self.locale_manager
                             .get(&self.state.lang, "nor-gate-tool-tip"),
-                    )
-                    .clicked()
-                {
-                    if let Some(selected_circuit) = self.selected_circuit {
-                        self.circuits[selected_circuit]
-                            .add_component(ComponentKind::new_nor_gate());
-                        self.requires_redraw = true;
-                    }
-                }
+                    );
+                self.palette_button(response, ComponentKind::new_nor_gate);
+            });
+
+            ui.horizontal(|ui| {
+                let response = ui
+                    .themed_image_button(&self.xor_gate_image, self.resolved_theme)
+                    .on_hover_text(
+                        self.locale_manager
+                            .get(&self.state.lang, "xor-gate-tool-tip"),
+                    );
+                self.palette_button(response, ComponentKind::new_xor_gate);
+
+                let response = ui
+                    .themed_image_button(&self.xnor_gate_image, self.resolved_theme)
+                    .on_hover_text(
+                        self.locale_manager
+                            .get(&self.state.lang, "xnor-gate-tool-tip"),
+                    );
+                self.palette_button(response, ComponentKind::new_xnor_gate);
             });
 
             ui.horizontal(|ui| {
-                if ui
-                    .themed_image_button(&self.xor_gate_image, self.state.theme)
+                let response = ui
+                    .themed_image_button(&self.buffer_image, self.resolved_theme)
+                    .on_hover_text(self.locale_manager.get(&self.state.lang, "buffer-tool-tip"));
+                self.palette_button(response, ComponentKind::new_buffer);
+
+                let response = ui
+                    .themed_image_button(&self.not_gate_image, self.resolved_theme)
                     .on_hover_text(
                         self.locale_manager
-                            .get(&self.state.lang, "xor-gate-tool-tip"),
-                    )
-                    .clicked()
-                {
-                    if let Some(selected_circuit) = self.selected_circuit {
-                        self.circuits[selected_circuit]
-                            .add_component(ComponentKind::new_xor_gate());
-                        self.requires_redraw = true;
-                    }
-                }
+                            .get(&self.state.lang, "not-gate-tool-tip"),
+                    );
+                self.palette_button(response, ComponentKind::new_not_gate);
 
-                if ui
-                    .themed_image_button(&self.xnor_gate_image, self.state.theme)
+                let response = ui
+                    .themed_image_button(&self.buffer_image, self.resolved_theme)
                     .on_hover_text(
                         self.locale_manager
-                            .get(&self.state.lang, "xnor-gate-tool-tip"),
-                    )
-                    .clicked()
-                {
-                    if let Some(selected_circuit) = self.selected_circuit {
-                        self.circuits[selected_circuit]
-                            .add_component(ComponentKind::new_xnor_gate());
-                        self.requires_redraw = true;
-                    }
-                }
+                            .get(&self.state.lang, "tri-state-buffer-tool-tip"),
+                    );
+                self.palette_button(response, ComponentKind::new_tri_state_buffer);
             });
 
+            ui.heading(self.locale_manager.get(&self.state.lang, "memory-header"));
+
             ui.horizontal(|ui| {
-                if ui
-                    .themed_image_button(&self.buffer_image, self.state.theme)
-                    .on_hover_text(self.locale_manager.get(&self.state.lang, "buffer-tool-tip"))
-                    .clicked()
-                {}
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
+                    .on_hover_text(
+                        self.locale_manager
+                            .get(&self.state.lang, "d-flip-flop-tool-tip"),
+                    );
+                self.palette_button(response, ComponentKind::new_d_flip_flop);
+            });
 
-                if ui
-                    .themed_image_button(&self.not_gate_image, self.state.theme)
+            ui.heading(self.locale_manager.get(&self.state.lang, "wiring-header"));
+
+            ui.horizontal(|ui| {
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
                     .on_hover_text(
                         self.locale_manager
-                            .get(&self.state.lang, "not-gate-tool-tip"),
-                    )
-                    .clicked()
-                {}
+                            .get(&self.state.lang, "splitter-tool-tip"),
+                    );
+                self.palette_button(response, ComponentKind::new_splitter);
+
+                let response = ui
+                    .themed_image_button(&self.and_gate_image, self.resolved_theme)
+                    .on_hover_text(self.locale_manager.get(&self.state.lang, "tunnel-tool-tip"));
+                self.palette_button(response, ComponentKind::new_tunnel);
             });
         });
 
@@ -556,6 +1797,28 @@ impl eframe::App for App {
             if let Some(selected_circuit) = self.selected_circuit {
                 self.requires_redraw |= self.circuits[selected_circuit]
                     .update_component_properties(ui, &self.locale_manager, &self.state.lang);
+
+                let probe_values = self.circuits[selected_circuit].probe_values();
+                if !probe_values.is_empty() {
+                    ui.separator();
+                    CollapsingHeader::new(
+                        self.locale_manager.get(&self.state.lang, "probes-header"),
+                    )
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        Grid::new("probe_values").striped(true).show(ui, |ui| {
+                            for probe in probe_values {
+                                ui.label(probe.name);
+                                ui.monospace(format_logic_state(
+                                    &probe.state,
+                                    probe.width,
+                                    probe.radix,
+                                ));
+                                ui.end_row();
+                            }
+                        });
+                    });
+                }
             }
 
             ui.with_layout(Layout::bottom_up(Align::RIGHT), |ui| {
@@ -564,29 +1827,197 @@ impl eframe::App for App {
         });
 
         TopBottomPanel::top("tab_headers").show(ctx, |ui| {
-            for (i, circuit) in self.circuits.iter().enumerate() {
-                let mut selected = self.selected_circuit.map(|sc| i == sc).unwrap_or(false);
+            ui.horizontal(|ui| {
+                let mut closed_tab = None;
+                let mut hovered_tab = None;
+                let mut finished_rename = None;
+
+                for (i, circuit) in self.circuits.iter().enumerate() {
+                    if let Some((_, name)) = self
+                        .renamed_tab
+                        .as_mut()
+                        .filter(|(renamed_tab, _)| *renamed_tab == i)
+                    {
+                        let response = ui.add(
+                            TextEdit::singleline(name)
+                                .id(Id::new(RENAME_TAB_ID))
+                                .desired_width(120.0),
+                        );
+
+                        if response.lost_focus() {
+                            let cancelled = ui.input(|state| state.key_pressed(Key::Escape));
+                            finished_rename = Some((i, !cancelled));
+                        }
+
+                        continue;
+                    }
 
-                ui.toggle_value(&mut selected, circuit.name());
+                    let mut selected = self.selected_circuit.map(|sc| i == sc).unwrap_or(false);
 
-                if selected {
-                    let old_selected = self.selected_circuit;
-                    self.selected_circuit = Some(i);
-                    self.requires_redraw |= self.selected_circuit != old_selected;
+                    let title = if circuit.is_modified() {
+                        format!("{}*", circuit.name())
+                    } else {
+                        circuit.name().to_owned()
+                    };
+
+                    let response = ui
+                        .toggle_value(&mut selected, title)
+                        .interact(Sense::drag());
+
+                    if response.drag_started() {
+                        self.dragged_tab = Some(i);
+                    }
+
+                    if response.double_clicked() {
+                        self.renamed_tab = Some((i, circuit.name().to_owned()));
+                        ui.memory_mut(|memory| memory.request_focus(Id::new(RENAME_TAB_ID)));
+                    }
+
+                    if ui.rect_contains_pointer(response.rect) {
+                        hovered_tab = Some(i);
+                    }
+
+                    if ui.small_button("🗙").clicked() {
+                        closed_tab = Some(i);
+                    }
+
+                    if selected {
+                        let old_selected = self.selected_circuit;
+                        self.selected_circuit = Some(i);
+                        if self.selected_circuit != old_selected {
+                            // Only the selected circuit is ticked
+                            self.sim_running = false;
+                            self.requires_redraw = true;
+                        }
+                    }
                 }
-            }
+
+                if let Some(dragged_tab) = self.dragged_tab {
+                    if !ui.input(|state| state.pointer.primary_down()) {
+                        self.dragged_tab = None;
+                    } else if let Some(hovered_tab) =
+                        hovered_tab.filter(|&hovered_tab| hovered_tab != dragged_tab)
+                    {
+                        self.move_circuit(dragged_tab, hovered_tab);
+                        self.dragged_tab = Some(hovered_tab);
+                    }
+                }
+
+                if let Some((renamed_tab, accept)) = finished_rename {
+                    if let Some((_, name)) = self.renamed_tab.take() {
+                        let name = name.trim();
+                        if accept && !name.is_empty() {
+                            self.circuits[renamed_tab].set_name(name.to_owned());
+                        }
+                    }
+                }
+
+                if let Some(closed_tab) = closed_tab {
+                    if self.circuits[closed_tab].is_modified() {
+                        self.pending_close = Some(closed_tab);
+                    } else {
+                        self.close_circuit(closed_tab);
+                    }
+                }
+            });
         });
 
         TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                let zoom = self
-                    .selected_circuit
-                    .map(|i| self.circuits[i].zoom())
-                    .unwrap_or(DEFAULT_ZOOM);
-                ui.label(format!("{:.0}%", zoom * 100.0));
+                if let Some(circuit) = self.selected_circuit.map(|i| &mut self.circuits[i]) {
+                    let mut zoom_percent = circuit.zoom() * 100.0;
+                    if ui
+                        .add(
+                            DragValue::new(&mut zoom_percent)
+                                .clamp_range((MIN_ZOOM * 100.0)..=(MAX_ZOOM * 100.0))
+                                .max_decimals(0)
+                                .suffix("%"),
+                        )
+                        .changed()
+                    {
+                        self.view_changed |= circuit.set_zoom(zoom_percent / 100.0);
+                    }
+
+                    for preset in [0.5, 1.0, 2.0] {
+                        if ui.small_button(format!("{:.0}%", preset * 100.0)).clicked() {
+                            self.view_changed |= circuit.set_zoom(preset);
+                        }
+                    }
+
+                    if ui
+                        .small_button(self.locale_manager.get(&self.state.lang, "zoom-fit-action"))
+                        .clicked()
+                    {
+                        if let Some(viewport) = &self.viewport {
+                            self.view_changed |= circuit.fit_to_view(viewport.size());
+                        }
+                    }
+                } else {
+                    ui.label(format!("{:.0}%", DEFAULT_ZOOM * 100.0));
+                }
+
+                if let Some(cursor_grid_pos) = self.cursor_grid_pos {
+                    let mut args = FluentArgs::new();
+                    args.set("x", cursor_grid_pos.x);
+                    args.set("y", cursor_grid_pos.y);
+
+                    ui.separator();
+                    ui.label(self.locale_manager.get_args(
+                        &self.state.lang,
+                        "cursor-position-status",
+                        &args,
+                    ));
+                }
+
+                if let Some(circuit) = self.selected_circuit.map(|i| &self.circuits[i]) {
+                    ui.separator();
+
+                    let status_key = match circuit.sim_state() {
+                        SimState::None => "sim-idle-status",
+                        SimState::Active { .. } => "sim-running-status",
+                        SimState::Conflict { .. } => "sim-conflict-status",
+                        SimState::Unstable { .. } => "sim-unstable-status",
+                    };
+                    ui.label(self.locale_manager.get(&self.state.lang, status_key));
+
+                    if !is_discriminant!(circuit.sim_state(), SimState::None) {
+                        let mut args = FluentArgs::new();
+                        args.set("steps", circuit.last_step_count());
+
+                        ui.separator();
+                        ui.label(self.locale_manager.get_args(
+                            &self.state.lang,
+                            "sim-steps-status",
+                            &args,
+                        ));
+                    }
+
+                    let selected_count = circuit.selection().item_count();
+                    if selected_count > 0 {
+                        let mut args = FluentArgs::new();
+                        args.set("count", selected_count);
+
+                        ui.separator();
+                        ui.label(self.locale_manager.get_args(
+                            &self.state.lang,
+                            "selection-count-status",
+                            &args,
+                        ));
+                    }
+                }
             });
         });
 
+        if let Some(circuit) = self.selected_circuit.map(|i| &self.circuits[i]) {
+            if !circuit.signal_traces().is_empty() {
+                TopBottomPanel::bottom("waveform_view")
+                    .resizable(true)
+                    .show(ctx, |ui| {
+                        waveform_view(ui, circuit.signal_traces(), circuit.sample_count());
+                    });
+            }
+        }
+
         CentralPanel::default().show(ctx, |ui| {
             let render_state = frame.wgpu_render_state().unwrap();
 
@@ -595,8 +2026,7 @@ impl eframe::App for App {
             let viewport_height = viewport_size.y.max(1.0) as u32;
 
             let viewport = if let Some(viewport) = self.viewport.as_mut() {
-                self.requires_redraw |=
-                    viewport.resize(render_state, viewport_width, viewport_height);
+                self.view_changed |= viewport.resize(render_state, viewport_width, viewport_height);
                 viewport
             } else {
                 let viewport = Viewport::create(render_state, viewport_width, viewport_height);
@@ -612,80 +2042,196 @@ impl eframe::App for App {
             .sense(Sense::click_and_drag())
             .ui(ui);
 
+            // The status bar is laid out before the viewport,
+            // so it has to be repainted to pick up a new cursor position
+            let prev_cursor_grid_pos = self.cursor_grid_pos.take();
+
             let selected_circuit = self.selected_circuit.map(|i| &mut self.circuits[i]);
             if let Some(circuit) = selected_circuit {
                 let viewport_rect = response.rect;
 
                 if let Some(pos) = response.interact_pointer_pos() {
                     if viewport_rect.contains(pos) {
-                        let mut rel_pos = pos - viewport_rect.min;
-                        rel_pos.y = viewport_rect.height() - rel_pos.y;
-                        rel_pos -= response.rect.size() * 0.5;
+                        let logical_pos = circuit.screen_to_logical(pos, viewport_rect);
 
                         if ui.input(|state| state.pointer.button_pressed(PointerButton::Primary)) {
+                            // Editing may invalidate the wire segment indices of the error
+                            self.requires_redraw |= self.start_sim_error.take().is_some();
                             self.requires_redraw |= circuit.primary_button_pressed(
-                                rel_pos.into(),
+                                logical_pos,
                                 self.drag_mode,
-                                self.state.max_steps,
+                                ui.input(|state| state.modifiers.shift),
+                                self.state.max_steps(),
                             );
+
+                            if circuit.edited_input().is_some() {
+                                self.input_editor_pos = pos;
+                            }
                         } else if ui
                             .input(|state| state.pointer.button_pressed(PointerButton::Secondary))
                         {
-                            self.requires_redraw |=
-                                circuit.secondary_button_pressed(rel_pos.into());
+                            self.requires_redraw |= circuit.secondary_button_pressed(logical_pos);
                         }
                     }
                 }
 
-                if ui.input(|state| state.key_pressed(Key::Delete)) {
-                    circuit.delete_selection();
-                    self.requires_redraw = true;
-                }
+                // Shortcuts must not fire while a text field in the properties panel has focus
+                if !ui.ctx().wants_keyboard_input() {
+                    if ui.input(|state| state.modifiers.command && state.key_pressed(Key::Z)) {
+                        self.requires_redraw |= self.start_sim_error.take().is_some();
 
-                if ui.input(|state| state.key_pressed(Key::R)) {
-                    if ui.input(|state| state.modifiers.shift) {
-                        circuit.clockwise_rotate_selection();
-                    } else {
-                        circuit.counterclockwise_rotate_selection();
+                        if ui.input(|state| state.modifiers.shift) {
+                            self.requires_redraw |= circuit.redo();
+                        } else {
+                            self.requires_redraw |= circuit.undo();
+                        }
                     }
 
-                    self.requires_redraw = true;
-                }
+                    if ui.input(|state| state.modifiers.command && state.key_pressed(Key::C)) {
+                        if let Some(data) = circuit.copy_selection() {
+                            self.clipboard = Some(data);
+                        }
+                    }
 
-                if ui.input(|state| state.key_pressed(Key::M)) {
-                    circuit.mirror_selection();
-                    self.requires_redraw = true;
-                }
+                    if ui.input(|state| state.modifiers.command && state.key_pressed(Key::V)) {
+                        if let Some(data) = &self.clipboard {
+                            let paste_pos = response
+                                .hover_pos()
+                                .filter(|&pos| viewport_rect.contains(pos))
+                                .map(|pos| circuit.screen_to_logical(pos, viewport_rect))
+                                .unwrap_or(circuit.offset());
 
-                if ui.input(|state| state.key_pressed(Key::ArrowUp)) {
-                    circuit.move_selection(Vec2i::new(0, 1));
-                    self.requires_redraw = true;
-                }
+                            circuit.paste(data, paste_pos.round().to_vec2i());
+                            self.requires_redraw = true;
+                        }
+                    }
 
-                if ui.input(|state| state.key_pressed(Key::ArrowDown)) {
-                    circuit.move_selection(Vec2i::new(0, -1));
-                    self.requires_redraw = true;
-                }
+                    if ui.input(|state| state.modifiers.command && state.key_pressed(Key::A)) {
+                        circuit.select_all();
+                        self.requires_redraw = true;
+                    }
 
-                if ui.input(|state| state.key_pressed(Key::ArrowLeft)) {
-                    circuit.move_selection(Vec2i::new(-1, 0));
-                    self.requires_redraw = true;
-                }
+                    if ui.input(|state| state.key_pressed(Key::Escape)) {
+                        circuit.clear_selection();
+                        self.requires_redraw = true;
+                    }
 
-                if ui.input(|state| state.key_pressed(Key::ArrowRight)) {
-                    circuit.move_selection(Vec2i::new(1, 0));
-                    self.requires_redraw = true;
+                    if ui.input(|state| state.modifiers.command && state.key_pressed(Key::D)) {
+                        circuit
+                            .duplicate_selection(Vec2i::new(DUPLICATE_OFFSET, -DUPLICATE_OFFSET));
+                        self.requires_redraw = true;
+                    }
+
+                    if ui.input(|state| {
+                        state.key_pressed(Key::Delete) || state.key_pressed(Key::Backspace)
+                    }) {
+                        self.start_sim_error = None;
+                        circuit.delete_selection();
+                        self.requires_redraw = true;
+                    }
+
+                    if ui.input(|state| state.key_pressed(Key::R)) {
+                        if ui.input(|state| state.modifiers.shift) {
+                            circuit.clockwise_rotate_selection();
+                        } else {
+                            circuit.rotate_selection();
+                        }
+
+                        self.requires_redraw = true;
+                    }
+
+                    if ui.input(|state| state.key_pressed(Key::F)) {
+                        self.view_changed |= circuit.fit_to_view(viewport_rect.size().into());
+                    }
+
+                    if ui.input(|state| state.key_pressed(Key::M)) {
+                        circuit.mirror_selection();
+                        self.requires_redraw = true;
+                    }
+
+                    if ui.input(|state| state.key_pressed(Key::Home)) {
+                        self.view_changed |= circuit.set_offset(Vec2f::ZERO);
+                    }
+
+                    // Components added while simulating wouldn't be part of the simulation
+                    let editing = is_discriminant!(circuit.sim_state(), SimState::None);
+                    for (key, new_kind) in PALETTE_HOTKEYS {
+                        if editing
+                            && ui.input(|state| state.modifiers.is_none() && state.key_pressed(key))
+                        {
+                            circuit.add_component(new_kind());
+                            self.requires_redraw = true;
+                        }
+                    }
+
+                    let move_step = if ui.input(|state| state.modifiers.shift) {
+                        LARGE_MOVE_STEP
+                    } else {
+                        self.state.snap_step
+                    };
+
+                    let now = ui.input(|state| state.time);
+                    let pressed_dir =
+                        ui.input(|state| arrow_key_dir(state, InputState::key_pressed));
+                    let held_dir = ui.input(|state| arrow_key_dir(state, InputState::key_down));
+
+                    if ui.input(|state| state.modifiers.command) {
+                        if held_dir != Vec2i::ZERO {
+                            let dt = ui.input(|state| state.stable_dt);
+                            let offset_delta = held_dir.to_vec2f() * (KEYBOARD_PAN_SPEED * dt)
+                                / (circuit.zoom() * BASE_ZOOM);
+                            self.view_changed |=
+                                circuit.set_offset(circuit.offset() + offset_delta);
+                            ui.ctx().request_repaint();
+                        }
+                    } else {
+                        if pressed_dir != Vec2i::ZERO {
+                            circuit.move_selection(pressed_dir * move_step);
+                            self.next_move_repeat_time = now + MOVE_REPEAT_DELAY;
+                            self.requires_redraw = true;
+                        } else if (held_dir != Vec2i::ZERO) && (now >= self.next_move_repeat_time) {
+                            circuit.move_selection(held_dir * move_step);
+                            self.next_move_repeat_time = now + MOVE_REPEAT_INTERVAL;
+                            self.requires_redraw = true;
+                        }
+
+                        if held_dir != Vec2i::ZERO {
+                            ui.ctx()
+                                .request_repaint_after(std::time::Duration::from_secs_f64(
+                                    (self.next_move_repeat_time - now).max(0.0),
+                                ));
+                        }
+                    }
                 }
 
                 const ZOOM_LEVELS: f32 = 10.0;
                 let zoom_delta = ui.input(|state| state.scroll_delta.y) / 120.0;
-                self.requires_redraw |=
-                    circuit.set_linear_zoom(circuit.linear_zoom() + (zoom_delta / ZOOM_LEVELS));
+                if zoom_delta != 0.0 {
+                    let new_zoom = circuit.linear_zoom() + (zoom_delta / ZOOM_LEVELS);
+
+                    // Keep the point under the cursor in place
+                    if let Some(pos) = response.hover_pos() {
+                        let logical_pos = circuit.screen_to_logical(pos, viewport_rect);
+                        self.view_changed |= circuit.set_linear_zoom_at(new_zoom, logical_pos);
+                    } else {
+                        self.view_changed |= circuit.set_linear_zoom(new_zoom);
+                    }
+                }
 
                 let mouse_delta = ui.input(|state| state.pointer.delta());
                 let mouse_delta = mouse_delta / (circuit.zoom() * BASE_ZOOM);
                 let mouse_delta = Vec2f::new(mouse_delta.x, -mouse_delta.y);
-                self.requires_redraw |= circuit.mouse_moved(mouse_delta, self.drag_mode);
+                let snap_angle = ui.input(|state| state.modifiers.shift);
+                match circuit.mouse_moved(
+                    mouse_delta,
+                    self.drag_mode,
+                    snap_angle,
+                    self.state.snap_step,
+                ) {
+                    Redraw::None => {}
+                    Redraw::Overlay => self.requires_overlay_redraw = true,
+                    Redraw::Full => self.requires_redraw = true,
+                }
 
                 if response.dragged()
                     && ui.input(|state| state.pointer.button_down(PointerButton::Middle))
@@ -695,59 +2241,418 @@ impl eframe::App for App {
                         circuit.offset().x - offset_delta.x,
                         circuit.offset().y + offset_delta.y,
                     );
-                    self.requires_redraw |= circuit.set_offset(new_offset);
+                    self.view_changed |= circuit.set_offset(new_offset);
                 }
 
                 if let Some(pos) = response.interact_pointer_pos() {
                     if viewport_rect.contains(pos) {
-                        let mut rel_pos = pos - viewport_rect.min;
-                        rel_pos.y = viewport_rect.height() - rel_pos.y;
-                        rel_pos -= response.rect.size() * 0.5;
+                        let logical_pos = circuit.screen_to_logical(pos, viewport_rect);
 
                         if ui.input(|state| state.pointer.button_released(PointerButton::Primary)) {
-                            self.requires_redraw |= circuit.primary_button_released(rel_pos.into());
+                            self.requires_redraw |= circuit.primary_button_released(
+                                logical_pos,
+                                ui.input(|state| state.modifiers.shift),
+                                self.state.max_steps(),
+                            );
                         } else if ui
                             .input(|state| state.pointer.button_released(PointerButton::Secondary))
                         {
-                            self.requires_redraw |=
-                                circuit.secondary_button_released(rel_pos.into());
+                            self.requires_redraw |= circuit.secondary_button_released(logical_pos);
+                        }
+                    }
+                }
+
+                if let Some(new_kind) = self.palette_drag {
+                    if ui.input(|state| state.pointer.any_released()) {
+                        let drop_pos = ui
+                            .input(|state| state.pointer.interact_pos())
+                            .filter(|&pos| viewport_rect.contains(pos));
+
+                        if let Some(pos) = drop_pos {
+                            let logical_pos = circuit.screen_to_logical(pos, viewport_rect);
+
+                            circuit.add_component_at(new_kind(), logical_pos.round().to_vec2i());
+                            self.requires_redraw = true;
+                        }
+                    } else {
+                        ui.ctx().set_cursor_icon(CursorIcon::Grabbing);
+                    }
+                }
+
+                self.cursor_grid_pos = response.hover_pos().map(|pos| {
+                    circuit
+                        .screen_to_logical(pos, viewport_rect)
+                        .round()
+                        .to_vec2i()
+                });
+
+                let any_button_down = ui.input(|state| state.pointer.any_down());
+                if let Some(pos) = response.hover_pos().filter(|_| !any_button_down) {
+                    let logical_pos = circuit.screen_to_logical(pos, viewport_rect);
+
+                    if let Some(info) = circuit.describe_wire_at(logical_pos) {
+                        show_tooltip_at_pointer(ui.ctx(), Id::new("wire_tool_tip"), |ui| {
+                            let width_name = self
+                                .locale_manager
+                                .get(&self.state.lang, "bit-width-property-name");
+                            match info.width {
+                                Some(width) => ui.label(format!("{width_name} {width}")),
+                                None => ui.label(
+                                    self.locale_manager
+                                        .get(&self.state.lang, "width-conflict-error"),
+                                ),
+                            };
+
+                            if let Some(value) = info.value {
+                                let value_name = self
+                                    .locale_manager
+                                    .get(&self.state.lang, "value-property-name");
+                                ui.label(format!("{value_name} {value}"));
+                            }
+                        });
+                    }
+                }
+
+                if let Some(target) = circuit.context_menu_target() {
+                    response.context_menu(|ui| match target {
+                        ContextMenuTarget::Empty { position } => {
+                            if ui
+                                .add_enabled(
+                                    self.clipboard.is_some(),
+                                    Button::new(
+                                        self.locale_manager
+                                            .get(&self.state.lang, "paste-here-menu-item"),
+                                    ),
+                                )
+                                .clicked()
+                            {
+                                if let Some(data) = &self.clipboard {
+                                    circuit.paste(data, position);
+                                    self.requires_redraw = true;
+                                }
+
+                                ui.close_menu();
+                            }
+                        }
+                        ContextMenuTarget::Component | ContextMenuTarget::WireSegment => {
+                            if ui
+                                .button(
+                                    self.locale_manager
+                                        .get(&self.state.lang, "delete-menu-item"),
+                                )
+                                .clicked()
+                            {
+                                self.start_sim_error = None;
+                                circuit.delete_selection();
+                                self.requires_redraw = true;
+                                ui.close_menu();
+                            }
+
+                            if ui
+                                .button(
+                                    self.locale_manager
+                                        .get(&self.state.lang, "rotate-cw-menu-item"),
+                                )
+                                .clicked()
+                            {
+                                circuit.clockwise_rotate_selection();
+                                self.requires_redraw = true;
+                                ui.close_menu();
+                            }
+
+                            if ui
+                                .button(
+                                    self.locale_manager
+                                        .get(&self.state.lang, "rotate-ccw-menu-item"),
+                                )
+                                .clicked()
+                            {
+                                circuit.counterclockwise_rotate_selection();
+                                self.requires_redraw = true;
+                                ui.close_menu();
+                            }
+
+                            if ui
+                                .button(
+                                    self.locale_manager
+                                        .get(&self.state.lang, "mirror-menu-item"),
+                                )
+                                .clicked()
+                            {
+                                circuit.mirror_selection();
+                                self.requires_redraw = true;
+                                ui.close_menu();
+                            }
+
+                            if is_discriminant!(target, ContextMenuTarget::WireSegment)
+                                && ui
+                                    .button(
+                                        self.locale_manager
+                                            .get(&self.state.lang, "auto-route-menu-item"),
+                                    )
+                                    .clicked()
+                            {
+                                circuit.auto_route_selection();
+                                self.requires_redraw = true;
+                                ui.close_menu();
+                            }
+
+                            if circuit.selection().component_count() >= 2 {
+                                ui.menu_button(
+                                    self.locale_manager.get(&self.state.lang, "align-menu-item"),
+                                    |ui| {
+                                        for (key, axis, mode) in [
+                                            (
+                                                "align-left-menu-item",
+                                                Axis::Horizontal,
+                                                AlignMode::Start,
+                                            ),
+                                            (
+                                                "align-center-menu-item",
+                                                Axis::Horizontal,
+                                                AlignMode::Center,
+                                            ),
+                                            (
+                                                "align-right-menu-item",
+                                                Axis::Horizontal,
+                                                AlignMode::End,
+                                            ),
+                                            ("align-top-menu-item", Axis::Vertical, AlignMode::End),
+                                            (
+                                                "align-middle-menu-item",
+                                                Axis::Vertical,
+                                                AlignMode::Center,
+                                            ),
+                                            (
+                                                "align-bottom-menu-item",
+                                                Axis::Vertical,
+                                                AlignMode::Start,
+                                            ),
+                                        ] {
+                                            if ui
+                                                .button(
+                                                    self.locale_manager.get(&self.state.lang, key),
+                                                )
+                                                .clicked()
+                                            {
+                                                circuit.align_selection(axis, mode);
+                                                self.requires_redraw = true;
+                                                ui.close_menu();
+                                            }
+                                        }
+
+                                        ui.separator();
+
+                                        let can_distribute =
+                                            circuit.selection().component_count() >= 3;
+                                        for (key, axis) in [
+                                            ("distribute-horizontally-menu-item", Axis::Horizontal),
+                                            ("distribute-vertically-menu-item", Axis::Vertical),
+                                        ] {
+                                            if ui
+                                                .add_enabled(
+                                                    can_distribute,
+                                                    Button::new(
+                                                        self.locale_manager
+                                                            .get(&self.state.lang, key),
+                                                    ),
+                                                )
+                                                .clicked()
+                                            {
+                                                circuit.distribute_selection(axis);
+                                                self.requires_redraw = true;
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    },
+                                );
+                            }
+
+                            if is_discriminant!(target, ContextMenuTarget::Component) {
+                                ui.menu_button(
+                                    self.locale_manager
+                                        .get(&self.state.lang, "edit-properties-menu-item"),
+                                    |ui| {
+                                        self.requires_redraw |= circuit
+                                            .update_component_properties(
+                                                ui,
+                                                &self.locale_manager,
+                                                &self.state.lang,
+                                            );
+                                    },
+                                );
+                            }
                         }
+                    });
+                }
+
+                if let Some(input) = circuit.edited_input() {
+                    if let ComponentKind::Input {
+                        value,
+                        width,
+                        radix,
+                        ..
+                    } = &circuit.components()[input].kind
+                    {
+                        let radix = *radix;
+                        let mut new_value = *value;
+                        let max_value = u32::MAX >> (32 - width.get().get().min(32));
+
+                        Area::new("input_editor")
+                            .order(Order::Foreground)
+                            .fixed_pos(self.input_editor_pos)
+                            .show(ui.ctx(), |ui| {
+                                Frame::popup(ui.style()).show(ui, |ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.label(
+                                            self.locale_manager
+                                                .get(&self.state.lang, "value-property-name"),
+                                        );
+                                        ui.add(radix.drag_value(&mut new_value, max_value));
+                                    });
+                                });
+                            });
+
+                        // Clicking into the viewport closes the editor as well
+                        if ui.input(|state| state.key_pressed(Key::Escape)) {
+                            circuit.close_input_editor();
+                        }
+
+                        self.requires_redraw |=
+                            circuit.set_input_value(input, new_value, self.state.max_steps());
+                    }
+                }
+
+                if self.state.show_minimap {
+                    let minimap_pos =
+                        viewport_rect.right_bottom() - MINIMAP_SIZE - Vec2::splat(MINIMAP_MARGIN);
+                    let target = Area::new("minimap")
+                        .fixed_pos(minimap_pos)
+                        .show(ui.ctx(), |ui| minimap(ui, circuit, viewport_rect.size()))
+                        .inner;
+
+                    if let Some(target) = target {
+                        self.view_changed |= circuit.set_offset(target);
                     }
                 }
             }
 
-            if self.requires_redraw {
+            if ui.input(|state| state.pointer.any_released()) {
+                self.palette_drag = None;
+            }
+
+            if self.cursor_grid_pos != prev_cursor_grid_pos {
+                ctx.request_repaint();
+            }
+
+            macro_rules! viewport_color {
+                ($color:ident) => {
+                    viewport::Color::rgba(
+                        $color.r() as f64,
+                        $color.g() as f64,
+                        $color.b() as f64,
+                        $color.a() as f64,
+                    )
+                };
+            }
+
+            if self.requires_redraw || self.view_changed {
                 let selected_circuit = self.selected_circuit.map(|i| &self.circuits[i]);
+                let width_conflicts = self.start_sim_error.as_ref().and_then(|(i, err)| {
+                    if Some(*i) != self.selected_circuit {
+                        return None;
+                    }
+
+                    match err {
+                        StartSimError::WidthConflict { conflict_segments } => {
+                            Some(conflict_segments)
+                        }
+                        StartSimError::UnsupportedSplitter => None,
+                    }
+                });
 
                 let background_color: Rgba = ui.visuals().extreme_bg_color.into();
                 let grid_color: Rgba = ui.visuals().weak_text_color().into();
                 let component_color: Rgba = ui.visuals().text_color().into();
                 let selected_component_color: Rgba = ui.visuals().strong_text_color().into();
-
-                macro_rules! viewport_color {
-                    ($color:ident) => {
-                        viewport::Color::rgba(
-                            $color.r() as f64,
-                            $color.g() as f64,
-                            $color.b() as f64,
-                            $color.a() as f64,
-                        )
+                let wire_color: Rgba = ui.visuals().hyperlink_color.into();
+                let selected_wire_color: Rgba = ui.visuals().selection.stroke.color.into();
+                let conflict_wire_color: Rgba = ui.visuals().error_fg_color.into();
+                let bidirectional_anchor_color: Rgba = ui.visuals().warn_fg_color.into();
+                let passive_anchor_color = wire_color;
+                let (input_anchor_color, output_anchor_color): (Rgba, Rgba) =
+                    if ui.visuals().dark_mode {
+                        (Color32::LIGHT_GREEN.into(), Color32::LIGHT_RED.into())
+                    } else {
+                        (Color32::DARK_GREEN.into(), Color32::DARK_RED.into())
                     };
-                }
 
                 viewport.draw(
                     render_state,
                     selected_circuit,
+                    self.requires_redraw,
+                    width_conflicts,
+                    &self.state.grid,
                     &ViewportColors {
                         background_color: viewport_color!(background_color),
                         grid_color: viewport_color!(grid_color),
                         component_color: viewport_color!(component_color),
                         selected_component_color: viewport_color!(selected_component_color),
+                        wire_color: viewport_color!(wire_color),
+                        selected_wire_color: viewport_color!(selected_wire_color),
+                        conflict_wire_color: viewport_color!(conflict_wire_color),
+                        input_anchor_color: viewport_color!(input_anchor_color),
+                        output_anchor_color: viewport_color!(output_anchor_color),
+                        bidirectional_anchor_color: viewport_color!(bidirectional_anchor_color),
+                        passive_anchor_color: viewport_color!(passive_anchor_color),
                     },
                 );
 
                 self.requires_redraw = false;
+                self.requires_overlay_redraw = false;
+                self.view_changed = false;
+            } else if self.requires_overlay_redraw {
+                let selected_circuit = self.selected_circuit.map(|i| &self.circuits[i]);
+                let selection_box_color: Rgba = ui.visuals().strong_text_color().into();
+                viewport.draw_overlay(
+                    render_state,
+                    selected_circuit,
+                    viewport_color!(selection_box_color),
+                );
+
+                self.requires_overlay_redraw = false;
             }
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamped_numeric_value_clamps_low() {
+        assert_eq!(clamped_numeric_value("0", 8u8, &(1..=64)), 1);
+        assert_eq!(clamped_numeric_value("-5", 8i32, &(1..=64)), 1);
+    }
+
+    #[test]
+    fn clamped_numeric_value_clamps_high() {
+        assert_eq!(clamped_numeric_value("200", 8u8, &(1..=64)), 64);
+        assert_eq!(clamped_numeric_value("0xFF", 8u8, &(1..=64)), 64);
+    }
+
+    #[test]
+    fn clamped_numeric_value_keeps_values_in_range() {
+        assert_eq!(clamped_numeric_value(" 32 ", 8u8, &(1..=64)), 32);
+        assert_eq!(clamped_numeric_value("0b101", 8u8, &(1..=64)), 5);
+    }
+
+    #[test]
+    fn clamped_numeric_value_reverts_on_parse_failure() {
+        assert_eq!(clamped_numeric_value("", 8u8, &(1..=64)), 8);
+        assert_eq!(clamped_numeric_value("abc", 8u8, &(1..=64)), 8);
+        // Out of range for the type itself
+        assert_eq!(clamped_numeric_value("300", 8u8, &(1..=64)), 8);
+    }
+}
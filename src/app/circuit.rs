@@ -2,17 +2,27 @@ use super::component::*;
 use super::locale::*;
 use super::viewport::{BASE_ZOOM, LOGICAL_PIXEL_SIZE};
 use crate::app::math::*;
-use crate::{is_discriminant, HashSet};
+use crate::{is_discriminant, HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
+use std::collections::VecDeque;
 use std::num::NonZeroU8;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
+mod history;
+use history::*;
+
+mod routing;
+use routing::*;
+
+mod netlist;
+pub use netlist::ImportNetlistError;
+
 const MIN_LINEAR_ZOOM: f32 = 0.0;
 const MAX_LINEAR_ZOOM: f32 = 1.0;
-const MIN_ZOOM: f32 = 0.5;
-const MAX_ZOOM: f32 = 4.0;
+pub const MIN_ZOOM: f32 = 0.5;
+pub const MAX_ZOOM: f32 = 4.0;
 pub const DEFAULT_ZOOM: f32 = 1.0;
 
 // Note: these should be constants but `ln` and `exp` are not constant functions
@@ -35,7 +45,7 @@ fn linear_to_zoom(linear: f32) -> f32 {
     zoom_fn_a() * (zoom_fn_b() * linear).exp()
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WireSegment {
     pub endpoint_a: Vec2i,
     pub midpoints: SmallVec<[Vec2i; 2]>,
@@ -44,9 +54,55 @@ pub struct WireSegment {
     pub sim_wires: SmallVec<[gsim::WireId; 4]>,
 }
 
+/// Projects `p` onto the closest of the eight horizontal, vertical and diagonal rays starting at `origin`
+fn snap_to_octant(origin: Vec2i, p: Vec2f) -> Vec2i {
+    const DIRECTIONS: [Vec2i; 8] = [
+        Vec2i::new(1, 0),
+        Vec2i::new(1, 1),
+        Vec2i::new(0, 1),
+        Vec2i::new(-1, 1),
+        Vec2i::new(-1, 0),
+        Vec2i::new(-1, -1),
+        Vec2i::new(0, -1),
+        Vec2i::new(1, -1),
+    ];
+
+    let delta = p - origin.to_vec2f();
+    let angle = delta.y.atan2(delta.x);
+    let octant = (angle / std::f32::consts::FRAC_PI_4).round() as i32;
+    let dir = DIRECTIONS[octant.rem_euclid(8) as usize];
+
+    // Diagonal directions are not normalized, so divide by their squared length
+    let steps = (delta.dot(dir.to_vec2f()) / (dir.dot(dir) as f32)).round() as i32;
+    origin + dir * steps
+}
+
+/// The state a port drives onto its net, ports that don't drive leave it floating
+fn port_drive(driven: bool, value: u32) -> gsim::LogicState {
+    if driven {
+        gsim::LogicState::from_int(value)
+    } else {
+        gsim::LogicState::HIGH_Z
+    }
+}
+
+/// Wire endpoints dropped within this distance of a component anchor are moved onto it, in units
+const ANCHOR_SNAP_DISTANCE: f32 = 1.0;
+
+/// Finds the component anchor closest to `p`, if any is within `ANCHOR_SNAP_DISTANCE`
+fn snap_to_anchor(components: &[Component], p: Vec2f) -> Option<Vec2i> {
+    components
+        .iter()
+        .flat_map(|component| component.anchors())
+        .map(|anchor| (anchor.position, (p - anchor.position.to_vec2f()).len()))
+        .filter(|&(_, dist)| dist <= ANCHOR_SNAP_DISTANCE)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(position, _)| position)
+}
+
 impl WireSegment {
-    pub fn contains(&self, p: Vec2f) -> Option<usize> {
-        // Bounding box test
+    /// The bounding box of all points of the wire, not including its thickness
+    pub fn bounding_box(&self) -> Rectangle {
         let midpoints = self.midpoints.iter().copied();
         let endpoint_a = std::iter::once(self.endpoint_a);
         let endpoint_b = std::iter::once(self.endpoint_b);
@@ -58,43 +114,28 @@ impl WireSegment {
                 (min.min(v), max.max(v))
             });
 
-        let bb = Rectangle {
-            top: (max.y as f32) + LOGICAL_PIXEL_SIZE,
-            bottom: (min.y as f32) - LOGICAL_PIXEL_SIZE,
-            left: (min.x as f32) - LOGICAL_PIXEL_SIZE,
-            right: (max.x as f32) + LOGICAL_PIXEL_SIZE,
-        };
+        Rectangle::from_points(min.to_vec2f(), max.to_vec2f())
+    }
+
+    pub fn contains(&self, p: Vec2f) -> Option<usize> {
+        // Bounding box test
+        let mut bb = self.bounding_box();
+        bb.top += LOGICAL_PIXEL_SIZE;
+        bb.bottom -= LOGICAL_PIXEL_SIZE;
+        bb.left -= LOGICAL_PIXEL_SIZE;
+        bb.right += LOGICAL_PIXEL_SIZE;
 
         if !bb.contains(p) {
             return None;
         }
 
-        // Triangle test
+        // Distance test
         let midpoints = self.midpoints.iter().copied();
         let endpoint_b = std::iter::once(self.endpoint_b);
 
         let mut a = self.endpoint_a.to_vec2f();
         for (i, b) in midpoints.chain(endpoint_b).map(Vec2i::to_vec2f).enumerate() {
-            let dir = (b - a).normalized();
-            let left = Vec2f::new(dir.y, -dir.x) * LOGICAL_PIXEL_SIZE;
-            let right = Vec2f::new(-dir.y, dir.x) * LOGICAL_PIXEL_SIZE;
-
-            let a1 = a + left;
-            let a2 = a + right;
-            let b1 = b + left;
-            let b2 = b + right;
-            let t1 = Triangle {
-                a: a1,
-                b: a2,
-                c: b2,
-            };
-            let t2 = Triangle {
-                a: a1,
-                b: b2,
-                c: b1,
-            };
-
-            if t1.contains(p) || t2.contains(p) {
+            if p.distance_to_segment(a, b) <= LOGICAL_PIXEL_SIZE {
                 return Some(i);
             }
 
@@ -104,6 +145,33 @@ impl WireSegment {
         None
     }
 
+    /// Checks whether any part of the wire, including its bends, lies inside the rectangle
+    pub fn intersects(&self, rect: &Rectangle) -> bool {
+        let midpoints = self.midpoints.iter().copied();
+        let endpoint_b = std::iter::once(self.endpoint_b);
+
+        let mut a = self.endpoint_a.to_vec2f();
+        for b in midpoints.chain(endpoint_b).map(Vec2i::to_vec2f) {
+            if rect.intersects_segment(a, b) {
+                return true;
+            }
+
+            a = b;
+        }
+
+        false
+    }
+
+    /// Applies `apply_pt` to all points of the wire, relative to `center`
+    fn transform_points(&mut self, center: Vec2i, apply_pt: impl Fn(Vec2i) -> Vec2i) {
+        self.endpoint_a = apply_pt(self.endpoint_a - center) + center;
+        self.endpoint_b = apply_pt(self.endpoint_b - center) + center;
+
+        for p in self.midpoints.iter_mut() {
+            *p = apply_pt(*p - center) + center;
+        }
+    }
+
     fn update_midpoints(&mut self) {
         self.midpoints.clear();
 
@@ -139,6 +207,29 @@ impl WireSegment {
         }
     }
 
+    /// Removes midpoints that lie on a straight line between their neighbours
+    fn remove_redundant_midpoints(&mut self) {
+        let mut i = 0;
+        while i < self.midpoints.len() {
+            let prev = if i == 0 {
+                self.endpoint_a
+            } else {
+                self.midpoints[i - 1]
+            };
+            let next = self
+                .midpoints
+                .get(i + 1)
+                .copied()
+                .unwrap_or(self.endpoint_b);
+
+            if (self.midpoints[i] - prev).cross(next - self.midpoints[i]) == 0 {
+                self.midpoints.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
     fn split_at(&mut self, index: usize, p: Vec2i) -> WireSegment {
         let (mut left, mut right) = self.midpoints.split_at(index);
 
@@ -178,6 +269,28 @@ pub enum Selection {
 }
 
 impl Selection {
+    /// Number of selected components and wire segments
+    pub fn item_count(&self) -> usize {
+        match self {
+            Selection::None => 0,
+            Selection::Component(_) | Selection::WireSegment(_) => 1,
+            Selection::Multi {
+                components,
+                wire_segments,
+                ..
+            } => components.len() + wire_segments.len(),
+        }
+    }
+
+    /// Number of selected components
+    pub fn component_count(&self) -> usize {
+        match self {
+            Selection::None | Selection::WireSegment(_) => 0,
+            Selection::Component(_) => 1,
+            Selection::Multi { components, .. } => components.len(),
+        }
+    }
+
     pub fn contains_component(&self, component: usize) -> bool {
         match self {
             Selection::None => false,
@@ -197,6 +310,39 @@ impl Selection {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// Which edge of the bounding boxes to align components by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignMode {
+    /// Left or bottom edge
+    Start,
+    Center,
+    /// Right or top edge
+    End,
+}
+
+/// Start, center and end of a rectangle along an axis
+fn rect_extent(rect: &Rectangle, axis: Axis) -> (f32, f32, f32) {
+    match axis {
+        Axis::Horizontal => (rect.left, (rect.left + rect.right) * 0.5, rect.right),
+        Axis::Vertical => (rect.bottom, (rect.bottom + rect.top) * 0.5, rect.top),
+    }
+}
+
+/// How much of the viewport has to be redrawn after an interaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redraw {
+    None,
+    /// Only the selection box changed
+    Overlay,
+    Full,
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum DragMode {
     #[default]
@@ -218,16 +364,26 @@ enum DragState {
     },
     DraggingWirePointA {
         wire_segment: usize,
+        original: WireSegment,
         drag_start: Vec2f,
         drag_delta: Vec2f,
     },
     DraggingWirePointB {
         wire_segment: usize,
+        original: WireSegment,
+        drag_start: Vec2f,
+        drag_delta: Vec2f,
+    },
+    DraggingWireMidpoint {
+        wire_segment: usize,
+        midpoint: usize,
+        original: WireSegment,
         drag_start: Vec2f,
         drag_delta: Vec2f,
     },
     Dragging {
         fract_drag_delta: Vec2f,
+        total_drag_delta: Vec2i,
     },
 }
 
@@ -238,6 +394,13 @@ enum HitTestResult {
     ComponentAnchor(usize),
     WirePointA(usize),
     WirePointB(usize),
+    WireMidpoint(usize, usize),
+}
+
+/// Components and wire segments copied out of a circuit, positioned relative to their center
+pub struct ClipboardData {
+    components: Vec<Component>,
+    wire_segments: Vec<WireSegment>,
 }
 
 #[derive(Default)]
@@ -252,6 +415,273 @@ pub enum SimState {
         sim: gsim::Simulator,
         conflict_segments: HashSet<usize>,
     },
+    Unstable {
+        sim: gsim::Simulator,
+        clock_state: bool,
+        max_steps: u64,
+    },
+}
+
+/// Formats a simulated state as hex digits, digits with undefined or high-impedance bits are shown as `X` or `Z`
+pub fn format_logic_state(state: &gsim::LogicState, width: NonZeroU8, radix: Radix) -> String {
+    let width = width.get();
+
+    match radix {
+        Radix::Bin => {
+            return (0..width)
+                .rev()
+                .map(|bit| match state.get_bit_state(bit) {
+                    gsim::LogicBitState::HighZ => 'Z',
+                    gsim::LogicBitState::Undefined => 'X',
+                    gsim::LogicBitState::Logic0 => '0',
+                    gsim::LogicBitState::Logic1 => '1',
+                })
+                .collect();
+        }
+        // Wider values don't fit a native integer, so they fall back to hex
+        Radix::Dec if width <= 128 => {
+            let mut value = 0u128;
+            for bit in (0..width).rev() {
+                value <<= 1;
+                match state.get_bit_state(bit) {
+                    gsim::LogicBitState::HighZ => return "Z".to_owned(),
+                    gsim::LogicBitState::Undefined => return "X".to_owned(),
+                    gsim::LogicBitState::Logic0 => {}
+                    gsim::LogicBitState::Logic1 => value |= 1,
+                }
+            }
+
+            return value.to_string();
+        }
+        Radix::Dec | Radix::Hex => {}
+    }
+
+    let digit_count = (width + 3) / 4;
+
+    let mut text = String::with_capacity(digit_count as usize);
+    for digit in (0..digit_count).rev() {
+        let mut value = 0;
+        let mut undefined = false;
+        let mut high_z = false;
+
+        for bit in ((digit * 4)..((digit * 4) + 4).min(width)).rev() {
+            value <<= 1;
+            match state.get_bit_state(bit) {
+                gsim::LogicBitState::HighZ => high_z = true,
+                gsim::LogicBitState::Undefined => undefined = true,
+                gsim::LogicBitState::Logic0 => {}
+                gsim::LogicBitState::Logic1 => value |= 1,
+            }
+        }
+
+        text.push(if undefined {
+            'X'
+        } else if high_z {
+            'Z'
+        } else {
+            char::from_digit(value, 16).unwrap().to_ascii_uppercase()
+        });
+    }
+
+    text
+}
+
+/// Information about the net a wire segment belongs to
+pub struct WireInfo {
+    /// `None` if the connected components disagree on the width
+    pub width: Option<NonZeroU8>,
+    /// The simulated value, if the simulation is running
+    pub value: Option<String>,
+}
+
+/// Size measurements of a circuit
+pub struct CircuitStats {
+    /// Number of components of each kind, keyed by `ComponentKind::kind_name_key`
+    pub component_counts: Vec<(&'static str, usize)>,
+    pub component_count: usize,
+    pub wire_segment_count: usize,
+    /// Sum of the horizontal and vertical distances covered by all wire segments, in units
+    pub wire_length: u64,
+    /// Number of separate groups of connected wires, tunnels join groups
+    pub net_count: usize,
+}
+
+/// The live value of a named port while simulating
+pub struct ProbeValue {
+    /// Index of the port in the components of the circuit
+    pub component: usize,
+    pub name: String,
+    pub state: gsim::LogicState,
+    pub width: NonZeroU8,
+    pub radix: Radix,
+}
+
+/// Number of samples kept per recorded signal before the oldest ones are dropped
+pub const MAX_SIGNAL_SAMPLES: usize = 256;
+
+/// The recorded history of a named port while simulating
+pub struct SignalTrace {
+    /// Index of the port in the components of the circuit, names don't have to be unique
+    pub component: usize,
+    pub name: String,
+    pub width: NonZeroU8,
+    pub radix: Radix,
+    /// Index of the first sample in `samples`, counted from the start of the simulation
+    pub first_sample: usize,
+    pub samples: VecDeque<gsim::LogicState>,
+}
+
+/// How a wire segment should be displayed according to its simulated state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireDisplayState {
+    /// Not simulating
+    None,
+    Logic0,
+    Logic1,
+    HighZ,
+    Undefined,
+    /// The bits of a multi-bit wire are not all in the same state
+    Mixed,
+}
+
+/// What a context menu opened by the secondary mouse button operates on
+#[derive(Clone, Copy)]
+pub enum ContextMenuTarget {
+    Empty { position: Vec2i },
+    Component,
+    WireSegment,
+}
+
+/// A simulation wire carrying a range of bits of a net
+#[derive(Clone, Copy)]
+struct NetWire {
+    first_bit: u8,
+    width: NonZeroU8,
+    sim_wire: gsim::WireId,
+}
+
+/// Maps anchor positions to the simulation wires of the net they are connected to
+#[derive(Default)]
+struct AnchorWireResolver {
+    nets: HashMap<Vec2i, SmallVec<[NetWire; 1]>>,
+}
+
+impl AnchorWireResolver {
+    /// The first net inserted at a position wins
+    fn insert(&mut self, position: Vec2i, net_wires: &[NetWire]) {
+        self.nets
+            .entry(position)
+            .or_insert_with(|| net_wires.iter().copied().collect());
+    }
+
+    /// Finds the wire carrying exactly the bits `first_bit..(first_bit + width)` at a position
+    fn resolve_bits(
+        &self,
+        position: Vec2i,
+        first_bit: u8,
+        width: NonZeroU8,
+    ) -> Option<gsim::WireId> {
+        self.nets
+            .get(&position)?
+            .iter()
+            .find(|net_wire| (net_wire.first_bit == first_bit) && (net_wire.width == width))
+            .map(|net_wire| net_wire.sim_wire)
+    }
+
+    /// Finds the wire an anchor is connected to, anchors always start at the first bit of a net
+    fn resolve(&self, anchor: &Anchor) -> Option<gsim::WireId> {
+        self.resolve_bits(anchor.position, 0, anchor.width)
+    }
+
+    /// Like `resolve`, but creates a dummy wire of the anchor's width if it is unconnected.
+    ///
+    /// Nets whose width doesn't match a connected anchor are rejected before this is called.
+    fn resolve_or_add(
+        &self,
+        anchor: &Anchor,
+        builder: &mut gsim::SimulatorBuilder,
+    ) -> gsim::WireId {
+        self.resolve(anchor)
+            .unwrap_or_else(|| builder.add_wire(anchor.width).unwrap())
+    }
+}
+
+/// Version of the file format written by `Circuit::serialize`
+const FORMAT_VERSION: u32 = 1;
+
+/// Upgrade steps for files written by older versions, the entry at index `i` converts the layout
+/// of version `i` to version `i + 1`
+const MIGRATIONS: [fn(&mut serde_json::Value); FORMAT_VERSION as usize] = [
+    // Files before version 1 have no version field but are otherwise identical
+    |_| {},
+];
+
+/// Binary circuit files start with these bytes, followed by the format version
+const BINARY_MAGIC: &[u8; 4] = b"GSIM";
+pub const JSON_EXTENSION: &str = "json";
+pub const BINARY_EXTENSION: &str = "gsim";
+
+#[derive(Serialize)]
+struct VersionedCircuit<'a> {
+    version: u32,
+    #[serde(flatten)]
+    circuit: &'a Circuit,
+}
+
+#[derive(Debug)]
+pub enum LoadCircuitError {
+    /// The file is not valid JSON or does not describe a circuit.
+    Invalid(serde_json::Error),
+    /// The binary file is corrupted.
+    InvalidBinary(bincode::Error),
+    /// The file was written by a newer version of the application.
+    UnsupportedVersion { version: u32 },
+}
+
+impl std::fmt::Display for LoadCircuitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadCircuitError::Invalid(err) => write!(f, "invalid circuit file: {err}"),
+            LoadCircuitError::InvalidBinary(err) => {
+                write!(f, "invalid binary circuit file: {err}")
+            }
+            LoadCircuitError::UnsupportedVersion { version } => write!(
+                f,
+                "circuit file has format version {version}, but only versions up to {FORMAT_VERSION} are supported"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoadCircuitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadCircuitError::Invalid(err) => Some(err),
+            LoadCircuitError::InvalidBinary(err) => Some(err),
+            LoadCircuitError::UnsupportedVersion { .. } => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for LoadCircuitError {
+    #[inline]
+    fn from(err: serde_json::Error) -> Self {
+        LoadCircuitError::Invalid(err)
+    }
+}
+
+impl From<bincode::Error> for LoadCircuitError {
+    #[inline]
+    fn from(err: bincode::Error) -> Self {
+        LoadCircuitError::InvalidBinary(err)
+    }
+}
+
+pub enum StartSimError {
+    /// Components of different bit-widths are connected to the same net.
+    WidthConflict { conflict_segments: HashSet<usize> },
+    /// The circuit contains splitters, which can't be simulated yet.
+    UnsupportedSplitter,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -272,9 +702,36 @@ pub struct Circuit {
     #[serde(skip)]
     secondary_button_down: bool,
     #[serde(skip)]
+    context_menu_target: Option<ContextMenuTarget>,
+    #[serde(skip)]
     file_name: Option<PathBuf>,
     #[serde(skip)]
     sim_state: SimState,
+    #[serde(skip)]
+    history: EditHistory,
+    #[serde(skip)]
+    pending_edits: Vec<EditCommand>,
+    /// Index and state before the first change of a property edit that is still in progress
+    #[serde(skip)]
+    property_edit: Option<(usize, Component)>,
+    #[serde(skip)]
+    shared_width_buffer: String,
+    /// Width of the net of every wire segment, `None` if the widths conflict. Computed on demand
+    /// for wire tooltips and cleared by every edit
+    #[serde(skip)]
+    net_widths: Option<Vec<Option<NonZeroU8>>>,
+    #[serde(skip)]
+    modified: bool,
+    #[serde(skip)]
+    held_push_button: Option<usize>,
+    #[serde(skip)]
+    edited_input: Option<usize>,
+    #[serde(skip)]
+    signal_traces: Vec<SignalTrace>,
+    #[serde(skip)]
+    sample_count: usize,
+    #[serde(skip)]
+    last_step_count: u64,
 }
 
 impl Circuit {
@@ -290,8 +747,45 @@ impl Circuit {
             drag_state: DragState::None,
             primary_button_down: false,
             secondary_button_down: false,
+            context_menu_target: None,
             file_name: None,
             sim_state: SimState::None,
+            history: EditHistory::default(),
+            pending_edits: vec![],
+            property_edit: None,
+            shared_width_buffer: String::new(),
+            net_widths: None,
+            modified: false,
+            held_push_button: None,
+            edited_input: None,
+            signal_traces: Vec::new(),
+            sample_count: 0,
+            last_step_count: 0,
+        }
+    }
+
+    /// Creates an unsaved copy of the circuit that does not belong to any file.
+    /// The simulation, selection and edit history are not copied.
+    pub fn duplicate(&self, name: String) -> Self {
+        let mut components = self.components.clone();
+        for component in &mut components {
+            component.kind.reset_sim_ids();
+        }
+
+        let mut wire_segments = self.wire_segments.clone();
+        for wire_segment in &mut wire_segments {
+            wire_segment.sim_wires.clear();
+        }
+
+        Self {
+            name,
+            offset: self.offset,
+            linear_zoom: self.linear_zoom,
+            zoom: self.zoom,
+            components,
+            wire_segments,
+            modified: true,
+            ..Self::new()
         }
     }
 
@@ -300,6 +794,13 @@ impl Circuit {
         &self.name
     }
 
+    pub fn set_name(&mut self, name: String) {
+        if name != self.name {
+            self.name = name;
+            self.modified = true;
+        }
+    }
+
     #[inline]
     pub fn offset(&self) -> Vec2f {
         self.offset
@@ -328,20 +829,130 @@ impl Circuit {
         }
     }
 
+    /// Sets the zoom factor directly, clamped to the supported range.
+    pub fn set_zoom(&mut self, zoom: f32) -> bool {
+        let new_zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        if new_zoom != self.zoom {
+            self.zoom = new_zoom;
+            self.linear_zoom = zoom_to_linear(new_zoom);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Changes the zoom while keeping the point at `logical_pos` in place on screen
+    pub fn set_linear_zoom_at(&mut self, zoom: f32, logical_pos: Vec2f) -> bool {
+        let old_zoom = self.zoom;
+        if self.set_linear_zoom(zoom) {
+            self.offset = logical_pos - (logical_pos - self.offset) * (old_zoom / self.zoom);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Converts a screen position inside `viewport_rect`, the area the circuit is drawn to, into logical coordinates
+    pub fn screen_to_logical(&self, pos: egui::Pos2, viewport_rect: egui::Rect) -> Vec2f {
+        let mut rel_pos = pos - viewport_rect.min;
+        // Logical Y points up, screen Y points down
+        rel_pos.y = viewport_rect.height() - rel_pos.y;
+        rel_pos -= viewport_rect.size() * 0.5;
+
+        Vec2f::from(rel_pos) / (self.zoom * BASE_ZOOM) + self.offset
+    }
+
     #[inline]
     pub fn zoom(&self) -> f32 {
         self.zoom
     }
 
+    /// The area covered by all components and wire segments, or `None` if the circuit is empty
+    pub fn content_bounds(&self) -> Option<Rectangle> {
+        let component_points = self.components.iter().flat_map(|component| {
+            let bb = component.bounding_box();
+            [Vec2f::new(bb.left, bb.bottom), Vec2f::new(bb.right, bb.top)]
+        });
+
+        let wire_points = self.wire_segments.iter().flat_map(|segment| {
+            std::iter::once(segment.endpoint_a)
+                .chain(segment.midpoints.iter().copied())
+                .chain(std::iter::once(segment.endpoint_b))
+                .map(Vec2i::to_vec2f)
+        });
+
+        let (min, max) = component_points
+            .chain(wire_points)
+            .fold((Vec2f::MAX, Vec2f::MIN), |(min, max), p| {
+                (min.min(p), max.max(p))
+            });
+
+        ((min.x <= max.x) && (min.y <= max.y)).then(|| Rectangle::from_points(min, max))
+    }
+
+    /// Centers the view on the circuit and zooms so it fits into a viewport of the given size.
+    pub fn fit_to_view(&mut self, viewport_size: Vec2f) -> bool {
+        const MARGIN: f32 = 2.0;
+
+        let Some(bounds) = self.content_bounds() else {
+            // The circuit is empty
+            let offset_changed = self.set_offset(Vec2f::ZERO);
+            let zoom_changed = self.set_zoom(DEFAULT_ZOOM);
+            return offset_changed | zoom_changed;
+        };
+
+        let zoom = (viewport_size.x / ((bounds.width() + MARGIN * 2.0) * BASE_ZOOM))
+            .min(viewport_size.y / ((bounds.height() + MARGIN * 2.0) * BASE_ZOOM))
+            .clamp(MIN_ZOOM, MAX_ZOOM);
+
+        let offset_changed = self.set_offset(bounds.center());
+        let zoom_changed = self.set_zoom(zoom);
+        offset_changed | zoom_changed
+    }
+
     #[inline]
     pub fn components(&self) -> &[Component] {
         &self.components
     }
 
+    /// Adds a component in the center of the view
+    #[inline]
     pub fn add_component(&mut self, kind: ComponentKind) {
-        self.selection = Selection::Component(self.components.len());
+        self.add_component_at(kind, self.offset.round().to_vec2i());
+    }
+
+    pub fn add_component_at(&mut self, kind: ComponentKind, pos: Vec2i) {
+        let mut component = Component::new(kind);
+        component.set_position(pos);
+        let index = self.components.len();
+
+        self.record_edit(EditCommand::Insert(ItemSet {
+            components: vec![(index, component.clone())],
+            wire_segments: vec![],
+        }));
+
+        self.selection = Selection::Component(index);
         self.drag_state = DragState::None;
-        self.components.push(Component::new(kind));
+        self.components.push(component);
+    }
+
+    /// Adds a wire segment through the given points, which have to be connected by
+    /// horizontal, vertical or diagonal lines
+    pub fn add_wire(&mut self, endpoint_a: Vec2i, midpoints: &[Vec2i], endpoint_b: Vec2i) {
+        let segment = WireSegment {
+            endpoint_a,
+            midpoints: midpoints.into(),
+            endpoint_b,
+            sim_wires: smallvec![],
+        };
+        let index = self.wire_segments.len();
+
+        self.record_edit(EditCommand::Insert(ItemSet {
+            components: vec![],
+            wire_segments: vec![(index, segment.clone())],
+        }));
+
+        self.wire_segments.push(segment);
     }
 
     #[inline]
@@ -354,6 +965,11 @@ impl Circuit {
         &self.selection
     }
 
+    #[inline]
+    pub fn context_menu_target(&self) -> Option<ContextMenuTarget> {
+        self.context_menu_target
+    }
+
     #[inline]
     pub fn selection_box(&self) -> Option<(Vec2f, Vec2f)> {
         match self.drag_state {
@@ -380,44 +996,546 @@ impl Circuit {
         &self.sim_state
     }
 
-    pub fn serialize(&self) -> Vec<u8> {
-        serde_json::to_vec_pretty(self).unwrap()
+    /// How many steps the simulation took to settle the last time it was advanced
+    #[inline]
+    pub fn last_step_count(&self) -> u64 {
+        self.last_step_count
     }
 
-    pub fn deserialize(data: &[u8]) -> Result<Self, serde_json::Error> {
-        let mut circuit: Circuit = serde_json::from_slice(data)?;
-        circuit.linear_zoom = zoom_to_linear(circuit.zoom);
-        Ok(circuit)
+    /// The multi-bit input whose value is currently being edited during simulation
+    pub fn edited_input(&self) -> Option<usize> {
+        self.edited_input
+            .filter(|_| is_discriminant!(self.sim_state, SimState::Active))
     }
 
-    fn hit_test(&self, logical_pos: Vec2f, exclude_wire: Option<usize>) -> HitTestResult {
-        for (i, component) in self.components.iter().enumerate() {
-            for anchor in component.anchors() {
-                if (logical_pos - anchor.position.to_vec2f()).len() <= (LOGICAL_PIXEL_SIZE * 2.0) {
-                    return HitTestResult::ComponentAnchor(i);
-                }
-            }
+    #[inline]
+    pub fn close_input_editor(&mut self) {
+        self.edited_input = None;
+    }
 
-            if component.bounding_box().contains(logical_pos) {
-                return HitTestResult::Component(i);
-            }
+    pub fn set_input_value(&mut self, component: usize, new_value: u32, max_steps: u64) -> bool {
+        let ComponentKind::Input {
+            value,
+            width,
+            sim_wire,
+            ..
+        } = &mut self.components[component].kind
+        else {
+            return false;
+        };
+
+        let max_value = u32::MAX >> (32 - width.value.get().min(32));
+        let new_value = new_value.min(max_value);
+        if *value == new_value {
+            return false;
         }
+        *value = new_value;
+        let sim_wire = *sim_wire;
 
-        for (i, wire_segment) in self.wire_segments.iter().enumerate() {
-            if Some(i) == exclude_wire {
-                continue;
-            }
+        let sim_state = std::mem::take(&mut self.sim_state);
+        if let SimState::Active {
+            mut sim,
+            clock_state,
+        } = sim_state
+        {
+            sim.set_wire_drive(sim_wire, &gsim::LogicState::from_int(new_value))
+                .unwrap();
+            self.advance_simulation(sim, clock_state, max_steps);
+        } else {
+            self.sim_state = sim_state;
+        }
 
-            if (logical_pos - wire_segment.endpoint_a.to_vec2f()).len()
-                <= (LOGICAL_PIXEL_SIZE * 2.0)
-            {
-                return HitTestResult::WirePointA(i);
-            }
+        true
+    }
 
-            if (logical_pos - wire_segment.endpoint_b.to_vec2f()).len()
-                <= (LOGICAL_PIXEL_SIZE * 2.0)
-            {
-                return HitTestResult::WirePointB(i);
+    pub fn describe_wire_at(&mut self, logical_pos: Vec2f) -> Option<WireInfo> {
+        let wire_segment = match self.hit_test(logical_pos, None) {
+            HitTestResult::WireSegment(wire_segment, _)
+            | HitTestResult::WirePointA(wire_segment)
+            | HitTestResult::WirePointB(wire_segment)
+            | HitTestResult::WireMidpoint(wire_segment, _) => wire_segment,
+            _ => return None,
+        };
+
+        let sim = match &self.sim_state {
+            SimState::None => None,
+            SimState::Active { sim, .. }
+            | SimState::Conflict { sim, .. }
+            | SimState::Unstable { sim, .. } => Some(sim),
+        };
+
+        // Wires added while simulating are not part of the simulation
+        let sim_wire = self.wire_segments[wire_segment].sim_wires.first();
+        let sim_info = Option::zip(sim, sim_wire).and_then(|(sim, &sim_wire)| {
+            let width = sim.get_wire_width(sim_wire).ok()?;
+            let state = sim.get_wire_state(sim_wire).ok()?;
+            Some((width, format_logic_state(&state, width, Radix::Hex)))
+        });
+
+        if let Some((width, value)) = sim_info {
+            return Some(WireInfo {
+                width: Some(width),
+                value: Some(value),
+            });
+        }
+
+        if self.net_widths.is_none() {
+            let (groups, group_map) = self.find_wire_groups();
+            let widths = self.find_wire_group_widths(&groups).ok();
+            self.net_widths = Some(
+                group_map
+                    .iter()
+                    .map(|&group| widths.as_ref().map(|widths| widths[group]))
+                    .collect(),
+            );
+        }
+
+        Some(WireInfo {
+            width: self.net_widths.as_ref().unwrap()[wire_segment],
+            value: None,
+        })
+    }
+
+    /// Values of all named inputs and outputs, in component order
+    pub fn probe_values(&self) -> Vec<ProbeValue> {
+        let SimState::Active { sim, .. } = &self.sim_state else {
+            return Vec::new();
+        };
+
+        self.components
+            .iter()
+            .enumerate()
+            .filter(|(_, component)| !component.kind.name().is_empty())
+            .filter_map(|(i, component)| {
+                let (width, sim_wire) = match &component.kind {
+                    ComponentKind::Input {
+                        width, sim_wire, ..
+                    }
+                    | ComponentKind::Output {
+                        width, sim_wire, ..
+                    } => (*width.get(), *sim_wire),
+                    ComponentKind::ClockInput { sim_wire, .. } => (NonZeroU8::MIN, *sim_wire),
+                    _ => return None,
+                };
+
+                // Ports added while simulating are not part of the simulation
+                let state = sim.get_wire_state(sim_wire).ok()?;
+
+                Some(ProbeValue {
+                    component: i,
+                    name: component.kind.name().to_owned(),
+                    state,
+                    width,
+                    radix: component.kind.radix(),
+                })
+            })
+            .collect()
+    }
+
+    #[inline]
+    pub fn signal_traces(&self) -> &[SignalTrace] {
+        &self.signal_traces
+    }
+
+    #[inline]
+    pub fn sample_count(&self) -> usize {
+        self.sample_count
+    }
+
+    /// Appends the current values of all named ports to their traces.
+    /// Traces of ports that are no longer named are dropped.
+    fn sample_signals(&mut self) {
+        let probe_values = self.probe_values();
+        self.signal_traces.retain(|trace| {
+            probe_values
+                .iter()
+                .any(|probe| probe.component == trace.component)
+        });
+
+        if probe_values.is_empty() {
+            return;
+        }
+
+        let sample = self.sample_count;
+        for probe in probe_values {
+            let trace = match self
+                .signal_traces
+                .iter()
+                .position(|trace| trace.component == probe.component)
+            {
+                Some(trace) => &mut self.signal_traces[trace],
+                None => {
+                    self.signal_traces.push(SignalTrace {
+                        component: probe.component,
+                        name: probe.name.clone(),
+                        width: probe.width,
+                        radix: probe.radix,
+                        first_sample: sample,
+                        samples: VecDeque::with_capacity(MAX_SIGNAL_SAMPLES),
+                    });
+                    self.signal_traces.last_mut().unwrap()
+                }
+            };
+
+            // Ports can be edited while simulating, old samples of a different width are useless
+            if trace.width != probe.width {
+                trace.width = probe.width;
+                trace.first_sample = sample;
+                trace.samples.clear();
+            }
+            trace.name = probe.name;
+            trace.radix = probe.radix;
+
+            if trace.samples.len() == MAX_SIGNAL_SAMPLES {
+                trace.samples.pop_front();
+                trace.first_sample += 1;
+            }
+            trace.samples.push_back(probe.state);
+        }
+
+        self.sample_count += 1;
+    }
+
+    /// The simulated value of an input or output component, if the simulation is running
+    pub fn component_value_text(&self, component: usize) -> Option<String> {
+        let SimState::Active { sim, .. } = &self.sim_state else {
+            return None;
+        };
+
+        let kind = &self.components[component].kind;
+        match kind {
+            ComponentKind::Input {
+                width, sim_wire, ..
+            }
+            | ComponentKind::Output {
+                width, sim_wire, ..
+            }
+            | ComponentKind::Port {
+                width, sim_wire, ..
+            } => {
+                // Components added while simulating are not part of the simulation
+                let state = sim.get_wire_state(*sim_wire).ok()?;
+                Some(format_logic_state(&state, *width.get(), kind.radix()))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn wire_display_state(&self, wire_segment: usize) -> WireDisplayState {
+        let sim = match &self.sim_state {
+            SimState::None => return WireDisplayState::None,
+            SimState::Active { sim, .. }
+            | SimState::Conflict { sim, .. }
+            | SimState::Unstable { sim, .. } => sim,
+        };
+
+        let Some(&sim_wire) = self.wire_segments[wire_segment].sim_wires.first() else {
+            return WireDisplayState::None;
+        };
+
+        let (Ok(width), Ok(state)) = (sim.get_wire_width(sim_wire), sim.get_wire_state(sim_wire))
+        else {
+            return WireDisplayState::None;
+        };
+
+        let mut display_state = WireDisplayState::None;
+        for bit in 0..width.get() {
+            let bit_state = match state.get_bit_state(bit) {
+                gsim::LogicBitState::HighZ => WireDisplayState::HighZ,
+                gsim::LogicBitState::Undefined => WireDisplayState::Undefined,
+                gsim::LogicBitState::Logic0 => WireDisplayState::Logic0,
+                gsim::LogicBitState::Logic1 => WireDisplayState::Logic1,
+            };
+
+            if display_state == WireDisplayState::None {
+                display_state = bit_state;
+            } else if display_state != bit_state {
+                return WireDisplayState::Mixed;
+            }
+        }
+
+        display_state
+    }
+
+    /// Counts the components, wires and nets of the circuit
+    pub fn stats(&self) -> CircuitStats {
+        let mut component_counts: Vec<(&'static str, usize)> = Vec::new();
+        for component in &self.components {
+            let key = component.kind.kind_name_key();
+            match component_counts.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, count)) => *count += 1,
+                None => component_counts.push((key, 1)),
+            }
+        }
+        component_counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        let wire_length = self
+            .wire_segments
+            .iter()
+            .map(|segment| {
+                let points = std::iter::once(segment.endpoint_a)
+                    .chain(segment.midpoints.iter().copied())
+                    .chain(std::iter::once(segment.endpoint_b));
+
+                points
+                    .clone()
+                    .zip(points.skip(1))
+                    .map(|(a, b)| {
+                        let diff = (b - a).abs();
+                        (diff.x as u64) + (diff.y as u64)
+                    })
+                    .sum::<u64>()
+            })
+            .sum();
+
+        let (groups, _) = self.find_wire_groups();
+
+        CircuitStats {
+            component_counts,
+            component_count: self.components.len(),
+            wire_segment_count: self.wire_segments.len(),
+            wire_length,
+            net_count: groups.len(),
+        }
+    }
+
+    /// Whether the circuit has been changed since it was last saved
+    #[inline]
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    #[inline]
+    pub fn mark_saved(&mut self) {
+        self.modified = false;
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        serde_json::to_vec_pretty(&VersionedCircuit {
+            version: FORMAT_VERSION,
+            circuit: self,
+        })
+        .unwrap()
+    }
+
+    /// A more compact alternative to `serialize`, for large circuits
+    pub fn serialize_binary(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(BINARY_MAGIC);
+        data.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bincode::serialize_into(&mut data, self).unwrap();
+        data
+    }
+
+    /// Picks the format based on the extension of the file the data is going to be written to
+    pub fn serialize_for_path(&self, path: &Path) -> Vec<u8> {
+        if path.extension().is_some_and(|ext| ext == BINARY_EXTENSION) {
+            self.serialize_binary()
+        } else {
+            self.serialize()
+        }
+    }
+
+    /// Loads a circuit in either format, detected by the magic bytes at the start of binary files
+    pub fn load(data: &[u8]) -> Result<Self, LoadCircuitError> {
+        if data.starts_with(BINARY_MAGIC) {
+            Self::deserialize_binary(data)
+        } else {
+            Self::deserialize(data)
+        }
+    }
+
+    /// Loads a circuit written by `serialize_binary`.
+    /// Unlike JSON files, binary files can only be read by the format version that wrote them.
+    pub fn deserialize_binary(data: &[u8]) -> Result<Self, LoadCircuitError> {
+        const HEADER_LEN: usize = BINARY_MAGIC.len() + std::mem::size_of::<u32>();
+        if (data.len() < HEADER_LEN) || !data.starts_with(BINARY_MAGIC) {
+            return Err(LoadCircuitError::InvalidBinary(Box::new(
+                bincode::ErrorKind::Custom("missing file header".to_owned()),
+            )));
+        }
+
+        let version_bytes = data[BINARY_MAGIC.len()..HEADER_LEN].try_into().unwrap();
+        let version = u32::from_le_bytes(version_bytes);
+        if version != FORMAT_VERSION {
+            return Err(LoadCircuitError::UnsupportedVersion { version });
+        }
+
+        let mut circuit: Circuit = bincode::deserialize(&data[HEADER_LEN..])?;
+        circuit.linear_zoom = zoom_to_linear(circuit.zoom);
+        for component in &mut circuit.components {
+            component.kind.clamp_properties();
+        }
+        Ok(circuit)
+    }
+
+    /// Loads a circuit written by `serialize`, upgrading files from older versions
+    pub fn deserialize(data: &[u8]) -> Result<Self, LoadCircuitError> {
+        let mut value: serde_json::Value = serde_json::from_slice(data)?;
+
+        // Files from before the version field was introduced count as version 0
+        let version = match value.as_object_mut().and_then(|obj| obj.remove("version")) {
+            Some(version) => serde_json::from_value(version)?,
+            None => 0,
+        };
+
+        if version > FORMAT_VERSION {
+            return Err(LoadCircuitError::UnsupportedVersion { version });
+        }
+
+        for migration in &MIGRATIONS[(version as usize)..] {
+            migration(&mut value);
+        }
+
+        let mut circuit: Circuit = serde_json::from_value(value)?;
+        circuit.linear_zoom = zoom_to_linear(circuit.zoom);
+        for component in &mut circuit.components {
+            component.kind.clamp_properties();
+        }
+        Ok(circuit)
+    }
+
+    fn record_edit(&mut self, command: EditCommand) {
+        self.modified = true;
+        self.net_widths = None;
+
+        // Edits are not undoable while simulating, the history gets cleared when a simulation starts
+        if is_discriminant!(self.sim_state, SimState::None) {
+            self.history.push(command);
+        }
+    }
+
+    fn selection_from_sets(
+        &self,
+        components: HashSet<usize>,
+        wire_segments: HashSet<usize>,
+    ) -> Selection {
+        if (components.len() == 1) && wire_segments.is_empty() {
+            Selection::Component(components.into_iter().next().unwrap())
+        } else if components.is_empty() && (wire_segments.len() == 1) {
+            Selection::WireSegment(wire_segments.into_iter().next().unwrap())
+        } else if !components.is_empty() || !wire_segments.is_empty() {
+            let bb = self.find_selection_bounding_box(&components, &wire_segments);
+
+            Selection::Multi {
+                components,
+                wire_segments,
+                center: bb.center(),
+            }
+        } else {
+            Selection::None
+        }
+    }
+
+    fn selected_indices(&self) -> (Vec<usize>, Vec<usize>) {
+        let (mut components, mut wire_segments) = match &self.selection {
+            Selection::None => (vec![], vec![]),
+            &Selection::Component(component) => (vec![component], vec![]),
+            &Selection::WireSegment(wire_segment) => (vec![], vec![wire_segment]),
+            Selection::Multi {
+                components,
+                wire_segments,
+                ..
+            } => (
+                components.iter().copied().collect(),
+                wire_segments.iter().copied().collect(),
+            ),
+        };
+
+        components.sort_unstable();
+        wire_segments.sort_unstable();
+        (components, wire_segments)
+    }
+
+    fn snapshot_selection(&self) -> ItemSet {
+        let (components, wire_segments) = self.selected_indices();
+
+        ItemSet {
+            components: components
+                .into_iter()
+                .map(|i| (i, self.components[i].clone()))
+                .collect(),
+            wire_segments: wire_segments
+                .into_iter()
+                .map(|i| (i, self.wire_segments[i].clone()))
+                .collect(),
+        }
+    }
+
+    pub fn undo(&mut self) -> bool {
+        if !is_discriminant!(self.sim_state, SimState::None) {
+            return false;
+        }
+
+        let Some(command) = self.history.undo() else {
+            return false;
+        };
+
+        self.modified = true;
+        self.net_widths = None;
+
+        let mut affected = AffectedItems::default();
+        command.revert(&mut self.components, &mut self.wire_segments, &mut affected);
+
+        self.drag_state = DragState::None;
+        self.selection = self.selection_from_sets(affected.components, affected.wire_segments);
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        if !is_discriminant!(self.sim_state, SimState::None) {
+            return false;
+        }
+
+        let Some(command) = self.history.redo() else {
+            return false;
+        };
+
+        self.modified = true;
+        self.net_widths = None;
+
+        let mut affected = AffectedItems::default();
+        command.apply(&mut self.components, &mut self.wire_segments, &mut affected);
+
+        self.drag_state = DragState::None;
+        self.selection = self.selection_from_sets(affected.components, affected.wire_segments);
+        true
+    }
+
+    fn hit_test(&self, logical_pos: Vec2f, exclude_wire: Option<usize>) -> HitTestResult {
+        for (i, component) in self.components.iter().enumerate() {
+            for anchor in component.anchors() {
+                if (logical_pos - anchor.position.to_vec2f()).len() <= (LOGICAL_PIXEL_SIZE * 2.0) {
+                    return HitTestResult::ComponentAnchor(i);
+                }
+            }
+
+            if component.bounding_box().contains(logical_pos) {
+                return HitTestResult::Component(i);
+            }
+        }
+
+        for (i, wire_segment) in self.wire_segments.iter().enumerate() {
+            if Some(i) == exclude_wire {
+                continue;
+            }
+
+            if (logical_pos - wire_segment.endpoint_a.to_vec2f()).len()
+                <= (LOGICAL_PIXEL_SIZE * 2.0)
+            {
+                return HitTestResult::WirePointA(i);
+            }
+
+            if (logical_pos - wire_segment.endpoint_b.to_vec2f()).len()
+                <= (LOGICAL_PIXEL_SIZE * 2.0)
+            {
+                return HitTestResult::WirePointB(i);
+            }
+
+            for (j, midpoint) in wire_segment.midpoints.iter().enumerate() {
+                if (logical_pos - midpoint.to_vec2f()).len() <= (LOGICAL_PIXEL_SIZE * 2.0) {
+                    return HitTestResult::WireMidpoint(i, j);
+                }
             }
 
             if let Some(split_point) = wire_segment.contains(logical_pos) {
@@ -430,8 +1548,9 @@ impl Circuit {
 
     pub fn primary_button_pressed(
         &mut self,
-        pos: Vec2f,
+        logical_pos: Vec2f,
         drag_mode: DragMode,
+        additive_selection: bool,
         max_steps: u64,
     ) -> bool {
         assert!(
@@ -439,7 +1558,6 @@ impl Circuit {
             "invalid drag state"
         );
 
-        let logical_pos = pos / (self.zoom * BASE_ZOOM) + self.offset;
         let hit = self.hit_test(logical_pos, None);
 
         let mut sim_state = SimState::None;
@@ -450,16 +1568,21 @@ impl Circuit {
             clock_state,
         } = sim_state
         {
+            self.edited_input = None;
+
             match hit {
-                HitTestResult::Component(component) | HitTestResult::ComponentAnchor(component) => {
-                    let component = &mut self.components[component];
+                HitTestResult::Component(component_index)
+                | HitTestResult::ComponentAnchor(component_index) => {
+                    let component = &mut self.components[component_index];
+                    // Components added while simulating are not part of the simulation, the
+                    // guards leave them alone
                     match &mut component.kind {
                         ComponentKind::Input {
                             value,
                             width,
                             sim_wire,
                             ..
-                        } if width.value.get() == 1 => {
+                        } if (width.value.get() == 1) && sim.get_wire_state(*sim_wire).is_ok() => {
                             *value = !*value;
                             sim.set_wire_drive(*sim_wire, &gsim::LogicState::from_int(*value))
                                 .unwrap();
@@ -468,6 +1591,40 @@ impl Circuit {
 
                             true
                         }
+                        ComponentKind::PushButton { sim_wire, .. }
+                            if sim.get_wire_state(*sim_wire).is_ok() =>
+                        {
+                            sim.set_wire_drive(*sim_wire, &gsim::LogicState::LOGIC_1)
+                                .unwrap();
+                            self.held_push_button = Some(component_index);
+
+                            self.advance_simulation(sim, clock_state, max_steps);
+
+                            true
+                        }
+                        ComponentKind::Port {
+                            driven,
+                            value,
+                            sim_wire,
+                            ..
+                        } if sim.get_wire_state(*sim_wire).is_ok() => {
+                            // Clicking a port hands the net to it or releases it again
+                            *driven = !*driven;
+                            sim.set_wire_drive(*sim_wire, &port_drive(*driven, *value))
+                                .unwrap();
+
+                            self.advance_simulation(sim, clock_state, max_steps);
+
+                            true
+                        }
+                        ComponentKind::Input { sim_wire, .. }
+                            if sim.get_wire_state(*sim_wire).is_ok() =>
+                        {
+                            // Multi-bit inputs can't be toggled, the UI shows an editor instead
+                            self.edited_input = Some(component_index);
+                            self.sim_state = SimState::Active { sim, clock_state };
+                            false
+                        }
                         _ => {
                             self.sim_state = SimState::Active { sim, clock_state };
                             false
@@ -489,7 +1646,7 @@ impl Circuit {
 
             match (hit, drag_mode) {
                 (HitTestResult::None, _) => {
-                    if !matches!(self.selection, Selection::None) {
+                    if !additive_selection && !matches!(self.selection, Selection::None) {
                         self.selection = Selection::None;
                         true
                     } else {
@@ -507,7 +1664,8 @@ impl Circuit {
                 }
                 (HitTestResult::WireSegment(wire_segment, _), DragMode::BoxSelection)
                 | (HitTestResult::WirePointA(wire_segment), DragMode::BoxSelection)
-                | (HitTestResult::WirePointB(wire_segment), DragMode::BoxSelection) => {
+                | (HitTestResult::WirePointB(wire_segment), DragMode::BoxSelection)
+                | (HitTestResult::WireMidpoint(wire_segment, _), DragMode::BoxSelection) => {
                     if !self.selection.contains_wire_segment(wire_segment) {
                         self.selection = Selection::WireSegment(wire_segment);
                         true
@@ -518,7 +1676,8 @@ impl Circuit {
                 (HitTestResult::ComponentAnchor(_), DragMode::DrawWire)
                 | (HitTestResult::WireSegment(_, _), DragMode::DrawWire)
                 | (HitTestResult::WirePointA(_), DragMode::DrawWire)
-                | (HitTestResult::WirePointB(_), DragMode::DrawWire) => false,
+                | (HitTestResult::WirePointB(_), DragMode::DrawWire)
+                | (HitTestResult::WireMidpoint(_, _), DragMode::DrawWire) => false,
             }
         };
 
@@ -526,17 +1685,21 @@ impl Circuit {
         requires_redraw
     }
 
-    pub fn primary_button_released(&mut self, pos: Vec2f) -> bool {
-        let mut requires_redraw = false;
+    pub fn primary_button_released(
+        &mut self,
+        logical_pos: Vec2f,
+        additive_selection: bool,
+        max_steps: u64,
+    ) -> bool {
+        let mut requires_redraw = self.release_push_button(max_steps);
 
         if self.primary_button_down {
             if is_discriminant!(self.drag_state, DragState::None) {
-                let logical_pos = pos / (self.zoom * BASE_ZOOM) + self.offset;
                 let hit = self.hit_test(logical_pos, None);
 
                 match hit {
                     HitTestResult::None => {
-                        if !matches!(self.selection, Selection::None) {
+                        if !additive_selection && !matches!(self.selection, Selection::None) {
                             self.selection = Selection::None;
                             requires_redraw = true;
                         }
@@ -548,7 +1711,8 @@ impl Circuit {
                     }
                     HitTestResult::WireSegment(wire_segment, _)
                     | HitTestResult::WirePointA(wire_segment)
-                    | HitTestResult::WirePointB(wire_segment) => {
+                    | HitTestResult::WirePointB(wire_segment)
+                    | HitTestResult::WireMidpoint(wire_segment, _) => {
                         self.selection = Selection::WireSegment(wire_segment);
                         requires_redraw = true;
                     }
@@ -560,44 +1724,33 @@ impl Circuit {
                 drag_delta,
             } = &self.drag_state
             {
-                let selection_box = Rectangle {
-                    top: drag_start.y.max(drag_start.y + drag_delta.y),
-                    bottom: drag_start.y.min(drag_start.y + drag_delta.y),
-                    left: drag_start.x.min(drag_start.x + drag_delta.x),
-                    right: drag_start.x.max(drag_start.x + drag_delta.x),
+                let selection_box = Rectangle::from_points(*drag_start, *drag_start + *drag_delta);
+
+                let (mut selected_components, mut selected_wire_segments) = if additive_selection {
+                    let (components, wire_segments) = self.selected_indices();
+                    (
+                        components.into_iter().collect(),
+                        wire_segments.into_iter().collect(),
+                    )
+                } else {
+                    (HashSet::new(), HashSet::new())
                 };
 
-                let mut selected_components = HashSet::new();
                 for (i, component) in self.components.iter().enumerate() {
                     if selection_box.contains(component.position().to_vec2f()) {
                         selected_components.insert(i);
                     }
                 }
 
-                let mut selected_wire_segments = HashSet::new();
                 for (i, wire_segment) in self.wire_segments.iter().enumerate() {
-                    if selection_box.contains(wire_segment.endpoint_a.to_vec2f())
-                        || selection_box.contains(wire_segment.endpoint_b.to_vec2f())
-                    {
+                    if wire_segment.intersects(&selection_box) {
                         selected_wire_segments.insert(i);
                     }
                 }
 
-                if (selected_components.len() == 1) && selected_wire_segments.is_empty() {
+                if !selected_components.is_empty() || !selected_wire_segments.is_empty() {
                     self.selection =
-                        Selection::Component(selected_components.into_iter().next().unwrap());
-                } else if selected_components.is_empty() && (selected_wire_segments.len() == 1) {
-                    self.selection =
-                        Selection::WireSegment(selected_wire_segments.into_iter().next().unwrap());
-                } else if !selected_components.is_empty() || !selected_wire_segments.is_empty() {
-                    let bb = self
-                        .find_selection_bounding_box(&selected_components, &selected_wire_segments);
-
-                    self.selection = Selection::Multi {
-                        components: selected_components,
-                        wire_segments: selected_wire_segments,
-                        center: bb.center(),
-                    };
+                        self.selection_from_sets(selected_components, selected_wire_segments);
                 }
 
                 requires_redraw = true;
@@ -616,27 +1769,84 @@ impl Circuit {
             //   x-----------x-----------x
             //               |
             //               |
-            let dragged = match self.drag_state {
-                DragState::DraggingWirePointA { wire_segment, .. } => {
-                    Some((wire_segment, self.wire_segments[wire_segment].endpoint_a))
+            let drag_state = std::mem::take(&mut self.drag_state);
+            let dragged = match drag_state {
+                DragState::DraggingWirePointA {
+                    wire_segment,
+                    original,
+                    ..
+                } => Some((
+                    wire_segment,
+                    original,
+                    self.wire_segments[wire_segment].endpoint_a,
+                )),
+                DragState::DraggingWirePointB {
+                    wire_segment,
+                    original,
+                    ..
+                } => Some((
+                    wire_segment,
+                    original,
+                    self.wire_segments[wire_segment].endpoint_b,
+                )),
+                DragState::DraggingWireMidpoint {
+                    wire_segment,
+                    original,
+                    ..
+                } => {
+                    let dragged_segment = &mut self.wire_segments[wire_segment];
+                    dragged_segment.remove_redundant_midpoints();
+
+                    if dragged_segment.midpoints != original.midpoints {
+                        self.pending_edits.push(EditCommand::Modify {
+                            components: vec![],
+                            wire_segments: vec![(wire_segment, original, dragged_segment.clone())],
+                        });
+                    }
+
+                    requires_redraw = true;
+                    None
                 }
-                DragState::DraggingWirePointB { wire_segment, .. } => {
-                    Some((wire_segment, self.wire_segments[wire_segment].endpoint_b))
+                DragState::Dragging {
+                    total_drag_delta, ..
+                } if total_drag_delta != Vec2i::ZERO => {
+                    let (components, wire_segments) = self.selected_indices();
+                    self.pending_edits.push(EditCommand::Move {
+                        components,
+                        wire_segments,
+                        delta: total_drag_delta,
+                    });
+
+                    None
                 }
                 _ => None,
             };
-            if let Some((dragged_wire, dragged_endpoint)) = dragged {
-                if let HitTestResult::WireSegment(split_segment, split_index) =
-                    self.hit_test(dragged_endpoint.to_vec2f(), Some(dragged_wire))
+            if let Some((dragged_wire, original, dragged_endpoint)) = dragged {
+                let dragged_segment = &self.wire_segments[dragged_wire];
+                if (dragged_segment.endpoint_a == dragged_segment.endpoint_b)
+                    && dragged_segment.midpoints.is_empty()
                 {
-                    let old_split_segment = &mut self.wire_segments[split_segment];
-                    let new_split_segment =
-                        old_split_segment.split_at(split_index, dragged_endpoint);
-                    self.wire_segments.push(new_split_segment);
+                    // Zero-length segments are invisible but would still end up in the wire groups
+                    self.remove_zero_length_wire_segment(dragged_wire, original);
+                    requires_redraw = true;
+                } else {
+                    if (dragged_segment.endpoint_a != original.endpoint_a)
+                        || (dragged_segment.endpoint_b != original.endpoint_b)
+                    {
+                        self.pending_edits.push(EditCommand::Modify {
+                            components: vec![],
+                            wire_segments: vec![(dragged_wire, original, dragged_segment.clone())],
+                        });
+                    }
+
+                    self.split_at_dragged_endpoint(dragged_wire, dragged_endpoint);
                 }
             }
 
-            self.drag_state = DragState::None;
+            let pending_edits = std::mem::take(&mut self.pending_edits);
+            if !pending_edits.is_empty() {
+                self.record_edit(EditCommand::Batch(pending_edits));
+            }
         }
 
         self.primary_button_down = false;
@@ -644,24 +1854,124 @@ impl Circuit {
         requires_redraw
     }
 
-    pub fn secondary_button_pressed(&mut self, _pos: Vec2f) -> bool {
+    fn split_at_dragged_endpoint(&mut self, dragged_wire: usize, dragged_endpoint: Vec2i) {
+        match self.hit_test(dragged_endpoint.to_vec2f(), Some(dragged_wire)) {
+            HitTestResult::WireSegment(split_segment, split_index)
+            | HitTestResult::WireMidpoint(split_segment, split_index) => {
+                self.split_wire_segment(split_segment, split_index, dragged_endpoint);
+            }
+            _ => (),
+        }
+    }
+
+    fn remove_zero_length_wire_segment(&mut self, wire_segment: usize, original: WireSegment) {
+        self.wire_segments.remove(wire_segment);
+
+        // If the segment was only just drawn it doesn't have to appear in the history at all
+        let insert_edit = self.pending_edits.iter().position(|edit| match edit {
+            EditCommand::Insert(items) => {
+                items.components.is_empty()
+                    && (items.wire_segments.len() == 1)
+                    && (items.wire_segments[0].0 == wire_segment)
+            }
+            _ => false,
+        });
+
+        if let Some(insert_edit) = insert_edit {
+            self.pending_edits.remove(insert_edit);
+        } else {
+            self.pending_edits.push(EditCommand::Remove(ItemSet {
+                components: vec![],
+                wire_segments: vec![(wire_segment, original)],
+            }));
+        }
+
+        // Indices after the removed segment have shifted
+        self.selection = Selection::None;
+    }
+
+    fn split_wire_segment(&mut self, wire_segment: usize, split_index: usize, p: Vec2i) {
+        let old_split_segment = &mut self.wire_segments[wire_segment];
+        let original = old_split_segment.clone();
+        let new_split_segment = old_split_segment.split_at(split_index, p);
+
+        self.pending_edits.push(EditCommand::Modify {
+            components: vec![],
+            wire_segments: vec![(wire_segment, original, old_split_segment.clone())],
+        });
+        self.pending_edits.push(EditCommand::Insert(ItemSet {
+            components: vec![],
+            wire_segments: vec![(self.wire_segments.len(), new_split_segment.clone())],
+        }));
+
+        self.wire_segments.push(new_split_segment);
+    }
+
+    fn add_wire_segment(&mut self, endpoint_a: Vec2i, endpoint_b: Vec2i) -> (usize, WireSegment) {
+        let mut segment = WireSegment {
+            endpoint_a,
+            midpoints: smallvec![],
+            endpoint_b,
+            sim_wires: smallvec![],
+        };
+        segment.update_midpoints();
+
+        let wire_segment = self.wire_segments.len();
+        self.pending_edits.push(EditCommand::Insert(ItemSet {
+            components: vec![],
+            wire_segments: vec![(wire_segment, segment.clone())],
+        }));
+        self.wire_segments.push(segment.clone());
+
+        (wire_segment, segment)
+    }
+
+    /// Push buttons only drive a 1 for as long as they are held down
+    fn release_push_button(&mut self, max_steps: u64) -> bool {
+        let Some(push_button) = self.held_push_button.take() else {
+            return false;
+        };
+
+        let sim_state = std::mem::take(&mut self.sim_state);
+        if let SimState::Active {
+            mut sim,
+            clock_state,
+        } = sim_state
+        {
+            if let ComponentKind::PushButton { sim_wire, .. } = self.components[push_button].kind {
+                sim.set_wire_drive(sim_wire, &gsim::LogicState::LOGIC_0)
+                    .unwrap();
+            }
+
+            self.advance_simulation(sim, clock_state, max_steps);
+            true
+        } else {
+            self.sim_state = sim_state;
+            false
+        }
+    }
+
+    pub fn secondary_button_pressed(&mut self, _logical_pos: Vec2f) -> bool {
         self.secondary_button_down = true;
         false
     }
 
-    pub fn secondary_button_released(&mut self, pos: Vec2f) -> bool {
+    pub fn secondary_button_released(&mut self, logical_pos: Vec2f) -> bool {
         let mut requires_redraw = false;
 
         if self.secondary_button_down {
-            let logical_pos = pos / (self.zoom * BASE_ZOOM) + self.offset;
             let hit = self.hit_test(logical_pos, None);
 
-            match hit {
+            let target = match hit {
                 HitTestResult::None => {
                     if !matches!(self.selection, Selection::None) {
                         self.selection = Selection::None;
                         requires_redraw = true;
                     }
+
+                    ContextMenuTarget::Empty {
+                        position: logical_pos.round().to_vec2i(),
+                    }
                 }
                 HitTestResult::Component(component) | HitTestResult::ComponentAnchor(component) => {
                     if !self.selection.contains_component(component) {
@@ -669,19 +1979,24 @@ impl Circuit {
                         requires_redraw = true;
                     }
 
-                    // TODO: show context menu
+                    ContextMenuTarget::Component
                 }
                 HitTestResult::WireSegment(wire_segment, _)
                 | HitTestResult::WirePointA(wire_segment)
-                | HitTestResult::WirePointB(wire_segment) => {
+                | HitTestResult::WirePointB(wire_segment)
+                | HitTestResult::WireMidpoint(wire_segment, _) => {
                     if !self.selection.contains_wire_segment(wire_segment) {
                         self.selection = Selection::WireSegment(wire_segment);
                         requires_redraw = true;
                     }
 
-                    // TODO: show context menu
+                    ContextMenuTarget::WireSegment
                 }
-            }
+            };
+
+            // The circuit cannot be edited while a simulation is running
+            self.context_menu_target =
+                is_discriminant!(self.sim_state, SimState::None).then_some(target);
 
             self.drag_state = DragState::None;
         }
@@ -692,6 +2007,24 @@ impl Circuit {
     }
 
     pub fn move_selection(&mut self, delta: Vec2i) {
+        if delta == Vec2i::ZERO {
+            return;
+        }
+
+        let (components, wire_segments) = self.selected_indices();
+        if components.is_empty() && wire_segments.is_empty() {
+            return;
+        }
+
+        self.translate_selection(delta);
+        self.record_edit(EditCommand::Move {
+            components,
+            wire_segments,
+            delta,
+        });
+    }
+
+    fn translate_selection(&mut self, delta: Vec2i) {
         match self.selection {
             Selection::None => {}
             Selection::Component(component) => {
@@ -746,12 +2079,20 @@ impl Circuit {
         }
     }
 
-    pub fn mouse_moved(&mut self, delta: Vec2f, drag_mode: DragMode) -> bool {
+    /// If `snap_angle` is set, newly drawn wires are constrained to horizontal, vertical and diagonal directions.
+    /// Dragged selections move in multiples of `snap_step` grid units.
+    pub fn mouse_moved(
+        &mut self,
+        delta: Vec2f,
+        drag_mode: DragMode,
+        snap_angle: bool,
+        snap_step: i32,
+    ) -> Redraw {
         const DEADZONE_RANGE: f32 = 0.8;
 
         if self.primary_button_down && !self.secondary_button_down {
             match &mut self.drag_state {
-                DragState::None => false,
+                DragState::None => Redraw::None,
                 DragState::Deadzone {
                     drag_start,
                     drag_delta,
@@ -775,20 +2116,12 @@ impl Circuit {
                             | (HitTestResult::ComponentAnchor(_), DragMode::DrawWire) => {
                                 let endpoint_a = drag_start.round().to_vec2i();
                                 let endpoint_b = (drag_start + drag_delta).round().to_vec2i();
-
-                                let mut segment = WireSegment {
-                                    endpoint_a,
-                                    midpoints: smallvec![],
-                                    endpoint_b,
-                                    sim_wires: smallvec![],
-                                };
-                                segment.update_midpoints();
-
-                                let wire_segment = self.wire_segments.len();
-                                self.wire_segments.push(segment);
+                                let (wire_segment, original) =
+                                    self.add_wire_segment(endpoint_a, endpoint_b);
 
                                 DragState::DraggingWirePointB {
                                     wire_segment,
+                                    original,
                                     drag_start,
                                     drag_delta,
                                 }
@@ -804,10 +2137,11 @@ impl Circuit {
                                 // TODO: already drag whole part of delta
                                 DragState::Dragging {
                                     fract_drag_delta: drag_delta,
+                                    total_drag_delta: Vec2i::ZERO,
                                 }
                             }
                             (
-                                HitTestResult::WireSegment(wire_segment, _),
+                                HitTestResult::WireSegment(wire_segment, split_index),
                                 DragMode::BoxSelection,
                             ) => {
                                 assert!(
@@ -815,14 +2149,33 @@ impl Circuit {
                                     "invalid drag state"
                                 );
 
-                                // TODO: already drag whole part of delta
-                                DragState::Dragging {
-                                    fract_drag_delta: drag_delta,
+                                if is_discriminant!(self.selection, Selection::Multi) {
+                                    // TODO: already drag whole part of delta
+                                    DragState::Dragging {
+                                        fract_drag_delta: drag_delta,
+                                        total_drag_delta: Vec2i::ZERO,
+                                    }
+                                } else {
+                                    // Dragging a single wire by its body inserts a new bend
+                                    let original = self.wire_segments[wire_segment].clone();
+                                    let new_midpoint = (drag_start + drag_delta).round().to_vec2i();
+                                    self.wire_segments[wire_segment]
+                                        .midpoints
+                                        .insert(split_index, new_midpoint);
+
+                                    DragState::DraggingWireMidpoint {
+                                        wire_segment,
+                                        midpoint: split_index,
+                                        original,
+                                        drag_start,
+                                        drag_delta,
+                                    }
                                 }
                             }
                             (HitTestResult::WirePointA(wire_segment), DragMode::BoxSelection) => {
                                 DragState::DraggingWirePointA {
                                     wire_segment,
+                                    original: self.wire_segments[wire_segment].clone(),
                                     drag_start,
                                     drag_delta,
                                 }
@@ -830,35 +2183,39 @@ impl Circuit {
                             (HitTestResult::WirePointB(wire_segment), DragMode::BoxSelection) => {
                                 DragState::DraggingWirePointB {
                                     wire_segment,
+                                    original: self.wire_segments[wire_segment].clone(),
                                     drag_start,
                                     drag_delta,
                                 }
                             }
+                            (
+                                HitTestResult::WireMidpoint(wire_segment, midpoint),
+                                DragMode::BoxSelection,
+                            ) => DragState::DraggingWireMidpoint {
+                                wire_segment,
+                                midpoint,
+                                original: self.wire_segments[wire_segment].clone(),
+                                drag_start,
+                                drag_delta,
+                            },
                             (
                                 HitTestResult::WireSegment(wire_segment, split_index),
                                 DragMode::DrawWire,
+                            )
+                            | (
+                                HitTestResult::WireMidpoint(wire_segment, split_index),
+                                DragMode::DrawWire,
                             ) => {
                                 let endpoint_a = drag_start.round().to_vec2i();
                                 let endpoint_b = (drag_start + drag_delta).round().to_vec2i();
 
-                                let old_split_segment = &mut self.wire_segments[wire_segment];
-                                let new_split_segment =
-                                    old_split_segment.split_at(split_index, endpoint_a);
-                                self.wire_segments.push(new_split_segment);
-
-                                let mut segment = WireSegment {
-                                    endpoint_a,
-                                    midpoints: smallvec![],
-                                    endpoint_b,
-                                    sim_wires: smallvec![],
-                                };
-                                segment.update_midpoints();
-
-                                let wire_segment = self.wire_segments.len();
-                                self.wire_segments.push(segment);
+                                self.split_wire_segment(wire_segment, split_index, endpoint_a);
+                                let (wire_segment, original) =
+                                    self.add_wire_segment(endpoint_a, endpoint_b);
 
                                 DragState::DraggingWirePointB {
                                     wire_segment,
+                                    original,
                                     drag_start,
                                     drag_delta,
                                 }
@@ -867,59 +2224,57 @@ impl Circuit {
                             | (HitTestResult::WirePointB(_), DragMode::DrawWire) => {
                                 let endpoint_a = drag_start.round().to_vec2i();
                                 let endpoint_b = (drag_start + drag_delta).round().to_vec2i();
-
-                                let mut segment = WireSegment {
-                                    endpoint_a,
-                                    midpoints: smallvec![],
-                                    endpoint_b,
-                                    sim_wires: smallvec![],
-                                };
-                                segment.update_midpoints();
-
-                                let wire_segment = self.wire_segments.len();
-                                self.wire_segments.push(segment);
+                                let (wire_segment, original) =
+                                    self.add_wire_segment(endpoint_a, endpoint_b);
 
                                 DragState::DraggingWirePointB {
                                     wire_segment,
+                                    original,
                                     drag_start,
                                     drag_delta,
                                 }
                             }
                         };
 
-                        true
+                        Redraw::Full
                     } else {
-                        false
+                        Redraw::None
                     }
                 }
                 DragState::DrawingBoxSelection { drag_delta, .. } => {
                     *drag_delta += delta;
-                    true
+                    Redraw::Overlay
                 }
                 DragState::DraggingWirePointA {
                     wire_segment,
                     drag_start,
                     drag_delta,
+                    ..
                 } => {
                     *drag_delta += delta;
 
+                    let new_a = *drag_start + *drag_delta;
+                    let new_a = snap_to_anchor(&self.components, new_a)
+                        .unwrap_or_else(|| new_a.round().to_vec2i());
+
                     let wire_segment = self
                         .wire_segments
                         .get_mut(*wire_segment)
                         .expect("invalid drag state");
 
-                    let new_a = (*drag_start + *drag_delta).round().to_vec2i();
                     if wire_segment.endpoint_a != new_a {
                         wire_segment.endpoint_a = new_a;
                         wire_segment.update_midpoints();
+                        Redraw::Full
+                    } else {
+                        Redraw::None
                     }
-
-                    true
                 }
                 DragState::DraggingWirePointB {
                     wire_segment,
                     drag_start,
                     drag_delta,
+                    ..
                 } => {
                     *drag_delta += delta;
 
@@ -928,35 +2283,92 @@ impl Circuit {
                         .get_mut(*wire_segment)
                         .expect("invalid drag state");
 
-                    let new_b = (*drag_start + *drag_delta).round().to_vec2i();
+                    // Connecting to an anchor takes precedence over keeping the angle
+                    let new_b = *drag_start + *drag_delta;
+                    let new_b = if let Some(anchor) = snap_to_anchor(&self.components, new_b) {
+                        anchor
+                    } else if snap_angle {
+                        snap_to_octant(wire_segment.endpoint_a, new_b)
+                    } else {
+                        new_b.round().to_vec2i()
+                    };
+
                     if wire_segment.endpoint_b != new_b {
                         wire_segment.endpoint_b = new_b;
                         wire_segment.update_midpoints();
+                        Redraw::Full
+                    } else {
+                        Redraw::None
                     }
+                }
+                DragState::DraggingWireMidpoint {
+                    wire_segment,
+                    midpoint,
+                    drag_start,
+                    drag_delta,
+                    ..
+                } => {
+                    *drag_delta += delta;
 
-                    true
+                    let wire_segment = self
+                        .wire_segments
+                        .get_mut(*wire_segment)
+                        .expect("invalid drag state");
+
+                    // Only the bend moves, the endpoints stay where they are
+                    let new_midpoint = (*drag_start + *drag_delta).round().to_vec2i();
+                    if wire_segment.midpoints[*midpoint] != new_midpoint {
+                        wire_segment.midpoints[*midpoint] = new_midpoint;
+                        Redraw::Full
+                    } else {
+                        Redraw::None
+                    }
                 }
-                DragState::Dragging { fract_drag_delta } => {
+                DragState::Dragging {
+                    fract_drag_delta,
+                    total_drag_delta,
+                } => {
                     assert!(
                         !is_discriminant!(self.selection, Selection::None),
                         "invalid drag state"
                     );
 
+                    let snap_step = snap_step.max(1) as f32;
+
                     *fract_drag_delta += delta;
-                    let whole_drag_delta = fract_drag_delta.round();
+                    let whole_drag_delta = (*fract_drag_delta / snap_step).round() * snap_step;
                     *fract_drag_delta -= whole_drag_delta;
 
                     let whole_drag_delta = whole_drag_delta.to_vec2i();
                     if whole_drag_delta != Vec2i::ZERO {
-                        self.move_selection(whole_drag_delta);
-                        true
+                        *total_drag_delta += whole_drag_delta;
+                        self.translate_selection(whole_drag_delta);
+                        Redraw::Full
                     } else {
-                        false
+                        Redraw::None
                     }
                 }
             }
         } else {
-            false
+            Redraw::None
+        }
+    }
+
+    /// Recomputes the center of a multi-selection from the current positions of its items
+    fn update_selection_center(&mut self) {
+        if let Selection::Multi {
+            components,
+            wire_segments,
+            ..
+        } = &self.selection
+        {
+            let new_center = self
+                .find_selection_bounding_box(components, wire_segments)
+                .center();
+
+            if let Selection::Multi { center, .. } = &mut self.selection {
+                *center = new_center;
+            }
         }
     }
 
@@ -965,14 +2377,15 @@ impl Circuit {
         components: &HashSet<usize>,
         wire_segments: &HashSet<usize>,
     ) -> Rectangle {
-        let mut min = Vec2i::new(i32::MAX, i32::MAX);
-        let mut max = Vec2i::new(i32::MIN, i32::MIN);
+        let mut bb: Option<Rectangle> = None;
+        let mut add_point = |p: Vec2i| {
+            let p = Rectangle::from_points(p.to_vec2f(), p.to_vec2f());
+            bb = Some(bb.map_or(p, |bb| bb.union(&p)));
+        };
 
         for &component in components {
             let component = self.components.get(component).expect("invalid selection");
-
-            min = min.min(component.position());
-            max = max.max(component.position());
+            add_point(component.position());
         }
 
         for &wire_segment in wire_segments {
@@ -981,32 +2394,27 @@ impl Circuit {
                 .get(wire_segment)
                 .expect("invalid selection");
 
-            min = min.min(wire_segment.endpoint_a);
-            max = max.max(wire_segment.endpoint_a);
-
-            min = min.min(wire_segment.endpoint_b);
-            max = max.max(wire_segment.endpoint_b);
-
-            for p in wire_segment.midpoints.iter() {
-                min = min.min(*p);
-                max = max.max(*p);
+            add_point(wire_segment.endpoint_a);
+            add_point(wire_segment.endpoint_b);
+            for &p in wire_segment.midpoints.iter() {
+                add_point(p);
             }
         }
 
-        Rectangle {
-            top: max.y as f32,
-            bottom: min.y as f32,
-            left: min.x as f32,
-            right: max.x as f32,
-        }
+        bb.unwrap_or(Rectangle::from_points(Vec2f::ZERO, Vec2f::ZERO))
     }
 
     fn transform_selection(
         &mut self,
         apply_mirror: impl Fn(bool) -> bool,
         apply_rot: impl Fn(Rotation) -> Rotation,
-        apply_pt: impl Fn(Vec2f) -> Vec2f,
+        apply_pt: impl Fn(Vec2i) -> Vec2i,
     ) {
+        let before = self.snapshot_selection();
+        if before.is_empty() {
+            return;
+        }
+
         match self.selection {
             Selection::None => {}
             Selection::Component(component) => {
@@ -1024,31 +2432,32 @@ impl Circuit {
                     .get_mut(wire_segment)
                     .expect("invalid selection");
 
-                let center = (wire_segment.endpoint_a + wire_segment.endpoint_b).to_vec2f() * 0.5;
-
-                let a = wire_segment.endpoint_a.to_vec2f() - center;
-                let b = wire_segment.endpoint_b.to_vec2f() - center;
-                wire_segment.endpoint_a = (apply_pt(a) + center).floor().to_vec2i();
-                wire_segment.endpoint_b = (apply_pt(b) + center).floor().to_vec2i();
+                // Pivoting around a grid point keeps every transformed point on the grid,
+                // so repeated rotations don't drift
+                let center = wire_segment
+                    .endpoint_a
+                    .to_vec2f()
+                    .lerp(wire_segment.endpoint_b.to_vec2f(), 0.5)
+                    .floor()
+                    .to_vec2i();
 
-                for p in wire_segment.midpoints.iter_mut() {
-                    let rp = p.to_vec2f() - center;
-                    *p = (apply_pt(rp) + center).floor().to_vec2i();
-                }
+                wire_segment.transform_points(center, &apply_pt);
             }
             Selection::Multi {
                 ref components,
                 ref wire_segments,
                 center,
             } => {
+                let center = center.floor().to_vec2i();
+
                 for &component in components {
                     let component = self
                         .components
                         .get_mut(component)
                         .expect("invalid selection");
 
-                    let pos = component.position().to_vec2f() - center;
-                    component.set_position((apply_pt(pos) + center).floor().to_vec2i());
+                    let pos = component.position() - center;
+                    component.set_position(apply_pt(pos) + center);
                     component.mirrored = apply_mirror(component.mirrored);
                     component.rotation = apply_rot(component.rotation);
                 }
@@ -1059,57 +2468,439 @@ impl Circuit {
                         .get_mut(wire_segment)
                         .expect("invalid selection");
 
-                    let a = wire_segment.endpoint_a.to_vec2f() - center;
-                    let b = wire_segment.endpoint_b.to_vec2f() - center;
-                    wire_segment.endpoint_a = (apply_pt(a) + center).floor().to_vec2i();
-                    wire_segment.endpoint_b = (apply_pt(b) + center).floor().to_vec2i();
+                    wire_segment.transform_points(center, &apply_pt);
+                }
+            }
+        }
+
+        // The pivot is snapped to the grid, so the transformed geometry is generally not
+        // centered on it anymore
+        self.update_selection_center();
+
+        let ItemSet {
+            components,
+            wire_segments,
+        } = before;
+
+        let components = components
+            .into_iter()
+            .map(|(i, old)| (i, old, self.components[i].clone()))
+            .collect();
+        let wire_segments = wire_segments
+            .into_iter()
+            .map(|(i, old)| (i, old, self.wire_segments[i].clone()))
+            .collect();
+
+        self.record_edit(EditCommand::Modify {
+            components,
+            wire_segments,
+        });
+    }
+
+    /// Rotates the selection counterclockwise
+    #[inline]
+    pub fn rotate_selection(&mut self) {
+        self.counterclockwise_rotate_selection();
+    }
+
+    pub fn counterclockwise_rotate_selection(&mut self) {
+        self.transform_selection(std::convert::identity, Rotation::next, Vec2i::rotate_90_ccw);
+    }
+
+    pub fn clockwise_rotate_selection(&mut self) {
+        self.transform_selection(std::convert::identity, Rotation::prev, Vec2i::rotate_90_cw);
+    }
+
+    pub fn mirror_selection(&mut self) {
+        self.transform_selection(std::ops::Not::not, Rotation::mirror, Vec2i::mirror_x);
+    }
+
+    /// Moves the selected components along `axis` by the given amounts
+    fn shift_selected_components(&mut self, axis: Axis, shifts: Vec<(usize, i32)>) {
+        let mut components = Vec::new();
+        for (i, shift) in shifts.into_iter().filter(|&(_, shift)| shift != 0) {
+            let delta = match axis {
+                Axis::Horizontal => Vec2i::new(shift, 0),
+                Axis::Vertical => Vec2i::new(0, shift),
+            };
+
+            let old = self.components[i].clone();
+            self.components[i].set_position(old.position() + delta);
+            components.push((i, old, self.components[i].clone()));
+        }
+
+        if components.is_empty() {
+            return;
+        }
+
+        self.update_selection_center();
+        self.record_edit(EditCommand::Modify {
+            components,
+            wire_segments: Vec::new(),
+        });
+    }
+
+    /// Lines up the bounding boxes of all selected components along `axis`.
+    /// Wire segments in the selection are left in place.
+    pub fn align_selection(&mut self, axis: Axis, mode: AlignMode) {
+        let (components, _) = self.selected_indices();
+        if components.len() < 2 {
+            return;
+        }
+
+        let extents: Vec<_> = components
+            .iter()
+            .map(|&i| rect_extent(&self.components[i].bounding_box(), axis))
+            .collect();
+
+        let target = match mode {
+            AlignMode::Start => extents.iter().map(|e| e.0).fold(f32::MAX, f32::min),
+            AlignMode::Center => {
+                let start = extents.iter().map(|e| e.0).fold(f32::MAX, f32::min);
+                let end = extents.iter().map(|e| e.2).fold(f32::MIN, f32::max);
+                (start + end) * 0.5
+            }
+            AlignMode::End => extents.iter().map(|e| e.2).fold(f32::MIN, f32::max),
+        };
+
+        let shifts = components
+            .into_iter()
+            .zip(extents)
+            .map(|(i, (start, center, end))| {
+                let current = match mode {
+                    AlignMode::Start => start,
+                    AlignMode::Center => center,
+                    AlignMode::End => end,
+                };
+
+                (i, (target - current).round() as i32)
+            })
+            .collect();
+
+        self.shift_selected_components(axis, shifts);
+    }
+
+    /// Spaces the centers of all selected components evenly along `axis`,
+    /// keeping the outermost two in place
+    pub fn distribute_selection(&mut self, axis: Axis) {
+        let (components, _) = self.selected_indices();
+        if components.len() < 3 {
+            return;
+        }
+
+        let mut centers: Vec<_> = components
+            .into_iter()
+            .map(|i| (i, rect_extent(&self.components[i].bounding_box(), axis).1))
+            .collect();
+        centers.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        let first = centers[0].1;
+        let last = centers[centers.len() - 1].1;
+        let spacing = (last - first) / ((centers.len() - 1) as f32);
+
+        let shifts = centers
+            .into_iter()
+            .enumerate()
+            .map(|(n, (i, center))| {
+                let target = first + spacing * (n as f32);
+                (i, (target - center).round() as i32)
+            })
+            .collect();
+
+        self.shift_selected_components(axis, shifts);
+    }
+
+    /// Creates an orthogonal wire from `from` to `to` that goes around all components.
+    /// Falls back to a single bend if there is no such route close by.
+    pub fn route_wire(&self, from: Vec2i, to: Vec2i) -> WireSegment {
+        let obstacles: Vec<_> = self
+            .components
+            .iter()
+            .map(Component::bounding_box)
+            .collect();
+
+        let mut segment = WireSegment {
+            endpoint_a: from,
+            midpoints: smallvec![],
+            endpoint_b: to,
+            sim_wires: smallvec![],
+        };
+
+        if let Some(corners) = find_route(from, to, &obstacles) {
+            segment.midpoints = corners.into();
+        } else {
+            segment.update_midpoints();
+        }
+
+        segment
+    }
+
+    /// Replaces the bends of all selected wire segments with automatically routed ones
+    pub fn auto_route_selection(&mut self) {
+        let (_, selected_wire_segments) = self.selected_indices();
+
+        let mut wire_segments = Vec::new();
+        for i in selected_wire_segments {
+            let old = self.wire_segments[i].clone();
+            let routed = self.route_wire(old.endpoint_a, old.endpoint_b);
+            if routed.midpoints == old.midpoints {
+                continue;
+            }
+
+            self.wire_segments[i].midpoints = routed.midpoints;
+            wire_segments.push((i, old, self.wire_segments[i].clone()));
+        }
+
+        if wire_segments.is_empty() {
+            return;
+        }
+
+        self.update_selection_center();
+        self.record_edit(EditCommand::Modify {
+            components: Vec::new(),
+            wire_segments,
+        });
+    }
+
+    pub fn select_all(&mut self) {
+        let components: HashSet<_> = (0..self.components.len()).collect();
+        let wire_segments: HashSet<_> = (0..self.wire_segments.len()).collect();
+
+        if components.is_empty() && wire_segments.is_empty() {
+            self.selection = Selection::None;
+        } else {
+            let bb = self.find_selection_bounding_box(&components, &wire_segments);
+            self.selection = Selection::Multi {
+                components,
+                wire_segments,
+                center: bb.center(),
+            };
+        }
+    }
+
+    #[inline]
+    pub fn clear_selection(&mut self) {
+        self.selection = Selection::None;
+    }
+
+    pub fn delete_selection(&mut self) {
+        let removed = self.snapshot_selection();
+        if removed.is_empty() {
+            return;
+        }
+
+        let command = EditCommand::Remove(removed);
+        command.apply(
+            &mut self.components,
+            &mut self.wire_segments,
+            &mut AffectedItems::default(),
+        );
+
+        if let EditCommand::Remove(removed) = &command {
+            self.remap_indices(removed);
+        }
+        self.record_edit(command);
+    }
+
+    /// Components and wire segments are identified by their index everywhere, and removing items
+    /// shifts all items after them. This updates every stored index after `removed` was taken
+    /// out of the circuit, dropping references to the removed items themselves.
+    fn remap_indices(&mut self, removed: &ItemSet) {
+        let (components, wire_segments) = self.selected_indices();
+        let components = components
+            .into_iter()
+            .filter_map(|i| removed.remap_component_index(i))
+            .collect();
+        let wire_segments = wire_segments
+            .into_iter()
+            .filter_map(|i| removed.remap_wire_segment_index(i))
+            .collect();
+        self.selection = self.selection_from_sets(components, wire_segments);
+
+        self.held_push_button = self
+            .held_push_button
+            .and_then(|i| removed.remap_component_index(i));
+        self.edited_input = self
+            .edited_input
+            .and_then(|i| removed.remap_component_index(i));
+
+        // Drags hold on to wire segment indices and a snapshot of the original segment
+        self.drag_state = DragState::None;
+    }
+
+    pub fn copy_selection(&self) -> Option<ClipboardData> {
+        let (components, wire_segments) = self.selected_indices();
+        if components.is_empty() && wire_segments.is_empty() {
+            return None;
+        }
+
+        let bb = self.find_selection_bounding_box(
+            &components.iter().copied().collect(),
+            &wire_segments.iter().copied().collect(),
+        );
+        let center = bb.center().round().to_vec2i();
+
+        let components = components
+            .into_iter()
+            .map(|i| {
+                let mut component = self.components[i].clone();
+                component.set_position(component.position() - center);
+                component
+            })
+            .collect();
+
+        let wire_segments = wire_segments
+            .into_iter()
+            .map(|i| {
+                let mut wire_segment = self.wire_segments[i].clone();
+                wire_segment.endpoint_a -= center;
+                wire_segment.endpoint_b -= center;
+                for p in wire_segment.midpoints.iter_mut() {
+                    *p -= center;
+                }
+                wire_segment
+            })
+            .collect();
+
+        Some(ClipboardData {
+            components,
+            wire_segments,
+        })
+    }
+
+    pub fn paste(&mut self, data: &ClipboardData, at: Vec2i) {
+        self.insert_copies(&data.components, &data.wire_segments, at);
+    }
+
+    /// Inserts copies of the selected items, moved by `offset`, and selects the copies
+    pub fn duplicate_selection(&mut self, offset: Vec2i) {
+        let (components, wire_segments) = self.selected_indices();
+        let components: Vec<_> = components
+            .into_iter()
+            .map(|i| self.components[i].clone())
+            .collect();
+        let wire_segments: Vec<_> = wire_segments
+            .into_iter()
+            .map(|i| self.wire_segments[i].clone())
+            .collect();
+
+        self.insert_copies(&components, &wire_segments, offset);
+
+        // Duplicating a single component behaves like adding a new one
+        if let Selection::Multi {
+            components,
+            wire_segments,
+            ..
+        } = &self.selection
+        {
+            if (components.len() == 1) && wire_segments.is_empty() {
+                let component = components.iter().copied().next().unwrap();
+                self.selection = Selection::Component(component);
+            }
+        }
+    }
+
+    fn insert_copies(
+        &mut self,
+        components: &[Component],
+        wire_segments: &[WireSegment],
+        at: Vec2i,
+    ) {
+        let mut inserted = ItemSet::default();
+
+        for component in components {
+            let mut component = component.clone();
+            component.set_position(component.position() + at);
+            component.kind.reset_sim_ids();
+
+            let index = self.components.len() + inserted.components.len();
+            inserted.components.push((index, component));
+        }
+
+        for wire_segment in wire_segments {
+            let mut wire_segment = wire_segment.clone();
+            wire_segment.endpoint_a += at;
+            wire_segment.endpoint_b += at;
+            for p in wire_segment.midpoints.iter_mut() {
+                *p += at;
+            }
+            wire_segment.sim_wires.clear();
+
+            let index = self.wire_segments.len() + inserted.wire_segments.len();
+            inserted.wire_segments.push((index, wire_segment));
+        }
 
-                    for p in wire_segment.midpoints.iter_mut() {
-                        let rp = p.to_vec2f() - center;
-                        *p = (apply_pt(rp) + center).floor().to_vec2i();
-                    }
-                }
-            }
+        if inserted.is_empty() {
+            return;
         }
-    }
 
-    pub fn counterclockwise_rotate_selection(&mut self) {
-        self.transform_selection(std::convert::identity, Rotation::next, |v| {
-            Vec2f::new(-v.y, v.x)
-        });
-    }
+        let components: HashSet<_> = inserted.components.iter().map(|(i, _)| *i).collect();
+        let wire_segments: HashSet<_> = inserted.wire_segments.iter().map(|(i, _)| *i).collect();
 
-    pub fn clockwise_rotate_selection(&mut self) {
-        self.transform_selection(std::convert::identity, Rotation::prev, |v| {
-            Vec2f::new(v.y, -v.x)
-        });
-    }
+        let command = EditCommand::Insert(inserted);
+        command.apply(
+            &mut self.components,
+            &mut self.wire_segments,
+            &mut AffectedItems::default(),
+        );
+        self.record_edit(command);
 
-    pub fn mirror_selection(&mut self) {
-        self.transform_selection(std::ops::Not::not, Rotation::mirror, |v| {
-            Vec2f::new(-v.x, v.y)
-        });
+        let bb = self.find_selection_bounding_box(&components, &wire_segments);
+        self.selection = Selection::Multi {
+            components,
+            wire_segments,
+            center: bb.center(),
+        };
+        self.drag_state = DragState::None;
     }
 
-    pub fn delete_selection(&mut self) {
-        let mut i = 0;
-        self.components.retain(|_| {
-            let in_selection = self.selection.contains_component(i);
-            i += 1;
-            !in_selection
-        });
+    pub fn update_component_properties(
+        &mut self,
+        ui: &mut egui::Ui,
+        locale_manager: &LocaleManager,
+        lang: &LangId,
+    ) -> bool {
+        if is_discriminant!(self.selection, Selection::None) {
+            return false;
+        }
 
-        let mut i = 0;
-        self.wire_segments.retain(|_| {
-            let in_selection = self.selection.contains_wire_segment(i);
-            i += 1;
-            !in_selection
+        let mut changed = self.update_selection_properties(ui, locale_manager, lang);
+
+        // Unlike the rotation property these also work for wires and multiple components
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui
+                .button(locale_manager.get(lang, "rotate-ccw-menu-item"))
+                .on_hover_text("R")
+                .clicked()
+            {
+                self.counterclockwise_rotate_selection();
+                changed = true;
+            }
+
+            if ui
+                .button(locale_manager.get(lang, "rotate-cw-menu-item"))
+                .on_hover_text("Shift+R")
+                .clicked()
+            {
+                self.clockwise_rotate_selection();
+                changed = true;
+            }
+
+            if ui
+                .button(locale_manager.get(lang, "mirror-menu-item"))
+                .on_hover_text("M")
+                .clicked()
+            {
+                self.mirror_selection();
+                changed = true;
+            }
         });
 
-        self.selection = Selection::None;
+        changed
     }
 
-    pub fn update_component_properties(
+    fn update_selection_properties(
         &mut self,
         ui: &mut egui::Ui,
         locale_manager: &LocaleManager,
@@ -1119,12 +2910,37 @@ impl Circuit {
             Selection::None => false,
             &Selection::Component(selected_component) => {
                 ui.heading(locale_manager.get(lang, "properties-header"));
-                self.components[selected_component].update_properties(ui, locale_manager, lang)
+
+                let original = self.components[selected_component].clone();
+                let changed =
+                    self.components[selected_component].update_properties(ui, locale_manager, lang);
+
+                if changed {
+                    self.modified = true;
+                    self.net_widths = None;
+                    self.property_edit
+                        .get_or_insert((selected_component, original));
+                }
+
+                // Dragged values change on every frame, the whole drag becomes a single edit
+                // once the button is released
+                if !ui.input(|state| state.pointer.any_down()) {
+                    if let Some((i, original)) = self.property_edit.take() {
+                        let modified = self.components[i].clone();
+                        self.record_edit(EditCommand::Modify {
+                            components: vec![(i, original, modified)],
+                            wire_segments: vec![],
+                        });
+                    }
+                }
+
+                changed
             }
             &Selection::WireSegment(selected_segment) => {
                 ui.heading(locale_manager.get(lang, "properties-header"));
 
                 let segment = &mut self.wire_segments[selected_segment];
+                let original = segment.clone();
                 let mut needs_midpoint_update = false;
 
                 ui.horizontal(|ui| {
@@ -1181,11 +2997,53 @@ impl Circuit {
 
                 if needs_midpoint_update {
                     segment.update_midpoints();
+
+                    let modified = segment.clone();
+                    self.record_edit(EditCommand::Modify {
+                        components: vec![],
+                        wire_segments: vec![(selected_segment, original, modified)],
+                    });
                 }
 
                 needs_midpoint_update
             }
-            Selection::Multi { .. } => false,
+            Selection::Multi { .. } => {
+                let (components, _) = self.selected_indices();
+                if components.is_empty() {
+                    return false;
+                }
+
+                ui.heading(locale_manager.get(lang, "properties-header"));
+
+                let originals: Vec<_> = components
+                    .iter()
+                    .map(|&i| self.components[i].clone())
+                    .collect();
+
+                let changed = update_shared_properties(
+                    &mut self.components,
+                    &components,
+                    &mut self.shared_width_buffer,
+                    ui,
+                    locale_manager,
+                    lang,
+                );
+
+                if changed {
+                    let modified = components
+                        .iter()
+                        .zip(originals)
+                        .map(|(&i, original)| (i, original, self.components[i].clone()))
+                        .collect();
+
+                    self.record_edit(EditCommand::Modify {
+                        components: modified,
+                        wire_segments: vec![],
+                    });
+                }
+
+                changed
+            }
         }
     }
 
@@ -1233,15 +3091,87 @@ impl Circuit {
             }
         }
 
-        let group_map = group_map
+        let group_map: Vec<_> = group_map
             .into_iter()
             .map(|i| i.expect("wire with no group"))
             .collect();
 
-        (groups, group_map)
+        self.merge_tunnel_groups(groups, group_map)
+    }
+
+    /// Tunnels with the same name connect their nets without a drawn wire
+    fn merge_tunnel_groups(
+        &self,
+        groups: Vec<Vec<usize>>,
+        group_map: Vec<usize>,
+    ) -> (Vec<Vec<usize>>, Vec<usize>) {
+        fn find_root(parents: &mut [usize], mut group: usize) -> usize {
+            while parents[group] != group {
+                parents[group] = parents[parents[group]];
+                group = parents[group];
+            }
+            group
+        }
+
+        let mut parents: Vec<_> = (0..groups.len()).collect();
+        let mut tunnel_groups: HashMap<&str, usize> = HashMap::default();
+        for component in &self.components {
+            let ComponentKind::Tunnel { name, .. } = &component.kind else {
+                continue;
+            };
+
+            // Unnamed tunnels are not connected to anything
+            if name.is_empty() {
+                continue;
+            }
+
+            let anchor = component.anchors()[0].position;
+            let Some(segment) = self.wire_segments.iter().position(|segment| {
+                (segment.endpoint_a == anchor) || (segment.endpoint_b == anchor)
+            }) else {
+                continue;
+            };
+
+            let group = group_map[segment];
+            if let Some(&other_group) = tunnel_groups.get(name.as_str()) {
+                let root = find_root(&mut parents, group);
+                let other_root = find_root(&mut parents, other_group);
+                parents[root] = other_root;
+            } else {
+                tunnel_groups.insert(name, group);
+            }
+        }
+
+        if tunnel_groups.is_empty() {
+            return (groups, group_map);
+        }
+
+        let mut merged_groups: Vec<Vec<usize>> = Vec::new();
+        let mut merged_indices = vec![None; groups.len()];
+        for (i, group) in groups.into_iter().enumerate() {
+            let root = find_root(&mut parents, i);
+            let merged_index = *merged_indices[root].get_or_insert_with(|| {
+                merged_groups.push(Vec::new());
+                merged_groups.len() - 1
+            });
+            merged_groups[merged_index].extend(group);
+        }
+
+        let mut merged_map = vec![0; group_map.len()];
+        for (i, group) in merged_groups.iter().enumerate() {
+            for &segment in group {
+                merged_map[segment] = i;
+            }
+        }
+
+        (merged_groups, merged_map)
     }
 
-    fn find_wire_group_widths(&self, groups: &[Vec<usize>]) -> Result<Vec<NonZeroU8>, ()> {
+    /// On failure, returns the indices of all wire segments that are part of a width conflict.
+    fn find_wire_group_widths(
+        &self,
+        groups: &[Vec<usize>],
+    ) -> Result<Vec<NonZeroU8>, HashSet<usize>> {
         fn find_segment_width(
             segment: &WireSegment,
             components: &[Component],
@@ -1264,35 +3194,70 @@ impl Circuit {
             Ok(segment_width)
         }
 
-        groups
-            .iter()
-            .map(|group| {
-                let mut group_width = None;
-                for segment in group.iter().map(|&i| &self.wire_segments[i]) {
-                    let segment_width = find_segment_width(segment, &self.components)?;
-
-                    match (group_width, segment_width) {
-                        (_, None) => (),
-                        (None, Some(segment_width)) => group_width = Some(segment_width),
-                        (Some(group_width), Some(segment_width)) => {
-                            if segment_width != group_width {
-                                return Err(());
-                            }
+        let mut group_widths = Vec::with_capacity(groups.len());
+        let mut conflict_segments = HashSet::new();
+        for group in groups {
+            let mut group_width = None;
+            let mut has_conflict = false;
+            for &i in group {
+                let Ok(segment_width) =
+                    find_segment_width(&self.wire_segments[i], &self.components)
+                else {
+                    has_conflict = true;
+                    break;
+                };
+
+                match (group_width, segment_width) {
+                    (_, None) => (),
+                    (None, Some(segment_width)) => group_width = Some(segment_width),
+                    (Some(group_width), Some(segment_width)) => {
+                        if segment_width != group_width {
+                            has_conflict = true;
+                            break;
                         }
                     }
                 }
+            }
 
-                Ok(group_width.unwrap_or(NonZeroU8::MIN))
-            })
-            .collect()
+            if has_conflict {
+                conflict_segments.extend(group.iter().copied());
+            } else {
+                group_widths.push(group_width.unwrap_or(NonZeroU8::MIN));
+            }
+        }
+
+        if conflict_segments.is_empty() {
+            Ok(group_widths)
+        } else {
+            Err(conflict_segments)
+        }
     }
 
     fn advance_simulation(&mut self, mut sim: gsim::Simulator, clock_state: bool, max_steps: u64) {
         use gsim::*;
 
-        self.sim_state = match sim.run_sim(max_steps) {
+        // Equivalent to `run_sim`, but keeps track of how many steps it took to settle
+        let mut steps = 0;
+        let result = loop {
+            if steps >= max_steps {
+                break SimulationRunResult::MaxStepsReached;
+            }
+
+            match sim.step_sim() {
+                SimulationStepResult::Unchanged => break SimulationRunResult::Ok,
+                SimulationStepResult::Changed => steps += 1,
+                SimulationStepResult::Err(err) => break SimulationRunResult::Err(err),
+            }
+        };
+        self.last_step_count = steps;
+
+        self.sim_state = match result {
             SimulationRunResult::Ok => SimState::Active { sim, clock_state },
-            SimulationRunResult::MaxStepsReached => todo!(),
+            SimulationRunResult::MaxStepsReached => SimState::Unstable {
+                sim,
+                clock_state,
+                max_steps,
+            },
             SimulationRunResult::Err(err) => {
                 let mut conflict_segments = HashSet::new();
                 for (i, segment) in self.wire_segments.iter().enumerate() {
@@ -1309,11 +3274,41 @@ impl Circuit {
                 }
             }
         };
+
+        self.sample_signals();
+    }
+
+    pub fn start_simulation(&mut self, max_steps: u64) -> Result<(), StartSimError> {
+        let builder = self.build_simulator()?;
+
+        // Indices stored in the history are not stable while simulating.
+        // Only cleared once the simulation is certain to start, so a failed start keeps them.
+        self.history.clear();
+        self.signal_traces.clear();
+        self.sample_count = 0;
+
+        let sim = builder.build();
+        self.advance_simulation(sim, false, max_steps);
+
+        Ok(())
     }
 
-    pub fn start_simulation(&mut self, max_steps: u64) {
+    /// Builds the simulation graph of the circuit.
+    ///
+    /// The IDs of the created simulation wires and components are stored in the circuit,
+    /// so the state of a simulator built from the result can be displayed.
+    pub fn build_simulator(&mut self) -> Result<gsim::SimulatorBuilder, StartSimError> {
         use gsim::*;
 
+        // Checked before anything is built so no stale simulation IDs are left behind
+        if self
+            .components
+            .iter()
+            .any(|component| is_discriminant!(component.kind, ComponentKind::Splitter))
+        {
+            return Err(StartSimError::UnsupportedSplitter);
+        }
+
         let mut builder = SimulatorBuilder::default();
 
         // TODO: build simulation graph
@@ -1322,139 +3317,118 @@ impl Circuit {
         //  2. Create wire(s) in simulation graph for each net
         //  3. Create component(s) in simulation graph for each editor component
 
-        // TODO: optimize all of this, because we are doing work multiple times
-
         // connected nets of wire segments
         let (groups, group_map) = self.find_wire_groups();
-        let Ok(group_widths) = self.find_wire_group_widths(&groups) else {
-            todo!() // TODO: display wire width conflict
-        };
+        let group_widths = self
+            .find_wire_group_widths(&groups)
+            .map_err(|conflict_segments| StartSimError::WidthConflict { conflict_segments })?;
 
         // TODO: find connected nets of wire segments _and_ splitters
 
         // TODO: depending on splitter configuration, potentially create more than one sim wire per group
+        let mut anchor_wires = AnchorWireResolver::default();
         for (group, &group_width) in groups.iter().zip(group_widths.iter()) {
             let sim_wire = builder.add_wire(group_width).unwrap();
+            let net_wires = [NetWire {
+                first_bit: 0,
+                width: group_width,
+                sim_wire,
+            }];
 
             for &i in group {
                 let segment = &mut self.wire_segments[i];
                 segment.sim_wires = smallvec![sim_wire];
+
+                anchor_wires.insert(segment.endpoint_a, &net_wires);
+                anchor_wires.insert(segment.endpoint_b, &net_wires);
             }
         }
 
-        // TODO: find some general solution to associate anchors with wires instead of hardcoding indices
-        // TODO: create dummy wires for unconnected anchors
         for component in &mut self.components {
             let anchors = component.anchors();
 
             match &mut component.kind {
-                ComponentKind::Input {
-                    name,
-                    value,
-                    width,
-                    sim_wire,
-                } => {
-                    let mut wire = None;
-                    for segment in &self.wire_segments {
-                        if (segment.endpoint_a == anchors[0].position)
-                            || (segment.endpoint_b == anchors[0].position)
-                        {
-                            wire = Some(segment.sim_wires[0]);
-                            break;
-                        }
-                    }
-                    *sim_wire = wire.unwrap();
+                // Unconnected ports still need a wire to be driven or read
+                ComponentKind::Input { sim_wire, .. }
+                | ComponentKind::ClockInput { sim_wire, .. }
+                | ComponentKind::PushButton { sim_wire, .. }
+                | ComponentKind::Output { sim_wire, .. }
+                | ComponentKind::Constant { sim_wire, .. }
+                | ComponentKind::Led { sim_wire }
+                | ComponentKind::SevenSegment { sim_wire } => {
+                    *sim_wire = anchor_wires.resolve_or_add(&anchors[0], &mut builder);
                 }
-                ComponentKind::ClockInput { name, sim_wire } => todo!(),
-                ComponentKind::Output {
-                    name,
-                    width,
-                    sim_wire,
-                } => {
-                    let mut wire = None;
-                    for segment in &self.wire_segments {
-                        if (segment.endpoint_a == anchors[0].position)
-                            || (segment.endpoint_b == anchors[0].position)
-                        {
-                            wire = Some(segment.sim_wires[0]);
-                            break;
-                        }
-                    }
-                    *sim_wire = wire.unwrap();
+                ComponentKind::Port { sim_wire, .. } => {
+                    // Ports drive the net wire itself, so drivers on both sides of a bus resolve
+                    // against each other like any other drivers of the net
+                    *sim_wire = anchor_wires.resolve_or_add(&anchors[0], &mut builder);
+                }
+                ComponentKind::Tunnel { sim_wire, .. } => {
+                    // Connected tunnels already share a net, there is nothing to simulate
+                    *sim_wire = anchor_wires.resolve_or_add(&anchors[0], &mut builder);
+                }
+                ComponentKind::Splitter { .. } => {
+                    unreachable!("circuits with splitters are rejected above")
                 }
-                ComponentKind::Splitter { width, ranges } => todo!(),
                 ComponentKind::AndGate {
-                    width,
+                    inverted,
                     sim_component,
+                    ..
                 } => {
-                    let mut wires = vec![];
-                    for anchor in anchors {
-                        for segment in &self.wire_segments {
-                            if (segment.endpoint_a == anchor.position)
-                                || (segment.endpoint_b == anchor.position)
-                            {
-                                wires.push(segment.sim_wires[0]);
-                                break;
-                            }
-                        }
-                    }
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
 
                     let output = wires.pop().unwrap();
-                    *sim_component = builder.add_and_gate(&wires, output).unwrap();
+                    *sim_component = if *inverted {
+                        builder.add_nand_gate(&wires, output).unwrap()
+                    } else {
+                        builder.add_and_gate(&wires, output).unwrap()
+                    };
                 }
                 ComponentKind::OrGate {
-                    width,
+                    inverted,
                     sim_component,
+                    ..
                 } => {
-                    let mut wires = vec![];
-                    for anchor in anchors {
-                        for segment in &self.wire_segments {
-                            if (segment.endpoint_a == anchor.position)
-                                || (segment.endpoint_b == anchor.position)
-                            {
-                                wires.push(segment.sim_wires[0]);
-                                break;
-                            }
-                        }
-                    }
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
 
                     let output = wires.pop().unwrap();
-                    *sim_component = builder.add_or_gate(&wires, output).unwrap();
+                    *sim_component = if *inverted {
+                        builder.add_nor_gate(&wires, output).unwrap()
+                    } else {
+                        builder.add_or_gate(&wires, output).unwrap()
+                    };
                 }
                 ComponentKind::XorGate {
-                    width,
+                    inverted,
                     sim_component,
+                    ..
                 } => {
-                    let mut wires = vec![];
-                    for anchor in anchors {
-                        for segment in &self.wire_segments {
-                            if (segment.endpoint_a == anchor.position)
-                                || (segment.endpoint_b == anchor.position)
-                            {
-                                wires.push(segment.sim_wires[0]);
-                                break;
-                            }
-                        }
-                    }
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
 
                     let output = wires.pop().unwrap();
-                    *sim_component = builder.add_xor_gate(&wires, output).unwrap();
+                    *sim_component = if *inverted {
+                        builder.add_xnor_gate(&wires, output).unwrap()
+                    } else {
+                        builder.add_xor_gate(&wires, output).unwrap()
+                    };
                 }
                 ComponentKind::NandGate {
                     width,
                     sim_component,
                 } => {
-                    let mut wires = vec![];
-                    for anchor in anchors {
-                        for segment in &self.wire_segments {
-                            if (segment.endpoint_a == anchor.position)
-                                || (segment.endpoint_b == anchor.position)
-                            {
-                                wires.push(segment.sim_wires[0]);
-                                break;
-                            }
-                        }
-                    }
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
 
                     let output = wires.pop().unwrap();
                     *sim_component = builder.add_nand_gate(&wires, output).unwrap();
@@ -1463,17 +3437,10 @@ impl Circuit {
                     width,
                     sim_component,
                 } => {
-                    let mut wires = vec![];
-                    for anchor in anchors {
-                        for segment in &self.wire_segments {
-                            if (segment.endpoint_a == anchor.position)
-                                || (segment.endpoint_b == anchor.position)
-                            {
-                                wires.push(segment.sim_wires[0]);
-                                break;
-                            }
-                        }
-                    }
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
 
                     let output = wires.pop().unwrap();
                     *sim_component = builder.add_nor_gate(&wires, output).unwrap();
@@ -1482,21 +3449,87 @@ impl Circuit {
                     width,
                     sim_component,
                 } => {
-                    let mut wires = vec![];
-                    for anchor in anchors {
-                        for segment in &self.wire_segments {
-                            if (segment.endpoint_a == anchor.position)
-                                || (segment.endpoint_b == anchor.position)
-                            {
-                                wires.push(segment.sim_wires[0]);
-                                break;
-                            }
-                        }
-                    }
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
 
                     let output = wires.pop().unwrap();
                     *sim_component = builder.add_xnor_gate(&wires, output).unwrap();
                 }
+                ComponentKind::Buffer {
+                    inverted: true,
+                    sim_component,
+                    ..
+                } => {
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
+
+                    let output = wires.pop().unwrap();
+                    *sim_component = builder.add_not_gate(wires[0], output).unwrap();
+                }
+                ComponentKind::Buffer { sim_component, .. } => {
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
+
+                    // The buffer is always enabled
+                    let enable = builder.add_wire(NonZeroU8::MIN).unwrap();
+                    builder
+                        .set_wire_drive(enable, &LogicState::LOGIC_1)
+                        .unwrap();
+
+                    let output = wires.pop().unwrap();
+                    *sim_component = builder.add_buffer(wires[0], enable, output).unwrap();
+                }
+                ComponentKind::NotGate {
+                    width,
+                    sim_component,
+                } => {
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
+
+                    let output = wires.pop().unwrap();
+                    *sim_component = builder.add_not_gate(wires[0], output).unwrap();
+                }
+                ComponentKind::TriStateBuffer {
+                    width,
+                    sim_component,
+                } => {
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
+
+                    // gsim buffers drive high-impedance while their enable input is low
+                    let output = wires.pop().unwrap();
+                    *sim_component = builder.add_buffer(wires[0], wires[1], output).unwrap();
+                }
+                ComponentKind::DFlipFlop {
+                    width,
+                    sim_component,
+                } => {
+                    let mut wires: Vec<_> = anchors
+                        .iter()
+                        .map(|anchor| anchor_wires.resolve_or_add(anchor, &mut builder))
+                        .collect();
+
+                    // The register is always enabled, it latches on every rising clock edge
+                    let enable = builder.add_wire(NonZeroU8::MIN).unwrap();
+                    builder
+                        .set_wire_drive(enable, &LogicState::LOGIC_1)
+                        .unwrap();
+
+                    let output = wires.pop().unwrap();
+                    *sim_component = builder
+                        .add_register(wires[0], output, enable, wires[1])
+                        .unwrap();
+                }
             }
         }
 
@@ -1512,12 +3545,77 @@ impl Circuit {
                 ComponentKind::ClockInput { sim_wire, .. } => {
                     builder.set_wire_drive(sim_wire, &clk_state).unwrap()
                 }
+                ComponentKind::PushButton { sim_wire, .. } => builder
+                    .set_wire_drive(sim_wire, &LogicState::LOGIC_0)
+                    .unwrap(),
+                ComponentKind::Constant {
+                    value, sim_wire, ..
+                } => {
+                    let state = LogicState::from_int(value);
+                    builder.set_wire_drive(sim_wire, &state).unwrap()
+                }
+                ComponentKind::Port {
+                    driven,
+                    value,
+                    sim_wire,
+                    ..
+                } => builder
+                    .set_wire_drive(sim_wire, &port_drive(driven, value))
+                    .unwrap(),
                 _ => (),
             }
         }
 
-        let sim = builder.build();
-        self.advance_simulation(sim, false, max_steps);
+        Ok(builder)
+    }
+
+    /// Describes the connectivity of the circuit as JSON, for use outside of the editor.
+    ///
+    /// Every component lists the index of the net connected to each of its anchors,
+    /// or `null` if the anchor is unconnected.
+    pub fn export_netlist(&self) -> Result<String, StartSimError> {
+        let (groups, group_map) = self.find_wire_groups();
+        let group_widths = self
+            .find_wire_group_widths(&groups)
+            .map_err(|conflict_segments| StartSimError::WidthConflict { conflict_segments })?;
+
+        let nets: Vec<_> = group_widths
+            .iter()
+            .map(|width| serde_json::json!({ "width": width.get() }))
+            .collect();
+
+        let components: Vec<_> = self
+            .components
+            .iter()
+            .map(|component| {
+                let anchor_nets: Vec<_> = component
+                    .anchors()
+                    .iter()
+                    .map(|anchor| {
+                        self.wire_segments
+                            .iter()
+                            .position(|segment| {
+                                (segment.endpoint_a == anchor.position)
+                                    || (segment.endpoint_b == anchor.position)
+                            })
+                            .map(|segment| group_map[segment])
+                    })
+                    .collect();
+
+                serde_json::json!({
+                    "kind": component.kind,
+                    "nets": anchor_nets,
+                })
+            })
+            .collect();
+
+        let netlist = serde_json::json!({
+            "name": self.name,
+            "nets": nets,
+            "components": components,
+        });
+
+        Ok(serde_json::to_string_pretty(&netlist).unwrap())
     }
 
     pub fn step_simulation(&mut self, max_steps: u64) {
@@ -1526,27 +3624,35 @@ impl Circuit {
         let mut sim_state = SimState::None;
         std::mem::swap(&mut sim_state, &mut self.sim_state);
 
-        let SimState::Active {
-            mut sim,
-            clock_state,
-        } = sim_state
-        else {
-            panic!("simulation is not running");
-        };
+        match sim_state {
+            SimState::Active {
+                mut sim,
+                clock_state,
+            } => {
+                let clock_state = !clock_state;
+                let clk = LogicState::from_bool(clock_state);
+                for component in &self.components {
+                    if let ComponentKind::ClockInput { sim_wire, .. } = component.kind {
+                        sim.set_wire_drive(sim_wire, &clk).unwrap();
+                    }
+                }
 
-        let clock_state = !clock_state;
-        let clk = LogicState::from_bool(clock_state);
-        for component in &self.components {
-            if let ComponentKind::ClockInput { sim_wire, .. } = component.kind {
-                sim.set_wire_drive(sim_wire, &clk).unwrap();
+                self.advance_simulation(sim, clock_state, max_steps);
+            }
+            SimState::Unstable {
+                sim, clock_state, ..
+            } => {
+                // The circuit has not settled yet, so keep running without toggling the clock
+                self.advance_simulation(sim, clock_state, max_steps);
             }
+            _ => panic!("simulation is not running"),
         }
-
-        self.advance_simulation(sim, clock_state, max_steps);
     }
 
     pub fn stop_simulation(&mut self) {
         self.sim_state = SimState::None;
+        self.held_push_button = None;
+        self.edited_input = None;
 
         for component in &mut self.components {
             component.kind.reset_sim_ids();
@@ -1557,3 +3663,100 @@ impl Circuit {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wire(endpoint_a: Vec2i, midpoints: &[Vec2i], endpoint_b: Vec2i) -> WireSegment {
+        WireSegment {
+            endpoint_a,
+            midpoints: midpoints.iter().copied().collect(),
+            endpoint_b,
+            sim_wires: smallvec![],
+        }
+    }
+
+    #[test]
+    fn wire_crossing_rectangle() {
+        let straight = wire(Vec2i::new(0, 0), &[], Vec2i::new(4, 0));
+        let rect = Rectangle::from_points(Vec2f::new(1.0, -1.0), Vec2f::new(3.0, 1.0));
+        assert!(straight.intersects(&rect));
+
+        // Only the second leg passes through the rectangle
+        let bent = wire(Vec2i::new(0, 0), &[Vec2i::new(2, 2)], Vec2i::new(2, 6));
+        let rect = Rectangle::from_points(Vec2f::new(1.5, 3.0), Vec2f::new(2.5, 4.0));
+        assert!(bent.intersects(&rect));
+    }
+
+    #[test]
+    fn wire_touching_rectangle() {
+        let straight = wire(Vec2i::new(0, 0), &[], Vec2i::new(4, 0));
+        let rect = Rectangle::from_points(Vec2f::new(4.0, -1.0), Vec2f::new(6.0, 1.0));
+        assert!(straight.intersects(&rect));
+        let rect = Rectangle::from_points(Vec2f::new(1.0, 0.0), Vec2f::new(3.0, 1.0));
+        assert!(straight.intersects(&rect));
+    }
+
+    #[test]
+    fn wire_disjoint_from_rectangle() {
+        let straight = wire(Vec2i::new(0, 0), &[], Vec2i::new(4, 0));
+        let rect = Rectangle::from_points(Vec2f::new(1.0, 1.0), Vec2f::new(3.0, 2.0));
+        assert!(!straight.intersects(&rect));
+
+        // The rectangle lies inside the bend, away from both legs
+        let bent = wire(Vec2i::new(0, 0), &[Vec2i::new(2, 2)], Vec2i::new(2, 6));
+        let rect = Rectangle::from_points(Vec2f::new(0.0, 3.0), Vec2f::new(1.0, 4.0));
+        assert!(!bent.intersects(&rect));
+    }
+
+    fn assert_center_matches_bounding_box(circuit: &Circuit) {
+        let Selection::Multi {
+            components,
+            wire_segments,
+            center,
+        } = circuit.selection()
+        else {
+            panic!("expected a multi-selection");
+        };
+
+        let bb = circuit.find_selection_bounding_box(components, wire_segments);
+        assert_eq!(*center, bb.center());
+    }
+
+    #[test]
+    fn multi_selection_center_follows_transforms() {
+        let mut circuit = Circuit::new();
+        circuit.add_component_at(ComponentKind::new_and_gate(), Vec2i::new(3, 1));
+        circuit.add_component_at(ComponentKind::new_input(), Vec2i::new(-4, 6));
+        circuit.add_wire(Vec2i::new(0, 0), &[Vec2i::new(2, 2)], Vec2i::new(2, 7));
+        circuit.select_all();
+        assert_center_matches_bounding_box(&circuit);
+
+        // The rotation pivot is snapped to the grid, so an odd-sized selection shifts
+        circuit.counterclockwise_rotate_selection();
+        assert_center_matches_bounding_box(&circuit);
+
+        circuit.move_selection(Vec2i::new(5, -3));
+        assert_center_matches_bounding_box(&circuit);
+
+        circuit.clockwise_rotate_selection();
+        circuit.mirror_selection();
+        assert_center_matches_bounding_box(&circuit);
+    }
+
+    #[test]
+    fn net_of_only_bidirectional_anchors_has_their_width() {
+        let width = NonZeroU8::new(8).unwrap();
+        let mut port = ComponentKind::new_port();
+        port.set_width(width);
+
+        let mut circuit = Circuit::new();
+        circuit.add_component_at(port.clone(), Vec2i::new(0, 0));
+        circuit.add_component_at(port, Vec2i::new(4, 0));
+        circuit.add_wire(Vec2i::new(0, 1), &[], Vec2i::new(4, 1));
+
+        let (groups, _) = circuit.find_wire_groups();
+        assert_eq!(circuit.find_wire_group_widths(&groups), Ok(vec![width]));
+    }
+}
@@ -0,0 +1,279 @@
+use super::{Component, WireSegment};
+use crate::app::math::*;
+use crate::HashSet;
+
+const MAX_HISTORY_LEN: usize = 256;
+
+#[derive(Default)]
+pub(super) struct ItemSet {
+    /// Sorted by index in ascending order
+    pub(super) components: Vec<(usize, Component)>,
+    /// Sorted by index in ascending order
+    pub(super) wire_segments: Vec<(usize, WireSegment)>,
+}
+
+impl ItemSet {
+    #[inline]
+    pub(super) fn is_empty(&self) -> bool {
+        self.components.is_empty() && self.wire_segments.is_empty()
+    }
+
+    fn insert_into(&self, components: &mut Vec<Component>, wire_segments: &mut Vec<WireSegment>) {
+        for (i, component) in &self.components {
+            components.insert(*i, component.clone());
+        }
+
+        for (i, wire_segment) in &self.wire_segments {
+            wire_segments.insert(*i, wire_segment.clone());
+        }
+    }
+
+    fn remove_from(&self, components: &mut Vec<Component>, wire_segments: &mut Vec<WireSegment>) {
+        for (i, _) in self.components.iter().rev() {
+            components.remove(*i);
+        }
+
+        for (i, _) in self.wire_segments.iter().rev() {
+            wire_segments.remove(*i);
+        }
+    }
+
+    /// Maps a component index from before removing this set to the index afterwards,
+    /// or `None` if the component itself is part of the set
+    pub(super) fn remap_component_index(&self, index: usize) -> Option<usize> {
+        remap_index(index, &self.components)
+    }
+
+    /// Maps a wire segment index from before removing this set to the index afterwards,
+    /// or `None` if the wire segment itself is part of the set
+    pub(super) fn remap_wire_segment_index(&self, index: usize) -> Option<usize> {
+        remap_index(index, &self.wire_segments)
+    }
+
+    fn mark_affected(&self, affected: &mut AffectedItems) {
+        affected
+            .components
+            .extend(self.components.iter().map(|(i, _)| *i));
+        affected
+            .wire_segments
+            .extend(self.wire_segments.iter().map(|(i, _)| *i));
+    }
+}
+
+fn remap_index<T>(index: usize, removed: &[(usize, T)]) -> Option<usize> {
+    match removed.binary_search_by_key(&index, |(i, _)| *i) {
+        Ok(_) => None,
+        Err(removed_before) => Some(index - removed_before),
+    }
+}
+
+/// The items that were touched by undoing or redoing a command
+#[derive(Default)]
+pub(super) struct AffectedItems {
+    pub(super) components: HashSet<usize>,
+    pub(super) wire_segments: HashSet<usize>,
+}
+
+impl AffectedItems {
+    fn clear(&mut self) {
+        self.components.clear();
+        self.wire_segments.clear();
+    }
+}
+
+pub(super) enum EditCommand {
+    Insert(ItemSet),
+    Remove(ItemSet),
+    Move {
+        components: Vec<usize>,
+        wire_segments: Vec<usize>,
+        delta: Vec2i,
+    },
+    Modify {
+        /// Index, old state, new state
+        components: Vec<(usize, Component, Component)>,
+        /// Index, old state, new state
+        wire_segments: Vec<(usize, WireSegment, WireSegment)>,
+    },
+    Batch(Vec<EditCommand>),
+}
+
+fn move_items(
+    components: &mut [Component],
+    wire_segments: &mut [WireSegment],
+    component_indices: &[usize],
+    wire_segment_indices: &[usize],
+    delta: Vec2i,
+) {
+    for &i in component_indices {
+        let component = &mut components[i];
+        component.set_position(component.position() + delta);
+    }
+
+    for &i in wire_segment_indices {
+        let wire_segment = &mut wire_segments[i];
+        wire_segment.endpoint_a += delta;
+        wire_segment.endpoint_b += delta;
+        for p in wire_segment.midpoints.iter_mut() {
+            *p += delta;
+        }
+    }
+}
+
+impl EditCommand {
+    pub(super) fn apply(
+        &self,
+        components: &mut Vec<Component>,
+        wire_segments: &mut Vec<WireSegment>,
+        affected: &mut AffectedItems,
+    ) {
+        match self {
+            EditCommand::Insert(items) => {
+                items.insert_into(components, wire_segments);
+                items.mark_affected(affected);
+            }
+            EditCommand::Remove(items) => {
+                items.remove_from(components, wire_segments);
+                affected.clear();
+            }
+            EditCommand::Move {
+                components: component_indices,
+                wire_segments: wire_segment_indices,
+                delta,
+            } => {
+                move_items(
+                    components,
+                    wire_segments,
+                    component_indices,
+                    wire_segment_indices,
+                    *delta,
+                );
+
+                affected
+                    .components
+                    .extend(component_indices.iter().copied());
+                affected
+                    .wire_segments
+                    .extend(wire_segment_indices.iter().copied());
+            }
+            EditCommand::Modify {
+                components: modified_components,
+                wire_segments: modified_wire_segments,
+            } => {
+                for (i, _, new) in modified_components {
+                    components[*i] = new.clone();
+                    affected.components.insert(*i);
+                }
+
+                for (i, _, new) in modified_wire_segments {
+                    wire_segments[*i] = new.clone();
+                    affected.wire_segments.insert(*i);
+                }
+            }
+            EditCommand::Batch(commands) => {
+                for command in commands {
+                    command.apply(components, wire_segments, affected);
+                }
+            }
+        }
+    }
+
+    pub(super) fn revert(
+        &self,
+        components: &mut Vec<Component>,
+        wire_segments: &mut Vec<WireSegment>,
+        affected: &mut AffectedItems,
+    ) {
+        match self {
+            EditCommand::Insert(items) => {
+                items.remove_from(components, wire_segments);
+                affected.clear();
+            }
+            EditCommand::Remove(items) => {
+                items.insert_into(components, wire_segments);
+                items.mark_affected(affected);
+            }
+            EditCommand::Move {
+                components: component_indices,
+                wire_segments: wire_segment_indices,
+                delta,
+            } => {
+                move_items(
+                    components,
+                    wire_segments,
+                    component_indices,
+                    wire_segment_indices,
+                    -*delta,
+                );
+
+                affected
+                    .components
+                    .extend(component_indices.iter().copied());
+                affected
+                    .wire_segments
+                    .extend(wire_segment_indices.iter().copied());
+            }
+            EditCommand::Modify {
+                components: modified_components,
+                wire_segments: modified_wire_segments,
+            } => {
+                for (i, old, _) in modified_components {
+                    components[*i] = old.clone();
+                    affected.components.insert(*i);
+                }
+
+                for (i, old, _) in modified_wire_segments {
+                    wire_segments[*i] = old.clone();
+                    affected.wire_segments.insert(*i);
+                }
+            }
+            EditCommand::Batch(commands) => {
+                for command in commands.iter().rev() {
+                    command.revert(components, wire_segments, affected);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub(super) struct EditHistory {
+    commands: Vec<EditCommand>,
+    cursor: usize,
+}
+
+impl EditHistory {
+    pub(super) fn push(&mut self, command: EditCommand) {
+        self.commands.truncate(self.cursor);
+        self.commands.push(command);
+
+        if self.commands.len() > MAX_HISTORY_LEN {
+            self.commands.remove(0);
+        }
+
+        self.cursor = self.commands.len();
+    }
+
+    pub(super) fn undo(&mut self) -> Option<&EditCommand> {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            Some(&self.commands[self.cursor])
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn redo(&mut self) -> Option<&EditCommand> {
+        if self.cursor < self.commands.len() {
+            self.cursor += 1;
+            Some(&self.commands[self.cursor - 1])
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.commands.clear();
+        self.cursor = 0;
+    }
+}
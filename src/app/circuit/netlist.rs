@@ -0,0 +1,426 @@
+//! A minimal text format for describing circuits by their connectivity.
+//!
+//! Every line describes one component as its kind, optionally followed by `:` and a bit width,
+//! and then the names of the nets connected to each of its anchors, in the same order as in
+//! exported netlists. Gates take their input count from the number of nets, `-` leaves an anchor
+//! unconnected and everything after a `#` is a comment:
+//!
+//! ```text
+//! input a
+//! input b
+//! xor:4 a b sum
+//! output:4 sum
+//! ```
+//!
+//! Inputs, outputs, ports and tunnels are named after the net they are connected to.
+
+use super::Circuit;
+use crate::app::component::*;
+use crate::app::math::*;
+use crate::HashMap;
+use std::num::NonZeroU8;
+
+/// Space between components in the same row, in units
+const COMPONENT_SPACING: f32 = 4.0;
+/// Space between rows, which leaves room for the wires between them
+const ROW_SPACING: f32 = 6.0;
+
+#[derive(Debug)]
+pub enum ImportNetlistError {
+    UnknownKind {
+        line: usize,
+        kind: String,
+    },
+    InvalidWidth {
+        line: usize,
+    },
+    WrongNetCount {
+        line: usize,
+        min: usize,
+        max: usize,
+        found: usize,
+    },
+}
+
+impl std::fmt::Display for ImportNetlistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportNetlistError::UnknownKind { line, kind } => {
+                write!(f, "line {line}: unknown component kind `{kind}`")
+            }
+            ImportNetlistError::InvalidWidth { line } => write!(
+                f,
+                "line {line}: bit width must be between {MIN_BIT_WIDTH} and {MAX_BIT_WIDTH}"
+            ),
+            ImportNetlistError::WrongNetCount {
+                line,
+                min,
+                max,
+                found,
+            } => {
+                if min == max {
+                    write!(f, "line {line}: expected {min} nets, found {found}")
+                } else {
+                    write!(
+                        f,
+                        "line {line}: expected {min} to {max} nets, found {found}"
+                    )
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportNetlistError {}
+
+fn new_kind(name: &str) -> Option<ComponentKind> {
+    let kind = match name {
+        "input" => ComponentKind::new_input(),
+        "clock" => ComponentKind::new_clock_input(),
+        "button" => ComponentKind::new_push_button(),
+        "output" => ComponentKind::new_output(),
+        "port" => ComponentKind::new_port(),
+        "seven-segment" => ComponentKind::new_seven_segment(),
+        "led" => ComponentKind::new_led(),
+        "tunnel" => ComponentKind::new_tunnel(),
+        "and" => ComponentKind::new_and_gate(),
+        "or" => ComponentKind::new_or_gate(),
+        "xor" => ComponentKind::new_xor_gate(),
+        "nand" => ComponentKind::new_nand_gate(),
+        "nor" => ComponentKind::new_nor_gate(),
+        "xnor" => ComponentKind::new_xnor_gate(),
+        "buffer" => ComponentKind::new_buffer(),
+        "not" => ComponentKind::new_not_gate(),
+        "tri-state-buffer" => ComponentKind::new_tri_state_buffer(),
+        "d-flip-flop" => ComponentKind::new_d_flip_flop(),
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
+/// Names and input counts can only be set once the nets of a line are known
+fn apply_nets(
+    component: &mut Component,
+    nets: &[Option<&str>],
+    line: usize,
+) -> Result<(), ImportNetlistError> {
+    match &mut component.kind {
+        ComponentKind::Input { name, .. }
+        | ComponentKind::ClockInput { name, .. }
+        | ComponentKind::PushButton { name, .. }
+        | ComponentKind::Output { name, .. }
+        | ComponentKind::Port { name, .. }
+        | ComponentKind::Tunnel { name, .. } => {
+            if let Some(&Some(net)) = nets.first() {
+                *name = net.to_owned();
+            }
+        }
+        ComponentKind::AndGate { input_count, .. }
+        | ComponentKind::OrGate { input_count, .. }
+        | ComponentKind::XorGate { input_count, .. }
+        | ComponentKind::NandGate { input_count, .. }
+        | ComponentKind::NorGate { input_count, .. }
+        | ComponentKind::XnorGate { input_count, .. } => {
+            let min = (MIN_INPUT_COUNT as usize) + 1;
+            let max = (MAX_INPUT_COUNT as usize) + 1;
+            if !(min..=max).contains(&nets.len()) {
+                return Err(ImportNetlistError::WrongNetCount {
+                    line,
+                    min,
+                    max,
+                    found: nets.len(),
+                });
+            }
+
+            *input_count = (nets.len() - 1) as u8;
+        }
+        _ => (),
+    }
+
+    let anchor_count = component.anchors().len();
+    if nets.len() != anchor_count {
+        return Err(ImportNetlistError::WrongNetCount {
+            line,
+            min: anchor_count,
+            max: anchor_count,
+            found: nets.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Sinks are placed in the last row, so the signal flows from the bottom to the top
+#[inline]
+fn is_sink(kind: &ComponentKind) -> bool {
+    matches!(
+        kind,
+        ComponentKind::Output { .. }
+            | ComponentKind::Led { .. }
+            | ComponentKind::SevenSegment { .. }
+    )
+}
+
+impl Circuit {
+    /// Creates a circuit from the text format described in the module documentation.
+    ///
+    /// Components are arranged in rows by how many components lie between them and the inputs,
+    /// and nets are connected with automatically routed wires.
+    pub fn import_netlist(text: &str) -> Result<Self, ImportNetlistError> {
+        let mut components = Vec::new();
+        let mut component_nets: Vec<Vec<Option<&str>>> = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.split('#').next().unwrap_or_default();
+
+            let mut tokens = line.split_whitespace();
+            let Some(kind_token) = tokens.next() else {
+                continue;
+            };
+
+            let (kind_name, width) = match kind_token.split_once(':') {
+                Some((kind_name, width)) => {
+                    let width = width
+                        .parse::<NonZeroU8>()
+                        .ok()
+                        .filter(|width| (MIN_BIT_WIDTH..=MAX_BIT_WIDTH).contains(width))
+                        .ok_or(ImportNetlistError::InvalidWidth { line: line_number })?;
+                    (kind_name, Some(width))
+                }
+                None => (kind_token, None),
+            };
+
+            let mut kind = new_kind(kind_name).ok_or_else(|| ImportNetlistError::UnknownKind {
+                line: line_number,
+                kind: kind_name.to_owned(),
+            })?;
+            if let Some(width) = width {
+                kind.set_width(width);
+            }
+
+            let nets: Vec<_> = tokens.map(|net| (net != "-").then_some(net)).collect();
+            let mut component = Component::new(kind);
+            apply_nets(&mut component, &nets, line_number)?;
+
+            components.push(component);
+            component_nets.push(nets);
+        }
+
+        // Every component is placed one row above the highest component driving one of its inputs.
+        // The number of rows is limited so feedback loops don't grow them forever.
+        let mut drivers: HashMap<&str, Vec<usize>> = HashMap::default();
+        for (i, (component, nets)) in components.iter().zip(&component_nets).enumerate() {
+            for (anchor, net) in component.anchors().iter().zip(nets) {
+                if let (AnchorKind::Output | AnchorKind::BiDirectional, Some(net)) =
+                    (anchor.kind, net)
+                {
+                    drivers.entry(*net).or_default().push(i);
+                }
+            }
+        }
+
+        let mut rows = vec![0usize; components.len()];
+        for _ in 0..components.len() {
+            let mut changed = false;
+
+            for (i, (component, nets)) in components.iter().zip(&component_nets).enumerate() {
+                for (anchor, net) in component.anchors().iter().zip(nets) {
+                    let (AnchorKind::Input | AnchorKind::Passive, Some(net)) = (anchor.kind, net)
+                    else {
+                        continue;
+                    };
+
+                    for &driver in drivers.get(net).into_iter().flatten() {
+                        let row = (rows[driver] + 1).min(components.len());
+                        if (driver != i) && (row > rows[i]) {
+                            rows[i] = row;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        let last_row = rows.iter().copied().max().unwrap_or_default() + 1;
+        for (row, component) in rows.iter_mut().zip(&components) {
+            if is_sink(&component.kind) {
+                *row = last_row;
+            }
+        }
+
+        // Lay out the rows from left to right, stacked on top of each other
+        let mut row_bottom = 0.0;
+        for row in 0..=last_row {
+            let mut left = 0.0;
+            let mut row_height: f32 = 0.0;
+
+            for (component, _) in components
+                .iter_mut()
+                .zip(&rows)
+                .filter(|(_, component_row)| **component_row == row)
+            {
+                let bb = component.bounding_box();
+                let x = (left - bb.left).ceil();
+                let y = (row_bottom - bb.bottom).ceil();
+                component.set_position(Vec2i::new(x as i32, y as i32));
+
+                left = x + bb.right + COMPONENT_SPACING;
+                row_height = row_height.max(bb.height());
+            }
+
+            if row_height > 0.0 {
+                row_bottom += row_height + ROW_SPACING;
+            }
+        }
+
+        let mut circuit = Circuit::new();
+        circuit.components = components;
+
+        // Connect every net as a star, starting at one of its drivers if it has any
+        let mut net_anchors: HashMap<&str, Vec<(bool, Vec2i)>> = HashMap::default();
+        for (component, nets) in circuit.components.iter().zip(&component_nets) {
+            for (anchor, net) in component.anchors().iter().zip(nets) {
+                if let Some(net) = net {
+                    let is_driver =
+                        matches!(anchor.kind, AnchorKind::Output | AnchorKind::BiDirectional);
+                    net_anchors
+                        .entry(*net)
+                        .or_default()
+                        .push((is_driver, anchor.position));
+                }
+            }
+        }
+
+        let mut nets: Vec<_> = net_anchors.into_iter().collect();
+        // Hash map order is random, this keeps imports reproducible
+        nets.sort_unstable_by_key(|&(net, _)| net);
+
+        for (_, mut anchors) in nets {
+            anchors.sort_by_key(|&(is_driver, _)| !is_driver);
+
+            let Some((&(_, root), rest)) = anchors.split_first() else {
+                continue;
+            };
+
+            for &(_, position) in rest {
+                if position != root {
+                    let segment = circuit.route_wire(root, position);
+                    circuit.wire_segments.push(segment);
+                }
+            }
+        }
+
+        circuit.modified = true;
+        Ok(circuit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Group of the wire ending at `position`
+    fn group_at(circuit: &Circuit, group_map: &[usize], position: Vec2i) -> usize {
+        let segment = circuit
+            .wire_segments
+            .iter()
+            .position(|segment| {
+                (segment.endpoint_a == position) || (segment.endpoint_b == position)
+            })
+            .expect("anchor is not connected");
+        group_map[segment]
+    }
+
+    #[test]
+    fn import_connects_nets() {
+        let circuit = Circuit::import_netlist("input a\ninput b\nxor:4 a b sum\noutput:4 sum")
+            .ok()
+            .unwrap();
+        assert_eq!(circuit.components.len(), 4);
+        assert_eq!(circuit.components[0].kind.name(), "a");
+        assert_eq!(circuit.components[3].kind.name(), "sum");
+
+        let anchor = |component: usize, anchor: usize| {
+            circuit.components[component].anchors()[anchor].position
+        };
+
+        let (groups, group_map) = circuit.find_wire_groups();
+        assert_eq!(groups.len(), 3);
+
+        let a = group_at(&circuit, &group_map, anchor(0, 0));
+        let b = group_at(&circuit, &group_map, anchor(1, 0));
+        let sum = group_at(&circuit, &group_map, anchor(3, 0));
+        assert_eq!(group_at(&circuit, &group_map, anchor(2, 0)), a);
+        assert_eq!(group_at(&circuit, &group_map, anchor(2, 1)), b);
+        assert_eq!(group_at(&circuit, &group_map, anchor(2, 2)), sum);
+        assert!((a != b) && (a != sum) && (b != sum));
+    }
+
+    #[test]
+    fn import_skips_comments_and_unconnected_anchors() {
+        let text = "# only comments\n\ninput a # named after its net\noutput -\n";
+        let circuit = Circuit::import_netlist(text).ok().unwrap();
+        assert_eq!(circuit.components.len(), 2);
+        assert_eq!(circuit.components[0].kind.name(), "a");
+        assert_eq!(circuit.components[1].kind.name(), "");
+        assert!(circuit.wire_segments.is_empty());
+    }
+
+    #[test]
+    fn import_rejects_unknown_kind() {
+        let err = Circuit::import_netlist("input a\nmux a b").err();
+        assert!(
+            matches!(err, Some(ImportNetlistError::UnknownKind { line: 2, kind }) if kind == "mux")
+        );
+    }
+
+    #[test]
+    fn import_rejects_invalid_width() {
+        let err = Circuit::import_netlist("# comment\ninput:0 a").err();
+        assert!(matches!(
+            err,
+            Some(ImportNetlistError::InvalidWidth { line: 2 })
+        ));
+
+        let err = Circuit::import_netlist("input a\n\noutput:65 a").err();
+        assert!(matches!(
+            err,
+            Some(ImportNetlistError::InvalidWidth { line: 3 })
+        ));
+
+        let err = Circuit::import_netlist("output:wide a").err();
+        assert!(matches!(
+            err,
+            Some(ImportNetlistError::InvalidWidth { line: 1 })
+        ));
+    }
+
+    #[test]
+    fn import_rejects_wrong_net_count() {
+        let err = Circuit::import_netlist("input a\nnot a").err();
+        assert!(matches!(
+            err,
+            Some(ImportNetlistError::WrongNetCount {
+                line: 2,
+                min: 2,
+                max: 2,
+                found: 1,
+            })
+        ));
+
+        let min = (MIN_INPUT_COUNT as usize) + 1;
+        let max = (MAX_INPUT_COUNT as usize) + 1;
+        let err = Circuit::import_netlist("and a y").err();
+        assert!(matches!(
+            err,
+            Some(ImportNetlistError::WrongNetCount { line: 1, min: err_min, max: err_max, found: 2 })
+                if (err_min == min) && (err_max == max)
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::app::math::*;
+use crate::HashMap;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// How far the route may leave the rectangle spanned by its endpoints, in units
+const SEARCH_MARGIN: i32 = 16;
+/// Gives up on routes that take longer than this to find
+const MAX_VISITED_NODES: usize = 65536;
+/// Extra cost of a bend compared to going straight for one unit, so routes prefer fewer bends
+const BEND_COST: u32 = 4;
+
+const DIRECTIONS: [Vec2i; 4] = [
+    Vec2i::new(1, 0),
+    Vec2i::new(0, 1),
+    Vec2i::new(-1, 0),
+    Vec2i::new(0, -1),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Node {
+    pos: Vec2i,
+    /// Index into `DIRECTIONS` of the step that led to this node, `None` for the start
+    dir: Option<u8>,
+}
+
+#[inline]
+fn manhattan_distance(a: Vec2i, b: Vec2i) -> u32 {
+    let diff = (b - a).abs();
+    (diff.x + diff.y) as u32
+}
+
+/// Finds an orthogonal path from `from` to `to` that does not cross the inside of any obstacle,
+/// using A* on the unit grid. Points on the edge of an obstacle are allowed so anchors can be reached.
+///
+/// Returns the corners of the path, not including the endpoints, or `None` if there is no path
+/// close enough to the endpoints.
+pub(super) fn find_route(from: Vec2i, to: Vec2i, obstacles: &[Rectangle]) -> Option<Vec<Vec2i>> {
+    let min = from.min(to) - Vec2i::new(SEARCH_MARGIN, SEARCH_MARGIN);
+    let max = from.max(to) + Vec2i::new(SEARCH_MARGIN, SEARCH_MARGIN);
+
+    let is_free = |p: Vec2i| {
+        if (p.x < min.x) || (p.y < min.y) || (p.x > max.x) || (p.y > max.y) {
+            return false;
+        }
+
+        let p = p.to_vec2f();
+        !obstacles.iter().any(|rect| {
+            (p.x > rect.left) && (p.x < rect.right) && (p.y > rect.bottom) && (p.y < rect.top)
+        })
+    };
+
+    let start = Node {
+        pos: from,
+        dir: None,
+    };
+
+    let mut open = BinaryHeap::new();
+    let mut costs: HashMap<Node, u32> = HashMap::default();
+    let mut came_from: HashMap<Node, Node> = HashMap::default();
+
+    // Vectors are not ordered, so the heap stores plain coordinates
+    open.push(Reverse((
+        manhattan_distance(from, to),
+        0u32,
+        (from.x, from.y),
+        None,
+    )));
+    costs.insert(start, 0);
+
+    let end = loop {
+        let Reverse((_, cost, (x, y), dir)) = open.pop()?;
+        let node = Node {
+            pos: Vec2i::new(x, y),
+            dir,
+        };
+
+        if node.pos == to {
+            break node;
+        }
+
+        if costs.get(&node).is_some_and(|&best| best < cost) {
+            // Outdated entry, the node has been reached more cheaply since
+            continue;
+        }
+
+        if costs.len() > MAX_VISITED_NODES {
+            return None;
+        }
+
+        for (i, &dir) in DIRECTIONS.iter().enumerate() {
+            let i = i as u8;
+
+            // Going back is never useful
+            if node.dir.is_some_and(|prev| (prev + 2) % 4 == i) {
+                continue;
+            }
+
+            let next = Node {
+                pos: node.pos + dir,
+                dir: Some(i),
+            };
+
+            if (next.pos != to) && !is_free(next.pos) {
+                continue;
+            }
+
+            let bend = node.dir.is_some_and(|prev| prev != i);
+            let next_cost = cost + 1 + if bend { BEND_COST } else { 0 };
+
+            if costs.get(&next).is_none_or(|&best| next_cost < best) {
+                costs.insert(next, next_cost);
+                came_from.insert(next, node);
+
+                let estimate = next_cost + manhattan_distance(next.pos, to);
+                open.push(Reverse((
+                    estimate,
+                    next_cost,
+                    (next.pos.x, next.pos.y),
+                    next.dir,
+                )));
+            }
+        }
+    };
+
+    // Walk back to the start, keeping only the points where the direction changes
+    let mut corners = Vec::new();
+    let mut node = end;
+    while let Some(&prev) = came_from.get(&node) {
+        if prev.dir.is_some() && (prev.dir != node.dir) {
+            corners.push(prev.pos);
+        }
+
+        node = prev;
+    }
+
+    corners.reverse();
+    Some(corners)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All grid points along the route, including both endpoints
+    fn route_points(from: Vec2i, corners: &[Vec2i], to: Vec2i) -> Vec<Vec2i> {
+        let mut points = vec![from];
+        for &target in corners.iter().chain(std::iter::once(&to)) {
+            let mut p = *points.last().unwrap();
+            assert!(
+                (p.x == target.x) || (p.y == target.y),
+                "route is not orthogonal"
+            );
+
+            while p != target {
+                p.x += (target.x - p.x).signum();
+                p.y += (target.y - p.y).signum();
+                points.push(p);
+            }
+        }
+
+        points
+    }
+
+    #[test]
+    fn straight_route_has_no_corners() {
+        let route = find_route(Vec2i::new(0, 0), Vec2i::new(10, 0), &[]);
+        assert_eq!(route, Some(vec![]));
+    }
+
+    #[test]
+    fn route_uses_a_single_bend() {
+        let from = Vec2i::new(0, 0);
+        let to = Vec2i::new(5, 5);
+
+        let corners = find_route(from, to, &[]).unwrap();
+        assert_eq!(corners.len(), 1);
+        assert!((corners[0] == Vec2i::new(5, 0)) || (corners[0] == Vec2i::new(0, 5)));
+    }
+
+    #[test]
+    fn route_avoids_obstacle() {
+        let from = Vec2i::new(0, 0);
+        let to = Vec2i::new(10, 0);
+        let obstacle = Rectangle::from_points(Vec2f::new(3.0, -2.0), Vec2f::new(7.0, 2.0));
+
+        let corners = find_route(from, to, &[obstacle]).unwrap();
+        // Leaving the line right at the start, going around and coming back down at the end
+        // takes two bends, detours that leave the line later take four
+        assert_eq!(corners.len(), 2);
+
+        for p in route_points(from, &corners, to) {
+            let p = p.to_vec2f();
+            let inside = (p.x > obstacle.left)
+                && (p.x < obstacle.right)
+                && (p.y > obstacle.bottom)
+                && (p.y < obstacle.top);
+            assert!(!inside, "route passes through the obstacle at {p:?}");
+        }
+    }
+
+    #[test]
+    fn no_route_beyond_search_margin() {
+        // A wall that only ends far outside of the search area
+        let wall = Rectangle::from_points(Vec2f::new(3.0, -100.0), Vec2f::new(7.0, 100.0));
+        assert_eq!(
+            find_route(Vec2i::new(0, 0), Vec2i::new(10, 0), &[wall]),
+            None
+        );
+    }
+}
@@ -5,9 +5,10 @@ use egui::*;
 use gsim::Id;
 use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
+use std::borrow::Cow;
 use std::num::NonZeroU8;
 
-use super::NumericTextValue;
+use super::{parse_numeric, parse_radix_prefixed, NumericTextValue};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -37,13 +38,116 @@ macro_rules! anchors {
     };
 }
 
+/// The number base values of a component are displayed in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Radix {
+    Bin,
+    Dec,
+    #[default]
+    Hex,
+}
+
+impl Radix {
+    pub fn drag_value(self, value: &mut u32, max_value: u32) -> DragValue<'_> {
+        let drag_value = DragValue::new(value).clamp_range(0..=max_value);
+        let drag_value = match self {
+            Radix::Bin => drag_value.binary(1, false).prefix("0b"),
+            Radix::Dec => drag_value,
+            Radix::Hex => drag_value.hexadecimal(1, false, true).prefix("0x"),
+        };
+
+        // Any radix can be typed in with a prefix, the displayed one is the default
+        let default_radix = match self {
+            Radix::Bin => 2,
+            Radix::Dec => 10,
+            Radix::Hex => 16,
+        };
+        drag_value.custom_parser(move |text| {
+            parse_radix_prefixed(text, default_radix).map(|value| value as f64)
+        })
+    }
+
+    fn update_property(
+        &mut self,
+        ui: &mut Ui,
+        locale_manager: &LocaleManager,
+        lang: &LangId,
+    ) -> bool {
+        ui.horizontal(|ui| {
+            ui.label(locale_manager.get(lang, "radix-property-name"));
+
+            let mut changed = false;
+            for (radix, key) in [
+                (Radix::Bin, "binary-radix-name"),
+                (Radix::Dec, "decimal-radix-name"),
+                (Radix::Hex, "hexadecimal-radix-name"),
+            ] {
+                changed |= ui
+                    .selectable_value(self, radix, locale_manager.get(lang, key))
+                    .changed();
+            }
+            changed
+        })
+        .inner
+    }
+}
+
+pub const MIN_INPUT_COUNT: u8 = 2;
+pub const MAX_INPUT_COUNT: u8 = 8;
+
+fn default_input_count() -> u8 {
+    MIN_INPUT_COUNT
+}
+
+/// Input anchors of a gate, spaced two units apart and centered on the gate
+fn gate_anchors(input_count: u8, width: NonZeroU8, output_y: i32) -> SmallVec<[Anchor; 3]> {
+    let first_x = 1 - (input_count as i32);
+    let mut anchors: SmallVec<[Anchor; 3]> = (0..(input_count as i32))
+        .map(|i| Anchor {
+            position: Vec2i::new(first_x + (i * 2), -2),
+            kind: AnchorKind::Input,
+            width,
+        })
+        .collect();
+    anchors.push(Anchor {
+        position: Vec2i::new(0, output_y),
+        kind: AnchorKind::Output,
+        width,
+    });
+    anchors
+}
+
+pub const MIN_BIT_WIDTH: NonZeroU8 = NonZeroU8::MIN;
+/// The widest wires the simulator supports
+pub const MAX_BIT_WIDTH: NonZeroU8 = match NonZeroU8::new(64) {
+    Some(width) => width,
+    None => unreachable!(),
+};
+
+/// Constant values are stored in 32 bits, so constants can't be any wider
+pub const MAX_CONSTANT_WIDTH: NonZeroU8 = match NonZeroU8::new(32) {
+    Some(width) => width,
+    None => unreachable!(),
+};
+
+/// Keeps positions entered by hand far away from overflowing when offset by anchors or moves
+const MAX_COORDINATE: i32 = 1 << 20;
+
+/// Segments `a` to `g` followed by the decimal point, one bit each
+pub const SEVEN_SEGMENT_WIDTH: NonZeroU8 = match NonZeroU8::new(8) {
+    Some(width) => width,
+    None => unreachable!(),
+};
+
 #[allow(clippy::enum_variant_names)]
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum ComponentKind {
     Input {
         name: String,
         value: u32,
         width: NumericTextValue<NonZeroU8>,
+        #[serde(default)]
+        radix: Radix,
         #[serde(skip)]
         sim_wire: gsim::WireId,
     },
@@ -52,9 +156,41 @@ pub enum ComponentKind {
         #[serde(skip)]
         sim_wire: gsim::WireId,
     },
+    PushButton {
+        name: String,
+        #[serde(skip)]
+        sim_wire: gsim::WireId,
+    },
     Output {
         name: String,
         width: NumericTextValue<NonZeroU8>,
+        #[serde(default)]
+        radix: Radix,
+        #[serde(skip)]
+        sim_wire: gsim::WireId,
+    },
+    /// Connects a shared net from either side, driving `value` onto it while `driven` is set
+    Port {
+        name: String,
+        width: NumericTextValue<NonZeroU8>,
+        #[serde(default)]
+        driven: bool,
+        #[serde(default)]
+        value: u32,
+        #[serde(skip)]
+        sim_wire: gsim::WireId,
+    },
+    SevenSegment {
+        #[serde(skip)]
+        sim_wire: gsim::WireId,
+    },
+    Led {
+        #[serde(skip)]
+        sim_wire: gsim::WireId,
+    },
+    Constant {
+        value: u32,
+        width: NumericTextValue<NonZeroU8>,
         #[serde(skip)]
         sim_wire: gsim::WireId,
     },
@@ -62,32 +198,78 @@ pub enum ComponentKind {
         width: NumericTextValue<NonZeroU8>,
         ranges: SmallVec<[(u8, u8); 8]>,
     },
+    Tunnel {
+        name: String,
+        width: NumericTextValue<NonZeroU8>,
+        #[serde(skip)]
+        sim_wire: gsim::WireId,
+    },
     AndGate {
         width: NumericTextValue<NonZeroU8>,
+        #[serde(default = "default_input_count")]
+        input_count: u8,
+        #[serde(default)]
+        inverted: bool,
         #[serde(skip)]
         sim_component: gsim::ComponentId,
     },
     OrGate {
         width: NumericTextValue<NonZeroU8>,
+        #[serde(default = "default_input_count")]
+        input_count: u8,
+        #[serde(default)]
+        inverted: bool,
         #[serde(skip)]
         sim_component: gsim::ComponentId,
     },
     XorGate {
         width: NumericTextValue<NonZeroU8>,
+        #[serde(default = "default_input_count")]
+        input_count: u8,
+        #[serde(default)]
+        inverted: bool,
         #[serde(skip)]
         sim_component: gsim::ComponentId,
     },
     NandGate {
         width: NumericTextValue<NonZeroU8>,
+        #[serde(default = "default_input_count")]
+        input_count: u8,
         #[serde(skip)]
         sim_component: gsim::ComponentId,
     },
     NorGate {
         width: NumericTextValue<NonZeroU8>,
+        #[serde(default = "default_input_count")]
+        input_count: u8,
         #[serde(skip)]
         sim_component: gsim::ComponentId,
     },
     XnorGate {
+        width: NumericTextValue<NonZeroU8>,
+        #[serde(default = "default_input_count")]
+        input_count: u8,
+        #[serde(skip)]
+        sim_component: gsim::ComponentId,
+    },
+    Buffer {
+        width: NumericTextValue<NonZeroU8>,
+        #[serde(default)]
+        inverted: bool,
+        #[serde(skip)]
+        sim_component: gsim::ComponentId,
+    },
+    NotGate {
+        width: NumericTextValue<NonZeroU8>,
+        #[serde(skip)]
+        sim_component: gsim::ComponentId,
+    },
+    TriStateBuffer {
+        width: NumericTextValue<NonZeroU8>,
+        #[serde(skip)]
+        sim_component: gsim::ComponentId,
+    },
+    DFlipFlop {
         width: NumericTextValue<NonZeroU8>,
         #[serde(skip)]
         sim_component: gsim::ComponentId,
@@ -99,6 +281,7 @@ impl ComponentKind {
         Self::Input {
             value: 0,
             width: NumericTextValue::new(NonZeroU8::MIN),
+            radix: Radix::default(),
             name: "".to_owned(),
             sim_wire: gsim::WireId::INVALID,
         }
@@ -111,10 +294,63 @@ impl ComponentKind {
         }
     }
 
+    pub fn new_push_button() -> Self {
+        Self::PushButton {
+            name: "".to_owned(),
+            sim_wire: gsim::WireId::INVALID,
+        }
+    }
+
     pub fn new_output() -> Self {
         Self::Output {
             width: NumericTextValue::new(NonZeroU8::MIN),
+            radix: Radix::default(),
+            name: "".to_owned(),
+            sim_wire: gsim::WireId::INVALID,
+        }
+    }
+
+    pub fn new_port() -> Self {
+        Self::Port {
+            width: NumericTextValue::new(NonZeroU8::MIN),
+            name: "".to_owned(),
+            driven: false,
+            value: 0,
+            sim_wire: gsim::WireId::INVALID,
+        }
+    }
+
+    pub fn new_seven_segment() -> Self {
+        Self::SevenSegment {
+            sim_wire: gsim::WireId::INVALID,
+        }
+    }
+
+    pub fn new_led() -> Self {
+        Self::Led {
+            sim_wire: gsim::WireId::INVALID,
+        }
+    }
+
+    pub fn new_constant() -> Self {
+        Self::Constant {
+            value: 0,
+            width: NumericTextValue::new(NonZeroU8::MIN),
+            sim_wire: gsim::WireId::INVALID,
+        }
+    }
+
+    pub fn new_splitter() -> Self {
+        Self::Splitter {
+            width: NumericTextValue::new(NonZeroU8::new(2).unwrap()),
+            ranges: smallvec![(0, 0), (1, 1)],
+        }
+    }
+
+    pub fn new_tunnel() -> Self {
+        Self::Tunnel {
             name: "".to_owned(),
+            width: NumericTextValue::new(NonZeroU8::MIN),
             sim_wire: gsim::WireId::INVALID,
         }
     }
@@ -122,6 +358,8 @@ impl ComponentKind {
     pub fn new_and_gate() -> Self {
         Self::AndGate {
             width: NumericTextValue::new(NonZeroU8::MIN),
+            input_count: MIN_INPUT_COUNT,
+            inverted: false,
             sim_component: gsim::ComponentId::INVALID,
         }
     }
@@ -129,6 +367,8 @@ impl ComponentKind {
     pub fn new_or_gate() -> Self {
         Self::OrGate {
             width: NumericTextValue::new(NonZeroU8::MIN),
+            input_count: MIN_INPUT_COUNT,
+            inverted: false,
             sim_component: gsim::ComponentId::INVALID,
         }
     }
@@ -136,6 +376,8 @@ impl ComponentKind {
     pub fn new_xor_gate() -> Self {
         Self::XorGate {
             width: NumericTextValue::new(NonZeroU8::MIN),
+            input_count: MIN_INPUT_COUNT,
+            inverted: false,
             sim_component: gsim::ComponentId::INVALID,
         }
     }
@@ -143,6 +385,7 @@ impl ComponentKind {
     pub fn new_nand_gate() -> Self {
         Self::NandGate {
             width: NumericTextValue::new(NonZeroU8::MIN),
+            input_count: MIN_INPUT_COUNT,
             sim_component: gsim::ComponentId::INVALID,
         }
     }
@@ -150,12 +393,43 @@ impl ComponentKind {
     pub fn new_nor_gate() -> Self {
         Self::NorGate {
             width: NumericTextValue::new(NonZeroU8::MIN),
+            input_count: MIN_INPUT_COUNT,
             sim_component: gsim::ComponentId::INVALID,
         }
     }
 
     pub fn new_xnor_gate() -> Self {
         Self::XnorGate {
+            width: NumericTextValue::new(NonZeroU8::MIN),
+            input_count: MIN_INPUT_COUNT,
+            sim_component: gsim::ComponentId::INVALID,
+        }
+    }
+
+    pub fn new_buffer() -> Self {
+        Self::Buffer {
+            width: NumericTextValue::new(NonZeroU8::MIN),
+            inverted: false,
+            sim_component: gsim::ComponentId::INVALID,
+        }
+    }
+
+    pub fn new_not_gate() -> Self {
+        Self::NotGate {
+            width: NumericTextValue::new(NonZeroU8::MIN),
+            sim_component: gsim::ComponentId::INVALID,
+        }
+    }
+
+    pub fn new_tri_state_buffer() -> Self {
+        Self::TriStateBuffer {
+            width: NumericTextValue::new(NonZeroU8::MIN),
+            sim_component: gsim::ComponentId::INVALID,
+        }
+    }
+
+    pub fn new_d_flip_flop() -> Self {
+        Self::DFlipFlop {
             width: NumericTextValue::new(NonZeroU8::MIN),
             sim_component: gsim::ComponentId::INVALID,
         }
@@ -166,10 +440,15 @@ impl ComponentKind {
             ComponentKind::Input { width, .. } => {
                 anchors![Output(0, 1)[width.value]]
             }
-            ComponentKind::ClockInput { .. } => {
+            ComponentKind::ClockInput { .. } | ComponentKind::PushButton { .. } => {
                 anchors![Output(0, 1)[NonZeroU8::MIN]]
             }
             ComponentKind::Output { width, .. } => anchors![Input(0, -1)[width.value]],
+            ComponentKind::Port { width, .. } => anchors![BiDirectional(0, 1)[width.value]],
+            ComponentKind::SevenSegment { .. } => anchors![Input(0, -3)[SEVEN_SEGMENT_WIDTH]],
+            ComponentKind::Led { .. } => anchors![Input(0, -1)[NonZeroU8::MIN]],
+            ComponentKind::Constant { width, .. } => anchors![Output(0, 1)[width.value]],
+            ComponentKind::Tunnel { width, .. } => anchors![Passive(0, -1)[width.value]],
             ComponentKind::Splitter { width, ranges, .. } => {
                 let mut anchors = anchors![Passive(0, -1)[width.value]];
                 for (i, &range) in ranges.iter().enumerate() {
@@ -187,22 +466,59 @@ impl ComponentKind {
                 }
                 anchors
             }
-            ComponentKind::AndGate { width, .. }
-            | ComponentKind::OrGate { width, .. }
-            | ComponentKind::XorGate { width, .. } => {
+            // Inverted gates are drawn with an output bubble, the anchor sits at its tip
+            ComponentKind::AndGate {
+                width,
+                input_count,
+                inverted,
+                ..
+            }
+            | ComponentKind::OrGate {
+                width,
+                input_count,
+                inverted,
+                ..
+            }
+            | ComponentKind::XorGate {
+                width,
+                input_count,
+                inverted,
+                ..
+            } => gate_anchors(*input_count, width.value, if *inverted { 3 } else { 2 }),
+            ComponentKind::NandGate {
+                width, input_count, ..
+            }
+            | ComponentKind::NorGate {
+                width, input_count, ..
+            }
+            | ComponentKind::XnorGate {
+                width, input_count, ..
+            } => gate_anchors(*input_count, width.value, 3),
+            ComponentKind::Buffer {
+                width,
+                inverted: false,
+                ..
+            } => anchors![Input(0, -2)[width.value], Output(0, 2)[width.value]],
+            ComponentKind::Buffer {
+                width,
+                inverted: true,
+                ..
+            } => anchors![Input(0, -2)[width.value], Output(0, 3)[width.value]],
+            ComponentKind::NotGate { width, .. } => {
+                anchors![Input(0, -2)[width.value], Output(0, 3)[width.value]]
+            }
+            ComponentKind::TriStateBuffer { width, .. } => {
                 anchors![
-                    Input(-1, -2)[width.value],
-                    Input(1, -2)[width.value],
+                    Input(0, -2)[width.value],
+                    Input(-2, 0)[NonZeroU8::MIN],
                     Output(0, 2)[width.value]
                 ]
             }
-            ComponentKind::NandGate { width, .. }
-            | ComponentKind::NorGate { width, .. }
-            | ComponentKind::XnorGate { width, .. } => {
+            ComponentKind::DFlipFlop { width, .. } => {
                 anchors![
                     Input(-1, -2)[width.value],
-                    Input(1, -2)[width.value],
-                    Output(0, 3)[width.value]
+                    Input(1, -2)[NonZeroU8::MIN],
+                    Output(0, 2)[width.value]
                 ]
             }
         }
@@ -212,19 +528,52 @@ impl ComponentKind {
         match self {
             ComponentKind::Input { .. }
             | ComponentKind::ClockInput { .. }
-            | ComponentKind::Output { .. } => Rectangle {
+            | ComponentKind::PushButton { .. }
+            | ComponentKind::Output { .. }
+            | ComponentKind::Port { .. }
+            | ComponentKind::Led { .. }
+            | ComponentKind::Constant { .. } => Rectangle {
                 top: 1.0,
                 bottom: -1.0,
                 left: -1.0,
                 right: 1.0,
             },
-            ComponentKind::Splitter { .. } => todo!(),
-            ComponentKind::AndGate { .. }
-            | ComponentKind::OrGate { .. }
-            | ComponentKind::XorGate { .. }
-            | ComponentKind::NandGate { .. }
-            | ComponentKind::NorGate { .. }
-            | ComponentKind::XnorGate { .. } => Rectangle {
+            ComponentKind::Splitter { ranges, .. } => Rectangle {
+                top: 1.0,
+                bottom: -1.0,
+                left: -1.0,
+                right: (ranges.len().saturating_sub(1) * 2) as f32 + 1.0,
+            },
+            ComponentKind::SevenSegment { .. } => Rectangle {
+                top: 3.0,
+                bottom: -3.0,
+                left: -2.0,
+                right: 2.0,
+            },
+            ComponentKind::Tunnel { .. } => Rectangle {
+                top: 1.0,
+                bottom: -1.0,
+                left: -2.0,
+                right: 2.0,
+            },
+            ComponentKind::AndGate { input_count, .. }
+            | ComponentKind::OrGate { input_count, .. }
+            | ComponentKind::XorGate { input_count, .. }
+            | ComponentKind::NandGate { input_count, .. }
+            | ComponentKind::NorGate { input_count, .. }
+            | ComponentKind::XnorGate { input_count, .. } => {
+                let half_width = ((*input_count as f32) - 1.0).max(2.0);
+                Rectangle {
+                    top: 2.0,
+                    bottom: -2.0,
+                    left: -half_width,
+                    right: half_width,
+                }
+            }
+            ComponentKind::Buffer { .. }
+            | ComponentKind::NotGate { .. }
+            | ComponentKind::TriStateBuffer { .. }
+            | ComponentKind::DFlipFlop { .. } => Rectangle {
                 top: 2.0,
                 bottom: -2.0,
                 left: -2.0,
@@ -240,82 +589,417 @@ impl ComponentKind {
         lang: &LangId,
     ) -> bool {
         match self {
-            ComponentKind::ClockInput { name, .. } => {
+            ComponentKind::SevenSegment { .. } | ComponentKind::Led { .. } => false,
+            ComponentKind::ClockInput { name, .. } | ComponentKind::PushButton { name, .. } => {
                 ui.horizontal(|ui| {
                     ui.label(locale_manager.get(lang, "name-property-name"));
                     ui.text_edit_singleline(name).lost_focus()
                 })
                 .inner
             }
-            ComponentKind::Input { name, width, .. }
-            | ComponentKind::Output { name, width, .. } => {
-                let name_chaged = ui
+            ComponentKind::Input {
+                name,
+                value,
+                width,
+                radix,
+                ..
+            } => {
+                let name_width_changed =
+                    update_name_width_properties(ui, name, width, locale_manager, lang);
+
+                let max_value = u32::MAX >> (32 - width.value.get().min(32));
+                *value = (*value).min(max_value);
+
+                let value_changed = ui
                     .horizontal(|ui| {
-                        ui.label(locale_manager.get(lang, "name-property-name"));
-                        ui.text_edit_singleline(name).lost_focus()
+                        ui.label(locale_manager.get(lang, "value-property-name"));
+                        ui.add(radix.drag_value(value, max_value)).changed()
                     })
                     .inner;
 
+                let radix_changed = radix.update_property(ui, locale_manager, lang);
+
+                name_width_changed | value_changed | radix_changed
+            }
+            ComponentKind::Output {
+                name, width, radix, ..
+            } => {
+                let name_width_changed =
+                    update_name_width_properties(ui, name, width, locale_manager, lang);
+                let radix_changed = radix.update_property(ui, locale_manager, lang);
+
+                name_width_changed | radix_changed
+            }
+            ComponentKind::Port {
+                name,
+                width,
+                driven,
+                value,
+                ..
+            } => {
+                let name_width_changed =
+                    update_name_width_properties(ui, name, width, locale_manager, lang);
+
+                let driven_changed = ui
+                    .checkbox(driven, locale_manager.get(lang, "driven-property-name"))
+                    .changed();
+
+                let max_value = u32::MAX >> (32 - width.value.get().min(32));
+                *value = (*value).min(max_value);
+
+                let value_changed = ui
+                    .add_enabled_ui(*driven, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(locale_manager.get(lang, "value-property-name"));
+                            ui.add(
+                                DragValue::new(value)
+                                    .clamp_range(0..=max_value)
+                                    .hexadecimal(1, false, true)
+                                    .prefix("0x"),
+                            )
+                            .changed()
+                        })
+                        .inner
+                    })
+                    .inner;
+
+                name_width_changed | driven_changed | value_changed
+            }
+            ComponentKind::Tunnel { name, width, .. } => {
+                update_name_width_properties(ui, name, width, locale_manager, lang)
+            }
+            ComponentKind::Constant { value, width, .. } => {
                 let width_changed = ui
                     .horizontal(|ui| {
                         ui.label(locale_manager.get(lang, "bit-width-property-name"));
-                        ui.numeric_text_edit(width).lost_focus()
+                        ui.numeric_text_edit_clamped(width, MIN_BIT_WIDTH..=MAX_CONSTANT_WIDTH)
+                            .lost_focus()
+                    })
+                    .inner;
+
+                let max_value = u32::MAX >> (32 - width.value.get());
+                *value = (*value).min(max_value);
+
+                let value_changed = ui
+                    .horizontal(|ui| {
+                        ui.label(locale_manager.get(lang, "value-property-name"));
+
+                        let hex_changed = ui
+                            .add(
+                                DragValue::new(value)
+                                    .clamp_range(0..=max_value)
+                                    .hexadecimal(1, false, true)
+                                    .prefix("0x"),
+                            )
+                            .changed();
+
+                        let bin_changed = ui
+                            .add(
+                                DragValue::new(value)
+                                    .clamp_range(0..=max_value)
+                                    .binary(1, false)
+                                    .prefix("0b"),
+                            )
+                            .changed();
+
+                        hex_changed | bin_changed
                     })
                     .inner;
 
-                name_chaged | width_changed
+                width_changed | value_changed
             }
-            ComponentKind::Splitter { width, .. } => {
-                ui.horizontal(|ui| {
-                    ui.label(locale_manager.get(lang, "bit-width-property-name"));
-                    ui.numeric_text_edit(width).lost_focus()
-                })
-                .inner
+            ComponentKind::Splitter { width, ranges } => {
+                let mut changed = ui
+                    .horizontal(|ui| {
+                        ui.label(locale_manager.get(lang, "bit-width-property-name"));
+                        ui.numeric_text_edit_clamped(width, MIN_BIT_WIDTH..=MAX_BIT_WIDTH)
+                            .lost_focus()
+                    })
+                    .inner;
+
+                ui.label(locale_manager.get(lang, "bit-ranges-property-name"));
+
+                let max_bit = width.value.get() - 1;
+                let can_remove = ranges.len() > 1;
+                let mut remove = None;
+                for (i, range) in ranges.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        changed |= ui
+                            .add(DragValue::new(&mut range.0).clamp_range(0..=max_bit))
+                            .changed();
+                        ui.label("-");
+                        changed |= ui
+                            .add(DragValue::new(&mut range.1).clamp_range(range.0..=max_bit))
+                            .changed();
+
+                        // Anchor widths are computed from the ranges and require `start <= end`
+                        range.1 = range.1.max(range.0);
+
+                        if ui
+                            .add_enabled(can_remove, Button::new("🗙").small())
+                            .clicked()
+                        {
+                            remove = Some(i);
+                        }
+                    });
+                }
+
+                if let Some(i) = remove {
+                    ranges.remove(i);
+                    changed = true;
+                }
+
+                if ui.small_button("+").clicked() {
+                    let start = ranges
+                        .last()
+                        .map(|range| range.1.saturating_add(1))
+                        .unwrap_or(0)
+                        .min(max_bit);
+                    ranges.push((start, start));
+                    changed = true;
+                }
+
+                if let Some(error) = validate_splitter_ranges(width.value, ranges) {
+                    ui.colored_label(ui.visuals().error_fg_color, locale_manager.get(lang, error));
+                }
 
-                // TODO: edit ranges
+                changed
             }
-            ComponentKind::AndGate { width, .. }
-            | ComponentKind::OrGate { width, .. }
-            | ComponentKind::XorGate { width, .. }
-            | ComponentKind::NandGate { width, .. }
-            | ComponentKind::NorGate { width, .. }
-            | ComponentKind::XnorGate { width, .. } => {
+            ComponentKind::AndGate {
+                width,
+                input_count,
+                inverted,
+                ..
+            }
+            | ComponentKind::OrGate {
+                width,
+                input_count,
+                inverted,
+                ..
+            }
+            | ComponentKind::XorGate {
+                width,
+                input_count,
+                inverted,
+                ..
+            } => {
+                let width_changed = ui
+                    .horizontal(|ui| {
+                        ui.label(locale_manager.get(lang, "bit-width-property-name"));
+                        ui.numeric_text_edit_clamped(width, MIN_BIT_WIDTH..=MAX_BIT_WIDTH)
+                            .lost_focus()
+                    })
+                    .inner;
+
+                let input_count_changed =
+                    update_input_count_property(ui, input_count, locale_manager, lang);
+
+                let inverted_changed = ui
+                    .checkbox(inverted, locale_manager.get(lang, "inverted-property-name"))
+                    .changed();
+
+                width_changed | input_count_changed | inverted_changed
+            }
+            ComponentKind::NandGate {
+                width, input_count, ..
+            }
+            | ComponentKind::NorGate {
+                width, input_count, ..
+            }
+            | ComponentKind::XnorGate {
+                width, input_count, ..
+            } => {
+                let width_changed = ui
+                    .horizontal(|ui| {
+                        ui.label(locale_manager.get(lang, "bit-width-property-name"));
+                        ui.numeric_text_edit_clamped(width, MIN_BIT_WIDTH..=MAX_BIT_WIDTH)
+                            .lost_focus()
+                    })
+                    .inner;
+
+                let input_count_changed =
+                    update_input_count_property(ui, input_count, locale_manager, lang);
+
+                width_changed | input_count_changed
+            }
+            ComponentKind::Buffer {
+                width, inverted, ..
+            } => {
+                let width_changed = ui
+                    .horizontal(|ui| {
+                        ui.label(locale_manager.get(lang, "bit-width-property-name"));
+                        ui.numeric_text_edit_clamped(width, MIN_BIT_WIDTH..=MAX_BIT_WIDTH)
+                            .lost_focus()
+                    })
+                    .inner;
+
+                let inverted_changed = ui
+                    .checkbox(inverted, locale_manager.get(lang, "inverted-property-name"))
+                    .changed();
+
+                width_changed | inverted_changed
+            }
+            ComponentKind::NotGate { width, .. }
+            | ComponentKind::TriStateBuffer { width, .. }
+            | ComponentKind::DFlipFlop { width, .. } => {
                 ui.horizontal(|ui| {
                     ui.label(locale_manager.get(lang, "bit-width-property-name"));
-                    ui.numeric_text_edit(width).lost_focus()
+                    ui.numeric_text_edit_clamped(width, MIN_BIT_WIDTH..=MAX_BIT_WIDTH)
+                        .lost_focus()
                 })
                 .inner
             }
         }
     }
 
-    pub fn label(&self) -> &str {
+    /// Locale key of the name of this kind of component, as shown in the palette
+    pub fn kind_name_key(&self) -> &'static str {
+        match self {
+            ComponentKind::Input { .. } => "input-tool-tip",
+            ComponentKind::ClockInput { .. } => "clock-input-tool-tip",
+            ComponentKind::PushButton { .. } => "push-button-tool-tip",
+            ComponentKind::Output { .. } => "output-tool-tip",
+            ComponentKind::Port { .. } => "port-tool-tip",
+            ComponentKind::SevenSegment { .. } => "seven-segment-tool-tip",
+            ComponentKind::Led { .. } => "led-tool-tip",
+            ComponentKind::Constant { .. } => "constant-tool-tip",
+            ComponentKind::Splitter { .. } => "splitter-tool-tip",
+            ComponentKind::Tunnel { .. } => "tunnel-tool-tip",
+            ComponentKind::AndGate { .. } => "and-gate-tool-tip",
+            ComponentKind::OrGate { .. } => "or-gate-tool-tip",
+            ComponentKind::XorGate { .. } => "xor-gate-tool-tip",
+            ComponentKind::NandGate { .. } => "nand-gate-tool-tip",
+            ComponentKind::NorGate { .. } => "nor-gate-tool-tip",
+            ComponentKind::XnorGate { .. } => "xnor-gate-tool-tip",
+            ComponentKind::Buffer { .. } => "buffer-tool-tip",
+            ComponentKind::NotGate { .. } => "not-gate-tool-tip",
+            ComponentKind::TriStateBuffer { .. } => "tri-state-buffer-tool-tip",
+            ComponentKind::DFlipFlop { .. } => "d-flip-flop-tool-tip",
+        }
+    }
+
+    pub fn label(&self) -> Cow<'_, str> {
         match self {
-            ComponentKind::ClockInput { .. } => "Φ",
+            ComponentKind::ClockInput { .. } => "Φ".into(),
+            ComponentKind::Constant { value, .. } => format!("{value:X}").into(),
+            ComponentKind::Tunnel { name, .. } => name.as_str().into(),
             ComponentKind::Input { .. }
+            | ComponentKind::PushButton { .. }
             | ComponentKind::Output { .. }
-            | ComponentKind::Splitter { .. } => "",
-            ComponentKind::AndGate { .. } => "AND",
-            ComponentKind::OrGate { .. } => "OR",
-            ComponentKind::XorGate { .. } => "XOR",
-            ComponentKind::NandGate { .. } => "NAND",
-            ComponentKind::NorGate { .. } => "NOR",
-            ComponentKind::XnorGate { .. } => "XNOR",
+            | ComponentKind::Port { .. }
+            | ComponentKind::SevenSegment { .. }
+            | ComponentKind::Led { .. }
+            | ComponentKind::Splitter { .. } => "".into(),
+            ComponentKind::AndGate { inverted: true, .. } => "NAND".into(),
+            ComponentKind::OrGate { inverted: true, .. } => "NOR".into(),
+            ComponentKind::XorGate { inverted: true, .. } => "XNOR".into(),
+            ComponentKind::AndGate { .. } => "AND".into(),
+            ComponentKind::OrGate { .. } => "OR".into(),
+            ComponentKind::XorGate { .. } => "XOR".into(),
+            ComponentKind::NandGate { .. } => "NAND".into(),
+            ComponentKind::NorGate { .. } => "NOR".into(),
+            ComponentKind::XnorGate { .. } => "XNOR".into(),
+            ComponentKind::Buffer { .. }
+            | ComponentKind::NotGate { .. }
+            | ComponentKind::TriStateBuffer { .. } => "".into(),
+            ComponentKind::DFlipFlop { .. } => "D".into(),
         }
     }
 
     pub fn name(&self) -> &str {
         match self {
             ComponentKind::ClockInput { name, .. }
+            | ComponentKind::PushButton { name, .. }
             | ComponentKind::Input { name, .. }
-            | ComponentKind::Output { name, .. } => name,
-            ComponentKind::Splitter { .. }
+            | ComponentKind::Output { name, .. }
+            | ComponentKind::Port { name, .. }
+            | ComponentKind::Tunnel { name, .. } => name,
+            ComponentKind::SevenSegment { .. }
+            | ComponentKind::Led { .. }
+            | ComponentKind::Constant { .. }
+            | ComponentKind::Splitter { .. }
             | ComponentKind::AndGate { .. }
             | ComponentKind::OrGate { .. }
             | ComponentKind::XorGate { .. }
             | ComponentKind::NandGate { .. }
             | ComponentKind::NorGate { .. }
-            | ComponentKind::XnorGate { .. } => "",
+            | ComponentKind::XnorGate { .. }
+            | ComponentKind::Buffer { .. }
+            | ComponentKind::NotGate { .. }
+            | ComponentKind::TriStateBuffer { .. }
+            | ComponentKind::DFlipFlop { .. } => "",
+        }
+    }
+
+    pub fn width(&self) -> Option<NonZeroU8> {
+        match self {
+            ComponentKind::ClockInput { .. }
+            | ComponentKind::PushButton { .. }
+            | ComponentKind::SevenSegment { .. }
+            | ComponentKind::Led { .. } => None,
+            ComponentKind::Input { width, .. }
+            | ComponentKind::Output { width, .. }
+            | ComponentKind::Port { width, .. }
+            | ComponentKind::Constant { width, .. }
+            | ComponentKind::Splitter { width, .. }
+            | ComponentKind::Tunnel { width, .. }
+            | ComponentKind::AndGate { width, .. }
+            | ComponentKind::OrGate { width, .. }
+            | ComponentKind::XorGate { width, .. }
+            | ComponentKind::NandGate { width, .. }
+            | ComponentKind::NorGate { width, .. }
+            | ComponentKind::XnorGate { width, .. }
+            | ComponentKind::Buffer { width, .. }
+            | ComponentKind::NotGate { width, .. }
+            | ComponentKind::TriStateBuffer { width, .. }
+            | ComponentKind::DFlipFlop { width, .. } => Some(*width.get()),
+        }
+    }
+
+    pub fn set_width(&mut self, new_width: NonZeroU8) {
+        match self {
+            ComponentKind::ClockInput { .. }
+            | ComponentKind::PushButton { .. }
+            | ComponentKind::SevenSegment { .. }
+            | ComponentKind::Led { .. } => (),
+            ComponentKind::Input { width, .. }
+            | ComponentKind::Output { width, .. }
+            | ComponentKind::Port { width, .. }
+            | ComponentKind::Splitter { width, .. }
+            | ComponentKind::Tunnel { width, .. }
+            | ComponentKind::AndGate { width, .. }
+            | ComponentKind::OrGate { width, .. }
+            | ComponentKind::XorGate { width, .. }
+            | ComponentKind::NandGate { width, .. }
+            | ComponentKind::NorGate { width, .. }
+            | ComponentKind::XnorGate { width, .. }
+            | ComponentKind::Buffer { width, .. }
+            | ComponentKind::NotGate { width, .. }
+            | ComponentKind::TriStateBuffer { width, .. }
+            | ComponentKind::DFlipFlop { width, .. } => width.set(new_width),
+            ComponentKind::Constant { width, .. } => width.set(new_width.min(MAX_CONSTANT_WIDTH)),
+        }
+    }
+
+    /// Brings properties read from a file back into their valid ranges
+    pub fn clamp_properties(&mut self) {
+        match self {
+            ComponentKind::Constant { value, width, .. } => {
+                if width.value > MAX_CONSTANT_WIDTH {
+                    width.set(MAX_CONSTANT_WIDTH);
+                }
+
+                *value = (*value).min(u32::MAX >> (32 - width.value.get()));
+            }
+            ComponentKind::AndGate { input_count, .. }
+            | ComponentKind::OrGate { input_count, .. }
+            | ComponentKind::XorGate { input_count, .. }
+            | ComponentKind::NandGate { input_count, .. }
+            | ComponentKind::NorGate { input_count, .. }
+            | ComponentKind::XnorGate { input_count, .. } => {
+                *input_count = (*input_count).clamp(MIN_INPUT_COUNT, MAX_INPUT_COUNT);
+            }
+            _ => (),
         }
     }
 
@@ -323,18 +1007,126 @@ impl ComponentKind {
         match self {
             ComponentKind::Input { sim_wire, .. }
             | ComponentKind::ClockInput { sim_wire, .. }
-            | ComponentKind::Output { sim_wire, .. } => *sim_wire = gsim::WireId::INVALID,
+            | ComponentKind::PushButton { sim_wire, .. }
+            | ComponentKind::Output { sim_wire, .. }
+            | ComponentKind::Port { sim_wire, .. }
+            | ComponentKind::SevenSegment { sim_wire }
+            | ComponentKind::Led { sim_wire }
+            | ComponentKind::Constant { sim_wire, .. }
+            | ComponentKind::Tunnel { sim_wire, .. } => *sim_wire = gsim::WireId::INVALID,
             ComponentKind::Splitter { .. } => (),
             ComponentKind::AndGate { sim_component, .. }
             | ComponentKind::OrGate { sim_component, .. }
             | ComponentKind::XorGate { sim_component, .. }
             | ComponentKind::NandGate { sim_component, .. }
             | ComponentKind::NorGate { sim_component, .. }
-            | ComponentKind::XnorGate { sim_component, .. } => {
+            | ComponentKind::XnorGate { sim_component, .. }
+            | ComponentKind::Buffer { sim_component, .. }
+            | ComponentKind::NotGate { sim_component, .. }
+            | ComponentKind::TriStateBuffer { sim_component, .. }
+            | ComponentKind::DFlipFlop { sim_component, .. } => {
                 *sim_component = gsim::ComponentId::INVALID
             }
         }
     }
+
+    /// The side of the body the name is drawn on, in component space.
+    ///
+    /// Ports show their name opposite of their anchor.
+    pub fn name_direction(&self) -> Option<Vec2f> {
+        match self {
+            ComponentKind::Input { .. }
+            | ComponentKind::ClockInput { .. }
+            | ComponentKind::PushButton { .. }
+            | ComponentKind::Port { .. } => Some(Vec2f::new(0.0, -1.0)),
+            ComponentKind::Output { .. } => Some(Vec2f::new(0.0, 1.0)),
+            // Tunnels already show their name as the label
+            _ => None,
+        }
+    }
+
+    pub fn input_count(&self) -> Option<u8> {
+        match self {
+            ComponentKind::AndGate { input_count, .. }
+            | ComponentKind::OrGate { input_count, .. }
+            | ComponentKind::XorGate { input_count, .. }
+            | ComponentKind::NandGate { input_count, .. }
+            | ComponentKind::NorGate { input_count, .. }
+            | ComponentKind::XnorGate { input_count, .. } => Some(*input_count),
+            _ => None,
+        }
+    }
+
+    pub fn radix(&self) -> Radix {
+        match self {
+            ComponentKind::Input { radix, .. } | ComponentKind::Output { radix, .. } => *radix,
+            _ => Radix::default(),
+        }
+    }
+}
+
+fn update_name_width_properties(
+    ui: &mut Ui,
+    name: &mut String,
+    width: &mut NumericTextValue<NonZeroU8>,
+    locale_manager: &LocaleManager,
+    lang: &LangId,
+) -> bool {
+    let name_chaged = ui
+        .horizontal(|ui| {
+            ui.label(locale_manager.get(lang, "name-property-name"));
+            ui.text_edit_singleline(name).lost_focus()
+        })
+        .inner;
+
+    let width_changed = ui
+        .horizontal(|ui| {
+            ui.label(locale_manager.get(lang, "bit-width-property-name"));
+            ui.numeric_text_edit_clamped(width, MIN_BIT_WIDTH..=MAX_BIT_WIDTH)
+                .lost_focus()
+        })
+        .inner;
+
+    name_chaged | width_changed
+}
+
+fn update_input_count_property(
+    ui: &mut Ui,
+    input_count: &mut u8,
+    locale_manager: &LocaleManager,
+    lang: &LangId,
+) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(locale_manager.get(lang, "input-count-property-name"));
+        ui.add(DragValue::new(input_count).clamp_range(MIN_INPUT_COUNT..=MAX_INPUT_COUNT))
+            .changed()
+    })
+    .inner
+}
+
+/// Checks the bit ranges of a splitter and returns the locale key of the first error found.
+fn validate_splitter_ranges(width: NonZeroU8, ranges: &[(u8, u8)]) -> Option<&'static str> {
+    if ranges.iter().any(|&(_, end)| end >= width.get()) {
+        return Some("splitter-range-bounds-error");
+    }
+
+    for (i, &(start_a, end_a)) in ranges.iter().enumerate() {
+        for &(start_b, end_b) in &ranges[(i + 1)..] {
+            if (start_a <= end_b) && (start_b <= end_a) {
+                return Some("splitter-range-overlap-error");
+            }
+        }
+    }
+
+    let total_width: u32 = ranges
+        .iter()
+        .map(|&(start, end)| (end as u32) - (start as u32) + 1)
+        .sum();
+    if total_width > (width.get() as u32) {
+        return Some("splitter-range-width-error");
+    }
+
+    None
 }
 
 #[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -401,7 +1193,7 @@ impl Rotation {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Component {
     pub kind: ComponentKind,
     pub position_x: NumericTextValue<i32>,
@@ -436,14 +1228,14 @@ impl Component {
         let mut anchors = self.kind.anchors();
         for anchor in anchors.iter_mut() {
             if self.mirrored {
-                anchor.position.x = -anchor.position.x;
+                anchor.position = anchor.position.mirror_x();
             }
 
             anchor.position = match self.rotation {
                 Rotation::Deg0 => anchor.position,
-                Rotation::Deg90 => Vec2i::new(-anchor.position.y, anchor.position.x),
+                Rotation::Deg90 => anchor.position.rotate_90_ccw(),
                 Rotation::Deg180 => -anchor.position,
-                Rotation::Deg270 => Vec2i::new(anchor.position.y, -anchor.position.x),
+                Rotation::Deg270 => anchor.position.rotate_90_cw(),
             };
 
             anchor.position.x += *self.position_x.get();
@@ -452,6 +1244,20 @@ impl Component {
         anchors
     }
 
+    /// Applies the mirroring and rotation of the component to a direction in component space
+    pub fn transform_direction(&self, mut dir: Vec2f) -> Vec2f {
+        if self.mirrored {
+            dir.x = -dir.x;
+        }
+
+        match self.rotation {
+            Rotation::Deg0 => dir,
+            Rotation::Deg90 => dir.perp(),
+            Rotation::Deg180 => -dir,
+            Rotation::Deg270 => -dir.perp(),
+        }
+    }
+
     pub fn bounding_box(&self) -> Rectangle {
         let mut bb = self.kind.bounding_box();
 
@@ -501,12 +1307,16 @@ impl Component {
 
         ui.horizontal(|ui| {
             ui.label("X:");
-            requires_redraw |= ui.numeric_text_edit(&mut self.position_x).lost_focus();
+            requires_redraw |= ui
+                .numeric_text_edit_clamped(&mut self.position_x, -MAX_COORDINATE..=MAX_COORDINATE)
+                .lost_focus();
         });
 
         ui.horizontal(|ui| {
             ui.label("Y:");
-            requires_redraw |= ui.numeric_text_edit(&mut self.position_y).lost_focus();
+            requires_redraw |= ui
+                .numeric_text_edit_clamped(&mut self.position_y, -MAX_COORDINATE..=MAX_COORDINATE)
+                .lost_focus();
         });
 
         ui.horizontal(|ui| {
@@ -543,3 +1353,104 @@ impl Component {
         requires_redraw
     }
 }
+
+/// Returns the value if all items are equal, or `None` if they differ or there are none
+fn common_value<T: PartialEq>(mut values: impl Iterator<Item = T>) -> Option<T> {
+    let first = values.next()?;
+    values.all(|value| value == first).then_some(first)
+}
+
+/// Shows the properties shared by multiple components and applies edits to all of them.
+/// `width_buffer` holds the text of the bit-width field between frames.
+pub fn update_shared_properties(
+    components: &mut [Component],
+    indices: &[usize],
+    width_buffer: &mut String,
+    ui: &mut Ui,
+    locale_manager: &LocaleManager,
+    lang: &LangId,
+) -> bool {
+    let mut requires_redraw = false;
+    let mixed_text = locale_manager.get(lang, "mixed-property-value");
+
+    let has_width = indices
+        .iter()
+        .any(|&i| components[i].kind.width().is_some());
+
+    if has_width {
+        ui.horizontal(|ui| {
+            ui.label(locale_manager.get(lang, "bit-width-property-name"));
+
+            let response =
+                ui.add(TextEdit::singleline(width_buffer).hint_text(mixed_text.as_ref()));
+            if response.lost_focus() {
+                if let Some(new_width) = parse_numeric::<NonZeroU8>(width_buffer) {
+                    let new_width = new_width.clamp(MIN_BIT_WIDTH, MAX_BIT_WIDTH);
+                    for &i in indices {
+                        if components[i].kind.width().is_some_and(|w| w != new_width) {
+                            components[i].kind.set_width(new_width);
+                            requires_redraw = true;
+                        }
+                    }
+                }
+            }
+
+            if !response.has_focus() {
+                width_buffer.clear();
+                if let Some(width) =
+                    common_value(indices.iter().filter_map(|&i| components[i].kind.width()))
+                {
+                    width_buffer.push_str(&width.to_string());
+                }
+            }
+        });
+    } else {
+        width_buffer.clear();
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(locale_manager.get(lang, "rotation-property-name"));
+
+        let common_rotation = common_value(indices.iter().map(|&i| components[i].rotation));
+        ComboBox::from_id_source("shared_rotation_property")
+            .selected_text(common_rotation.map(Rotation::as_str).unwrap_or(&mixed_text))
+            .show_ui(ui, |ui| {
+                for rot in Rotation::ALL {
+                    if ui
+                        .selectable_label(common_rotation == Some(rot), rot.as_str())
+                        .clicked()
+                    {
+                        for &i in indices {
+                            if components[i].rotation != rot {
+                                components[i].rotation = rot;
+                                requires_redraw = true;
+                            }
+                        }
+                    }
+                }
+            });
+    });
+
+    let common_mirrored = common_value(indices.iter().map(|&i| components[i].mirrored));
+    let mut mirrored = common_mirrored.unwrap_or(false);
+    let mut mirrored_text = locale_manager
+        .get(lang, "mirrored-property-name")
+        .into_owned();
+    if common_mirrored.is_none() {
+        mirrored_text.push(' ');
+        mirrored_text.push_str(&mixed_text);
+    }
+
+    if ui.checkbox(&mut mirrored, mirrored_text).changed() {
+        for &i in indices {
+            let component = &mut components[i];
+            if component.mirrored != mirrored {
+                component.mirrored = mirrored;
+                component.rotation = component.rotation.mirror();
+                requires_redraw = true;
+            }
+        }
+    }
+
+    requires_redraw
+}
@@ -3,25 +3,33 @@ mod native {
     use std::path::{Path, PathBuf};
 
     pub struct FileDialog {
-        open_file: Option<(PathBuf, Vec<u8>)>,
+        open_files: Vec<(PathBuf, Vec<u8>)>,
     }
 
     impl FileDialog {
         #[inline]
         pub fn new() -> Option<Self> {
-            Some(Self { open_file: None })
+            Some(Self {
+                open_files: Vec::new(),
+            })
         }
 
         pub fn open(&mut self) {
-            self.open_file = rfd::FileDialog::new().pick_file().and_then(|path| {
-                let data = std::fs::read(&path).ok()?;
-                Some((path, data))
-            });
+            for path in rfd::FileDialog::new().pick_files().unwrap_or_default() {
+                self.open_path(path);
+            }
+        }
+
+        /// Opens a known file without asking the user
+        pub fn open_path(&mut self, path: PathBuf) {
+            if let Ok(data) = std::fs::read(&path) {
+                self.open_files.push((path, data));
+            }
         }
 
         #[inline]
-        pub fn get(&mut self) -> Option<(PathBuf, Vec<u8>)> {
-            self.open_file.take()
+        pub fn get(&mut self) -> Vec<(PathBuf, Vec<u8>)> {
+            std::mem::take(&mut self.open_files)
         }
 
         pub fn save(
@@ -29,17 +37,31 @@ mod native {
             file_name: Option<&Path>,
             data: &[u8],
         ) -> std::io::Result<Option<PathBuf>> {
+            if let Some(path) = self.pick_save_path(file_name, &[]) {
+                std::fs::write(&path, data)?;
+                Ok(Some(path))
+            } else {
+                Ok(None)
+            }
+        }
+
+        /// Asks the user where to save a file, without writing anything yet.
+        /// Each filter is a name and the file extensions it allows.
+        pub fn pick_save_path(
+            &self,
+            file_name: Option<&Path>,
+            filters: &[(&str, &[&str])],
+        ) -> Option<PathBuf> {
             let mut dialog = rfd::FileDialog::new();
             if let Some(file_name) = file_name {
                 dialog = dialog.set_file_name(file_name.to_str().expect("invalid path"));
             }
 
-            if let Some(path) = dialog.save_file() {
-                std::fs::write(&path, data)?;
-                Ok(Some(path))
-            } else {
-                Ok(None)
+            for (name, extensions) in filters {
+                dialog = dialog.add_filter(name, extensions);
             }
+
+            dialog.save_file()
         }
     }
 }
@@ -2,6 +2,7 @@ use fluent::{FluentBundle, FluentResource};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
+pub use fluent::FluentArgs;
 pub use unic_langid::langid;
 pub use unic_langid::LanguageIdentifier as LangId;
 
@@ -24,16 +25,18 @@ impl Locale {
         };
 
         let mut bundle = FluentBundle::new(vec![lang]);
+        // Unicode isolation marks around arguments are rendered as boxes by egui
+        bundle.set_use_isolating(false);
         bundle.add_resource(res).expect("failed to add resource");
 
         Self { bundle }
     }
 
-    fn get<'a>(&'a self, key: &'static str) -> Option<Cow<'a, str>> {
+    fn get<'a>(&'a self, key: &'static str, args: Option<&FluentArgs>) -> Option<Cow<'a, str>> {
         let msg = self.bundle.get_message(key)?;
         let pattern = msg.value()?;
         let mut errors = vec![];
-        let value = self.bundle.format_pattern(pattern, None, &mut errors);
+        let value = self.bundle.format_pattern(pattern, args, &mut errors);
 
         if !errors.is_empty() {
             let mut error_value = String::new();
@@ -73,6 +76,37 @@ macro_rules! locale {
     }};
 }
 
+/// Derives a pseudo-locale from the English strings to spot hardcoded and truncated text.
+///
+/// Every value is wrapped in brackets and padded by about a third of its length.
+#[cfg(debug_assertions)]
+fn pseudo_localize(source: &str) -> String {
+    let mut result = String::with_capacity(source.len() * 2);
+
+    for line in source.lines() {
+        match line.split_once(" = ") {
+            Some((key @ ("english-lang-name" | "native-lang-name"), _)) => {
+                result.push_str(key);
+                result.push_str(" = Pseudo-locale");
+            }
+            Some((key, value)) => {
+                let padding = (value.chars().count() + 2) / 3;
+
+                result.push_str(key);
+                result.push_str(" = [");
+                result.push_str(value);
+                result.extend(std::iter::repeat_n('~', padding));
+                result.push(']');
+            }
+            None => result.push_str(line),
+        }
+
+        result.push('\n');
+    }
+
+    result
+}
+
 #[repr(transparent)]
 pub struct LocaleManager {
     locales: HashMap<LangId, Locale>,
@@ -84,6 +118,18 @@ impl LocaleManager {
 
         locale!(locales, "en");
         locale!(locales, "de");
+        locale!(locales, "fr");
+        locale!(locales, "es");
+
+        #[cfg(debug_assertions)]
+        {
+            const SOURCE: &str =
+                include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/lang/en.ftl"));
+
+            let lang = langid!("en-XA");
+            let locale = Locale::load(lang.clone(), pseudo_localize(SOURCE));
+            locales.insert(lang, locale);
+        }
 
         assert!(locales.get(&DEFAULT_LANG).is_some());
         Self { locales }
@@ -92,19 +138,117 @@ impl LocaleManager {
     #[inline]
     pub fn langs(&self) -> impl Iterator<Item = &LangId> {
         let mut langs: Vec<_> = self.locales.keys().collect();
-        langs.sort_by_cached_key(|&lang| self.locales[lang].get("english-lang-name"));
+        langs.sort_by_cached_key(|&lang| self.locales[lang].get("english-lang-name", None));
         langs.into_iter()
     }
 
-    fn get_default<'a>(&'a self, key: &'static str) -> Cow<'a, str> {
+    fn get_default<'a>(&'a self, key: &'static str, args: Option<&FluentArgs>) -> Cow<'a, str> {
         let locale = &self.locales[&DEFAULT_LANG];
-        locale.get(key).unwrap_or(key.into())
+        locale.get(key, args).unwrap_or(key.into())
     }
 
+    #[inline]
     pub fn get<'a>(&'a self, lang: &LangId, key: &'static str) -> Cow<'a, str> {
-        self.locales
+        self.lookup(lang, key, None)
+    }
+
+    #[inline]
+    pub fn get_args<'a>(
+        &'a self,
+        lang: &LangId,
+        key: &'static str,
+        args: &FluentArgs,
+    ) -> Cow<'a, str> {
+        self.lookup(lang, key, Some(args))
+    }
+
+    /// Looks up a message, stripping variant, region and script subtags in that order
+    /// until a loaded language has it, e.g. `de-AT` → `de` → `en`.
+    fn lookup<'a>(
+        &'a self,
+        lang: &LangId,
+        key: &'static str,
+        args: Option<&FluentArgs>,
+    ) -> Cow<'a, str> {
+        if let Some(value) = self
+            .locales
             .get(lang)
-            .and_then(|locale| locale.get(key))
-            .unwrap_or_else(|| self.get_default(key))
+            .and_then(|locale| locale.get(key, args))
+        {
+            return value;
+        }
+
+        let mut lang = lang.clone();
+        loop {
+            if lang.variants().len() > 0 {
+                lang.clear_variants();
+            } else if lang.region.is_some() {
+                lang.region = None;
+            } else if lang.script.is_some() {
+                lang.script = None;
+            } else {
+                break;
+            }
+
+            if let Some(value) = self
+                .locales
+                .get(&lang)
+                .and_then(|locale| locale.get(key, args))
+            {
+                return value;
+            }
+        }
+
+        self.get_default(key, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_manager() -> LocaleManager {
+        let mut locales = HashMap::new();
+        locales.insert(
+            DEFAULT_LANG,
+            Locale::load(
+                DEFAULT_LANG,
+                "greeting = Hello\nfarewell = Goodbye\n".to_owned(),
+            ),
+        );
+        locales.insert(
+            langid!("fr"),
+            Locale::load(langid!("fr"), "greeting = Bonjour\n".to_owned()),
+        );
+
+        LocaleManager { locales }
+    }
+
+    #[test]
+    fn region_falls_back_to_language() {
+        let manager = test_manager();
+        assert_eq!(manager.get(&langid!("fr-CA"), "greeting"), "Bonjour");
+        assert_eq!(manager.get(&langid!("fr-Latn-CA"), "greeting"), "Bonjour");
+    }
+
+    #[test]
+    fn missing_key_falls_back_to_default() {
+        let manager = test_manager();
+        assert_eq!(manager.get(&langid!("fr"), "farewell"), "Goodbye");
+        assert_eq!(manager.get(&langid!("fr-CA"), "farewell"), "Goodbye");
+        assert_eq!(manager.get(&langid!("de-AT"), "greeting"), "Hello");
+    }
+
+    #[test]
+    fn unknown_key_is_returned_verbatim() {
+        let manager = test_manager();
+        assert_eq!(manager.get(&langid!("fr-CA"), "unknown-key"), "unknown-key");
+    }
+
+    #[test]
+    fn bundled_locales_fall_back_by_region() {
+        let manager = LocaleManager::init();
+        assert_eq!(manager.get(&langid!("fr-CA"), "file-menu-item"), "Fichier");
+        assert_eq!(manager.get(&langid!("en-GB"), "file-menu-item"), "File");
     }
 }
@@ -302,6 +302,33 @@ impl Vec2i {
             y: self.y as f32,
         }
     }
+
+    /// Rotates the vector counterclockwise by 90°
+    #[inline]
+    pub fn rotate_90_ccw(self) -> Self {
+        Self {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    /// Rotates the vector clockwise by 90°
+    #[inline]
+    pub fn rotate_90_cw(self) -> Self {
+        Self {
+            x: self.y,
+            y: -self.x,
+        }
+    }
+
+    /// Mirrors the vector along the Y axis
+    #[inline]
+    pub fn mirror_x(self) -> Self {
+        Self {
+            x: -self.x,
+            y: self.y,
+        }
+    }
 }
 
 def_vec2!(
@@ -321,6 +348,17 @@ impl Vec2f {
         self / self.len()
     }
 
+    /// Like `normalized`, but returns zero instead of NaN for (nearly) zero-length vectors
+    #[inline]
+    pub fn normalized_or_zero(self) -> Self {
+        let len = self.len();
+        if len < f32::EPSILON {
+            Self::ZERO
+        } else {
+            self / len
+        }
+    }
+
     #[inline]
     pub fn round(self) -> Self {
         Self {
@@ -352,6 +390,41 @@ impl Vec2f {
             y: self.y as i32,
         }
     }
+
+    /// Linear interpolation between `self` (at `t = 0`) and `other` (at `t = 1`)
+    #[inline]
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+
+    /// Rotates the vector counterclockwise by the given angle
+    #[inline]
+    pub fn rotate(self, radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self {
+            x: (self.x * cos) - (self.y * sin),
+            y: (self.x * sin) + (self.y * cos),
+        }
+    }
+
+    /// The vector rotated counterclockwise by 90°
+    #[inline]
+    pub fn perp(self) -> Self {
+        Self {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    /// Shortest distance between this point and the line segment from `a` to `b`
+    pub fn distance_to_segment(self, a: Self, b: Self) -> f32 {
+        let ab = b - a;
+        // Zero-length segments have no direction, their closest point is always `a`
+        let dir = ab.normalized_or_zero();
+
+        let t = (self - a).dot(dir).clamp(0.0, ab.len());
+        (self - (a + dir * t)).len()
+    }
 }
 
 impl From<egui::Vec2> for Vec2f {
@@ -374,10 +447,85 @@ pub struct Rectangle {
 
 #[allow(dead_code)]
 impl Rectangle {
+    /// The smallest rectangle that has `a` and `b` as corners
+    pub fn from_points(a: Vec2f, b: Vec2f) -> Self {
+        Self {
+            top: a.y.max(b.y),
+            bottom: a.y.min(b.y),
+            left: a.x.min(b.x),
+            right: a.x.max(b.x),
+        }
+    }
+
     pub fn contains(&self, p: Vec2f) -> bool {
         (p.x >= self.left) && (p.x <= self.right) && (p.y >= self.bottom) && (p.y <= self.top)
     }
 
+    /// Checks whether `other` lies completely inside the rectangle
+    pub fn contains_rect(&self, other: &Rectangle) -> bool {
+        (other.left >= self.left)
+            && (other.right <= self.right)
+            && (other.bottom >= self.bottom)
+            && (other.top <= self.top)
+    }
+
+    /// Checks whether any part of the line segment from `a` to `b` lies inside the rectangle
+    pub fn intersects_segment(&self, a: Vec2f, b: Vec2f) -> bool {
+        if self.contains(a) || self.contains(b) {
+            return true;
+        }
+
+        // Liang-Barsky clipping
+        let d = b - a;
+        let mut t_enter = 0.0f32;
+        let mut t_exit = 1.0f32;
+
+        for (p, q) in [
+            (-d.x, a.x - self.left),
+            (d.x, self.right - a.x),
+            (-d.y, a.y - self.bottom),
+            (d.y, self.top - a.y),
+        ] {
+            if p == 0.0 {
+                // Parallel to this edge and completely outside of it
+                if q < 0.0 {
+                    return false;
+                }
+            } else {
+                let t = q / p;
+                if p < 0.0 {
+                    t_enter = t_enter.max(t);
+                } else {
+                    t_exit = t_exit.min(t);
+                }
+
+                if t_enter > t_exit {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Checks whether the rectangles overlap, rectangles that only touch count as overlapping
+    pub fn intersects(&self, other: &Rectangle) -> bool {
+        (self.left <= other.right)
+            && (self.right >= other.left)
+            && (self.bottom <= other.top)
+            && (self.top >= other.bottom)
+    }
+
+    /// The smallest rectangle containing both rectangles
+    pub fn union(&self, other: &Rectangle) -> Rectangle {
+        Rectangle {
+            top: self.top.max(other.top),
+            bottom: self.bottom.min(other.bottom),
+            left: self.left.min(other.left),
+            right: self.right.max(other.right),
+        }
+    }
+
     pub fn center(&self) -> Vec2f {
         let min = Vec2f::new(self.left, self.bottom);
         let max = Vec2f::new(self.right, self.top);
@@ -395,29 +543,220 @@ impl Rectangle {
     }
 }
 
-pub struct Triangle {
-    pub a: Vec2f,
-    pub b: Vec2f,
-    pub c: Vec2f,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl Triangle {
-    pub fn contains(&self, p: Vec2f) -> bool {
-        let ca = self.a - self.c;
-        let ab = self.b - self.a;
-        let cp = p - self.c;
-        let ap = p - self.a;
-        let s = ca.cross(cp);
-        let t = ab.cross(ap);
+    fn rect(left: f32, bottom: f32, right: f32, top: f32) -> Rectangle {
+        Rectangle {
+            top,
+            bottom,
+            left,
+            right,
+        }
+    }
+
+    #[test]
+    fn segment_crossing_rectangle() {
+        let r = rect(0.0, 0.0, 2.0, 2.0);
+        assert!(r.intersects_segment(Vec2f::new(-1.0, 1.0), Vec2f::new(3.0, 1.0)));
+        assert!(r.intersects_segment(Vec2f::new(1.0, 3.0), Vec2f::new(1.0, -1.0)));
+        assert!(r.intersects_segment(Vec2f::new(-1.0, -1.0), Vec2f::new(3.0, 3.0)));
+        assert!(r.intersects_segment(Vec2f::new(0.5, 0.5), Vec2f::new(1.5, 1.5)));
+    }
 
-        if ((s < 0.0) != (t < 0.0)) && (s != 0.0) && (t != 0.0) {
-            return false;
+    #[test]
+    fn segment_touching_rectangle() {
+        let r = rect(0.0, 0.0, 2.0, 2.0);
+        // Along an edge
+        assert!(r.intersects_segment(Vec2f::new(-1.0, 2.0), Vec2f::new(3.0, 2.0)));
+        // Through a corner
+        assert!(r.intersects_segment(Vec2f::new(-1.0, 1.0), Vec2f::new(1.0, 3.0)));
+        // Ending on an edge
+        assert!(r.intersects_segment(Vec2f::new(-1.0, 1.0), Vec2f::new(0.0, 1.0)));
+    }
+
+    #[test]
+    fn segment_disjoint_from_rectangle() {
+        let r = rect(0.0, 0.0, 2.0, 2.0);
+        assert!(!r.intersects_segment(Vec2f::new(3.0, 0.0), Vec2f::new(3.0, 2.0)));
+        assert!(!r.intersects_segment(Vec2f::new(-1.0, 3.0), Vec2f::new(3.0, 3.0)));
+        assert!(!r.intersects_segment(Vec2f::new(-2.0, 1.0), Vec2f::new(0.0, 3.0)));
+        assert!(!r.intersects_segment(Vec2f::new(2.5, 1.0), Vec2f::new(4.0, 1.0)));
+    }
+
+    fn assert_rect_eq(a: Rectangle, b: Rectangle) {
+        assert_eq!(
+            (a.left, a.bottom, a.right, a.top),
+            (b.left, b.bottom, b.right, b.top)
+        );
+    }
+
+    #[test]
+    fn rectangle_intersection() {
+        let r = rect(0.0, 0.0, 2.0, 2.0);
+
+        // Disjoint
+        assert!(!r.intersects(&rect(3.0, 0.0, 4.0, 2.0)));
+        assert!(!r.intersects(&rect(0.0, -2.0, 2.0, -1.0)));
+
+        // Touching edges and corners
+        assert!(r.intersects(&rect(2.0, 0.0, 4.0, 2.0)));
+        assert!(r.intersects(&rect(-2.0, -2.0, 0.0, 0.0)));
+
+        // Overlapping and nested, in both directions
+        assert!(r.intersects(&rect(1.0, 1.0, 3.0, 3.0)));
+        assert!(r.intersects(&rect(0.5, 0.5, 1.5, 1.5)));
+        assert!(rect(0.5, 0.5, 1.5, 1.5).intersects(&r));
+    }
+
+    #[test]
+    fn rectangle_union() {
+        let r = rect(0.0, 0.0, 2.0, 2.0);
+
+        assert_rect_eq(
+            r.union(&rect(3.0, -1.0, 4.0, 1.0)),
+            rect(0.0, -1.0, 4.0, 2.0),
+        );
+        assert_rect_eq(r.union(&rect(2.0, 0.0, 4.0, 2.0)), rect(0.0, 0.0, 4.0, 2.0));
+        assert_rect_eq(r.union(&rect(0.5, 0.5, 1.5, 1.5)), r);
+        assert_rect_eq(rect(0.5, 0.5, 1.5, 1.5).union(&r), r);
+    }
+
+    #[test]
+    fn rectangle_from_points() {
+        let expected = rect(-1.0, -2.0, 3.0, 4.0);
+
+        // The result doesn't depend on which corners are passed or in which order
+        assert_rect_eq(
+            Rectangle::from_points(Vec2f::new(-1.0, -2.0), Vec2f::new(3.0, 4.0)),
+            expected,
+        );
+        assert_rect_eq(
+            Rectangle::from_points(Vec2f::new(3.0, 4.0), Vec2f::new(-1.0, -2.0)),
+            expected,
+        );
+        assert_rect_eq(
+            Rectangle::from_points(Vec2f::new(-1.0, 4.0), Vec2f::new(3.0, -2.0)),
+            expected,
+        );
+
+        let point = Rectangle::from_points(Vec2f::new(1.0, 1.0), Vec2f::new(1.0, 1.0));
+        assert_eq!((point.width(), point.height()), (0.0, 0.0));
+    }
+
+    fn assert_vec_near(a: Vec2f, b: Vec2f) {
+        assert!((a - b).len() < 1e-5, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn vec2f_lerp() {
+        let a = Vec2f::new(1.0, -2.0);
+        let b = Vec2f::new(5.0, 6.0);
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.5), Vec2f::new(3.0, 2.0));
+        assert_eq!(a.lerp(b, 0.25), Vec2f::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn vec2f_perp_matches_swizzles() {
+        for v in [
+            Vec2f::new(1.0, 0.0),
+            Vec2f::new(0.0, 1.0),
+            Vec2f::new(3.0, -2.0),
+            Vec2f::new(-1.5, 4.0),
+        ] {
+            // Counterclockwise and clockwise cases previously written out inline
+            assert_eq!(v.perp(), Vec2f::new(-v.y, v.x));
+            assert_eq!(-v.perp(), Vec2f::new(v.y, -v.x));
+            assert_eq!(v.perp().dot(v), 0.0);
+        }
+    }
+
+    #[test]
+    fn vec2f_rotate_right_angles() {
+        use std::f32::consts::{FRAC_PI_2, PI};
+
+        for v in [
+            Vec2f::new(1.0, 0.0),
+            Vec2f::new(3.0, -2.0),
+            Vec2f::new(-1.5, 4.0),
+        ] {
+            assert_vec_near(v.rotate(0.0), v);
+            assert_vec_near(v.rotate(FRAC_PI_2), v.perp());
+            assert_vec_near(v.rotate(PI), -v);
+            assert_vec_near(v.rotate(-FRAC_PI_2), -v.perp());
+        }
+    }
+
+    #[test]
+    fn vec2i_rotate_full_circle() {
+        for start in [Vec2i::new(3, -7), Vec2i::new(-12, 5), Vec2i::new(0, 1)] {
+            let mut ccw = start;
+            let mut cw = start;
+            for _ in 0..4 {
+                ccw = ccw.rotate_90_ccw();
+                cw = cw.rotate_90_cw();
+            }
+
+            assert_eq!(ccw, start);
+            assert_eq!(cw, start);
+            assert_eq!(start.rotate_90_ccw().rotate_90_cw(), start);
+            assert_eq!(start.rotate_90_ccw().rotate_90_ccw(), -start);
         }
+    }
+
+    #[test]
+    fn vec2i_rotate_matches_vec2f() {
+        let v = Vec2i::new(3, -7);
+        assert_eq!(v.rotate_90_ccw().to_vec2f(), v.to_vec2f().perp());
+        assert_eq!(v.rotate_90_cw().to_vec2f(), -v.to_vec2f().perp());
+    }
 
-        let bc = self.c - self.b;
-        let bp = p - self.c;
-        let d = bc.cross(bp);
+    #[test]
+    fn vec2i_mirror_x() {
+        let v = Vec2i::new(3, -7);
+        assert_eq!(v.mirror_x(), Vec2i::new(-3, -7));
+        assert_eq!(v.mirror_x().mirror_x(), v);
+    }
+
+    #[test]
+    fn distance_to_segment() {
+        let a = Vec2f::new(0.0, 0.0);
+        let b = Vec2f::new(4.0, 0.0);
+
+        // Perpendicular to the segment
+        assert_eq!(Vec2f::new(2.0, 3.0).distance_to_segment(a, b), 3.0);
+        assert_eq!(Vec2f::new(2.0, 0.0).distance_to_segment(a, b), 0.0);
+        // Beyond either end, the closest point is the endpoint
+        assert_eq!(Vec2f::new(-3.0, 4.0).distance_to_segment(a, b), 5.0);
+        assert_eq!(Vec2f::new(7.0, -4.0).distance_to_segment(a, b), 5.0);
+    }
+
+    #[test]
+    fn distance_to_zero_length_segment() {
+        let a = Vec2f::new(1.0, 1.0);
+
+        let distance = Vec2f::new(4.0, 5.0).distance_to_segment(a, a);
+        assert!(!distance.is_nan());
+        assert_eq!(distance, 5.0);
+        assert_eq!(a.distance_to_segment(a, a), 0.0);
+    }
 
-        (d == 0.0) || ((d < 0.0) == (s + t <= 0.0))
+    #[test]
+    fn normalized_or_zero() {
+        let zero = Vec2f::ZERO.normalized_or_zero();
+        assert!(!zero.x.is_nan() && !zero.y.is_nan());
+        assert_eq!(zero, Vec2f::ZERO);
+
+        assert_eq!(
+            Vec2f::new(0.0, -3.0).normalized_or_zero(),
+            Vec2f::new(0.0, -1.0)
+        );
+        assert_vec_near(
+            Vec2f::new(3.0, 4.0).normalized_or_zero(),
+            Vec2f::new(0.6, 0.8),
+        );
     }
 }
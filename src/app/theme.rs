@@ -3,9 +3,31 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Theme {
-    #[default]
     Light,
     Dark,
+    /// Follows the light/dark preference of the OS
+    #[default]
+    System,
+}
+
+impl Theme {
+    /// Resolves `System` to a concrete theme, falling back to light if the OS has no preference
+    pub fn resolve(self, system_theme: Option<eframe::Theme>) -> Self {
+        match self {
+            Theme::Light | Theme::Dark => self,
+            Theme::System => match system_theme {
+                Some(eframe::Theme::Dark) => Theme::Dark,
+                Some(eframe::Theme::Light) | None => Theme::Light,
+            },
+        }
+    }
+
+    pub fn visuals(self) -> egui::Visuals {
+        match self {
+            Theme::Dark => egui::Visuals::dark(),
+            Theme::Light | Theme::System => egui::Visuals::light(),
+        }
+    }
 }
 
 pub struct ThemedImage {
@@ -19,10 +41,11 @@ impl ThemedImage {
         Self { light, dark }
     }
 
+    /// Expects a resolved theme, `System` is treated as light
     #[inline]
     pub fn source(&self, theme: Theme) -> ImageSource<'static> {
         match theme {
-            Theme::Light => self.light.clone(),
+            Theme::Light | Theme::System => self.light.clone(),
             Theme::Dark => self.dark.clone(),
         }
     }
@@ -1,9 +1,13 @@
 mod buffer;
 mod pass;
+use pass::RenderStateEx;
 
 mod geometry;
 use geometry::*;
 
+mod grid;
+use grid::*;
+
 mod text;
 use text::*;
 
@@ -11,9 +15,11 @@ mod selection_box;
 use selection_box::*;
 
 use super::circuit::*;
-use crate::app::math::Vec2f;
+use crate::app::math::{Rectangle, Vec2f, Vec2i};
+use crate::{HashMap, HashSet};
 use eframe::egui_wgpu::RenderState;
 use egui::TextureId;
+use serde::{Deserialize, Serialize};
 use vello::kurbo::*;
 use vello::peniko::*;
 use wgpu::{FilterMode, Texture, TextureView};
@@ -41,7 +47,9 @@ fn create_render_target(render_state: &RenderState, width: u32, height: u32) ->
         format: TextureFormat::Rgba8Unorm,
         usage: TextureUsages::RENDER_ATTACHMENT
             | TextureUsages::TEXTURE_BINDING
-            | TextureUsages::STORAGE_BINDING,
+            | TextureUsages::STORAGE_BINDING
+            | TextureUsages::COPY_SRC
+            | TextureUsages::COPY_DST,
         view_formats: &[],
     };
 
@@ -54,25 +62,74 @@ fn create_render_target(render_state: &RenderState, width: u32, height: u32) ->
 pub const BASE_ZOOM: f32 = 10.0; // Logical pixels per unit
 pub const LOGICAL_PIXEL_SIZE: f32 = 1.0 / BASE_ZOOM;
 
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GridStyle {
+    #[default]
+    Dots,
+    Lines,
+    Crosses,
+}
+
+pub const MIN_GRID_SPACING_UNITS: u32 = 1;
+pub const MAX_GRID_SPACING_UNITS: u32 = 16;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridSettings {
+    pub style: GridStyle,
+    /// Distance between minor grid points, in units
+    pub minor_spacing: u32,
+    /// Number of minor grid cells per major grid cell, or 0 to disable the major grid
+    pub major_spacing: u32,
+    /// Opacity of the minor grid, the major grid is drawn twice as opaque
+    pub opacity: f32,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            style: GridStyle::default(),
+            minor_spacing: 1,
+            major_spacing: 0,
+            opacity: 1.0,
+        }
+    }
+}
+
 pub struct ViewportColors {
     pub background_color: Color,
     pub grid_color: Color,
     pub component_color: Color,
     pub selected_component_color: Color,
+    pub wire_color: Color,
+    pub selected_wire_color: Color,
+    pub conflict_wire_color: Color,
+    pub input_anchor_color: Color,
+    pub output_anchor_color: Color,
+    pub bidirectional_anchor_color: Color,
+    pub passive_anchor_color: Color,
 }
 
 pub struct Viewport {
+    /// The circuit without overlays, so they can be redrawn on their own
+    base_target: RenderTarget,
     render_target: RenderTarget,
     texture_id: TextureId,
     renderer: vello::Renderer,
     scene: vello::Scene,
+    /// Wires and components, which only change when the circuit does
+    circuit_fragment: vello::SceneFragment,
+    /// The area `circuit_fragment` was built for, `None` if it has to be rebuilt
+    circuit_fragment_rect: Option<Rectangle>,
     geometry: GeometryStore,
+    grid_pass: GridPass,
     text_pass: TextPass,
     selection_box_pass: SelectionBoxPass,
 }
 
 impl Viewport {
     pub fn create(render_state: &RenderState, width: u32, height: u32) -> Self {
+        let base_target = create_render_target(render_state, width, height);
         let render_target = create_render_target(render_state, width, height);
 
         let texture_id = render_state.renderer.write().register_native_texture(
@@ -93,11 +150,15 @@ impl Viewport {
         .unwrap();
 
         Self {
+            base_target,
             render_target,
             texture_id,
             renderer,
             scene: vello::Scene::new(),
+            circuit_fragment: vello::SceneFragment::new(),
+            circuit_fragment_rect: None,
             geometry: GeometryStore::new(),
+            grid_pass: GridPass::create(render_state),
             text_pass: TextPass::create(render_state),
             selection_box_pass: SelectionBoxPass::create(render_state),
         }
@@ -110,6 +171,7 @@ impl Viewport {
             return false;
         }
 
+        self.base_target = create_render_target(render_state, width, height);
         self.render_target = create_render_target(render_state, width, height);
 
         render_state
@@ -130,26 +192,177 @@ impl Viewport {
         self.texture_id
     }
 
+    /// Reads back the last drawn frame and encodes it as a PNG image.
+    ///
+    /// Only available on native targets because it blocks until the GPU is done.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn capture_png(&self, render_state: &RenderState) -> Vec<u8> {
+        use wgpu::*;
+
+        let width = self.render_target.texture.width();
+        let height = self.render_target.texture.height();
+
+        // Rows of the copy destination have to be aligned
+        let unpadded_bytes_per_row = width * 4;
+        let padded_bytes_per_row =
+            unpadded_bytes_per_row.next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer = render_state.device.create_buffer(&BufferDescriptor {
+            label: Some("Viewport capture"),
+            size: (padded_bytes_per_row as u64) * (height as u64),
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = render_state
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Viewport capture"),
+            });
+
+        encoder.copy_texture_to_buffer(
+            ImageCopyTexture {
+                texture: &self.render_target.texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        render_state.queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        slice.map_async(MapMode::Read, |_| ());
+        render_state.device.poll(Maintain::Wait);
+
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row as usize) * (height as usize));
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks_exact(padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..(unpadded_bytes_per_row as usize)]);
+            }
+        }
+        buffer.unmap();
+
+        let image = image::RgbaImage::from_raw(width, height, pixels).expect("invalid image size");
+        let mut png = Vec::new();
+        image
+            .write_to(
+                &mut std::io::Cursor::new(&mut png),
+                image::ImageOutputFormat::Png,
+            )
+            .expect("error encoding image");
+        png
+    }
+
+    #[inline]
+    pub fn size(&self) -> Vec2f {
+        Vec2f::new(
+            self.render_target.texture.width() as f32,
+            self.render_target.texture.height() as f32,
+        )
+    }
+
     pub fn draw(
         &mut self,
         render_state: &RenderState,
         circuit: Option<&Circuit>,
+        circuit_changed: bool,
+        width_conflicts: Option<&HashSet<usize>>,
+        grid: &GridSettings,
+        colors: &ViewportColors,
+    ) {
+        let (offset, zoom) = circuit
+            .map(|c| (c.offset(), c.zoom()))
+            .unwrap_or((Vec2f::default(), DEFAULT_ZOOM));
+
+        render_state.batch(Some("Viewport"), |encoder| {
+            self.draw_base(
+                render_state,
+                encoder,
+                circuit,
+                offset,
+                zoom,
+                circuit_changed,
+                width_conflicts,
+                grid,
+                colors,
+            );
+
+            self.encode_overlay(
+                render_state,
+                encoder,
+                circuit,
+                colors.selected_component_color,
+            );
+        });
+    }
+
+    /// Renders the circuit, grid and text into the base target. The grid and text are recorded
+    /// into `encoder`, so they are only drawn once it is submitted.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_base(
+        &mut self,
+        render_state: &RenderState,
+        encoder: &mut wgpu::CommandEncoder,
+        circuit: Option<&Circuit>,
+        offset: Vec2f,
+        zoom: f32,
+        circuit_changed: bool,
+        width_conflicts: Option<&HashSet<usize>>,
+        grid: &GridSettings,
         colors: &ViewportColors,
     ) {
         let width = self.render_target.texture.width();
         let height = self.render_target.texture.height();
         let resolution = Vec2f::new(width as f32, height as f32);
 
-        let (offset, zoom) = circuit
-            .map(|c| (c.offset(), c.zoom()))
-            .unwrap_or((Vec2f::default(), DEFAULT_ZOOM));
+        // Grow the culling area a bit so strokes and anchors on the edge of the screen are kept
+        const CULL_MARGIN: f32 = 1.0;
+        let visible_rect = visible_rect(resolution, offset, zoom);
+        let cull_rect = Rectangle {
+            top: visible_rect.top + CULL_MARGIN,
+            bottom: visible_rect.bottom - CULL_MARGIN,
+            left: visible_rect.left - CULL_MARGIN,
+            right: visible_rect.right + CULL_MARGIN,
+        };
 
-        let mut fragment = vello::SceneFragment::new();
-        let mut builder = vello::SceneBuilder::for_fragment(&mut fragment);
-        draw_grid(&mut builder, resolution, offset, zoom, colors.grid_color);
-        if let Some(circuit) = circuit {
-            draw_wires(&mut builder, circuit);
-            draw_components(&mut builder, circuit, colors, &self.geometry);
+        // Panning and zooming reuse the circuit geometry as long as the view stays inside the area
+        // it was built for. It covers a generous area around the view so that is usually the case.
+        let fragment_valid = !circuit_changed
+            && self
+                .circuit_fragment_rect
+                .is_some_and(|rect| rect.contains_rect(&visible_rect));
+
+        if !fragment_valid {
+            let fragment_rect = Rectangle {
+                top: visible_rect.top + visible_rect.height(),
+                bottom: visible_rect.bottom - visible_rect.height(),
+                left: visible_rect.left - visible_rect.width(),
+                right: visible_rect.right + visible_rect.width(),
+            };
+            let fragment_cull_rect = Rectangle {
+                top: fragment_rect.top + CULL_MARGIN,
+                bottom: fragment_rect.bottom - CULL_MARGIN,
+                left: fragment_rect.left - CULL_MARGIN,
+                right: fragment_rect.right + CULL_MARGIN,
+            };
+
+            self.build_circuit_fragment(circuit, width_conflicts, colors, &fragment_cull_rect);
+            self.circuit_fragment_rect = Some(fragment_rect);
         }
 
         let mut builder = vello::SceneBuilder::for_scene(&mut self.scene);
@@ -158,7 +371,7 @@ impl Viewport {
         builder.fill(
             Fill::NonZero,
             Affine::IDENTITY,
-            colors.background_color,
+            Color::TRANSPARENT,
             None,
             &Rect::ZERO,
         );
@@ -167,16 +380,17 @@ impl Viewport {
             .then_translate((-offset.x as f64, offset.y as f64).into())
             .then_scale((zoom * BASE_ZOOM) as f64)
             .then_translate(((width as f64) * 0.5, (height as f64) * 0.5).into());
-        builder.append(&fragment, Some(transform));
+        builder.append(&self.circuit_fragment, Some(transform));
 
         self.renderer
             .render_to_texture(
                 &render_state.device,
                 &render_state.queue,
                 &self.scene,
-                &self.render_target.view,
+                &self.base_target.view,
+                // The grid pass fills in the background afterwards
                 &vello::RenderParams {
-                    base_color: colors.background_color,
+                    base_color: Color::TRANSPARENT,
                     width,
                     height,
                     antialiasing_method: vello::AaConfig::Area,
@@ -184,86 +398,226 @@ impl Viewport {
             )
             .unwrap();
 
+        self.grid_pass.draw(
+            render_state,
+            encoder,
+            &self.base_target.view,
+            resolution,
+            offset,
+            zoom,
+            grid,
+            colors.background_color,
+            colors.grid_color,
+        );
+
         if let Some(circuit) = circuit {
             self.text_pass.draw(
                 render_state,
-                &self.render_target.view,
+                encoder,
+                &self.base_target.view,
                 circuit,
                 resolution,
                 offset,
                 zoom,
+                &cull_rect,
+                colors,
+            );
+        }
+    }
+
+    fn build_circuit_fragment(
+        &mut self,
+        circuit: Option<&Circuit>,
+        width_conflicts: Option<&HashSet<usize>>,
+        colors: &ViewportColors,
+        cull_rect: &Rectangle,
+    ) {
+        let mut builder = vello::SceneBuilder::for_fragment(&mut self.circuit_fragment);
+
+        if let Some(circuit) = circuit {
+            // Number of wire segments ending at each point
+            let mut wire_endpoints: HashMap<Vec2i, usize> = HashMap::default();
+            for segment in circuit.wire_segments() {
+                *wire_endpoints.entry(segment.endpoint_a).or_default() += 1;
+                *wire_endpoints.entry(segment.endpoint_b).or_default() += 1;
+            }
+
+            draw_wires(
+                &mut builder,
+                circuit,
+                width_conflicts,
                 colors,
+                &wire_endpoints,
+                cull_rect,
             );
+            draw_components(
+                &mut builder,
+                circuit,
+                colors,
+                &self.geometry,
+                &wire_endpoints,
+                cull_rect,
+            );
+        }
+    }
 
+    /// Redraws the selection box on top of the last drawn circuit, which is a lot cheaper than
+    /// drawing everything again
+    pub fn draw_overlay(
+        &mut self,
+        render_state: &RenderState,
+        circuit: Option<&Circuit>,
+        selection_box_color: Color,
+    ) {
+        render_state.batch(Some("Viewport overlay"), |encoder| {
+            self.encode_overlay(render_state, encoder, circuit, selection_box_color);
+        });
+    }
+
+    fn encode_overlay(
+        &mut self,
+        render_state: &RenderState,
+        encoder: &mut wgpu::CommandEncoder,
+        circuit: Option<&Circuit>,
+        selection_box_color: Color,
+    ) {
+        use wgpu::*;
+
+        let width = self.render_target.texture.width();
+        let height = self.render_target.texture.height();
+        let resolution = Vec2f::new(width as f32, height as f32);
+
+        encoder.copy_texture_to_texture(
+            self.base_target.texture.as_image_copy(),
+            self.render_target.texture.as_image_copy(),
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        if let Some(circuit) = circuit {
             if let Some((box_a, box_b)) = circuit.selection_box() {
                 self.selection_box_pass.draw(
                     render_state,
+                    encoder,
                     &self.render_target.view,
                     resolution,
-                    offset,
-                    zoom,
+                    circuit.offset(),
+                    circuit.zoom(),
                     box_a,
                     box_b,
-                    colors.selected_component_color,
+                    selection_box_color,
                 );
             }
         }
     }
 }
 
-fn draw_grid(
-    builder: &mut vello::SceneBuilder,
-    resolution: Vec2f,
+/// Renders a circuit into a PNG image without a window, for example to generate
+/// documentation or thumbnails. The device can come from any adapter, including a headless one.
+///
+/// `offset` is the point of the circuit in the center of the image. The selection box is not drawn.
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::too_many_arguments)]
+pub fn render_circuit_png(
+    adapter: std::sync::Arc<wgpu::Adapter>,
+    device: std::sync::Arc<wgpu::Device>,
+    queue: std::sync::Arc<wgpu::Queue>,
+    circuit: &Circuit,
+    width: u32,
+    height: u32,
     offset: Vec2f,
     zoom: f32,
-    color: Color,
-) {
-    if zoom > 0.99 {
-        let step = if zoom > 1.99 { 1 } else { 2 };
-
-        let grid_width = resolution.x / (zoom * BASE_ZOOM);
-        let grid_height = resolution.y / (zoom * BASE_ZOOM);
-
-        let left = (offset.x - (grid_width * 0.5)).floor() as i32;
-        let right = (offset.x + (grid_width * 0.5)).ceil() as i32;
-        let bottom = (offset.y - (grid_height * 0.5)).floor() as i32;
-        let top = (offset.y + (grid_height * 0.5)).ceil() as i32;
-
-        let rect = Rect {
-            x0: ((-LOGICAL_PIXEL_SIZE as f64) / 2.0) * (step as f64),
-            x1: ((LOGICAL_PIXEL_SIZE as f64) / 2.0) * (step as f64),
-            y0: ((-LOGICAL_PIXEL_SIZE as f64) / 2.0) * (step as f64),
-            y1: ((LOGICAL_PIXEL_SIZE as f64) / 2.0) * (step as f64),
-        };
+    grid: &GridSettings,
+    colors: &ViewportColors,
+) -> Vec<u8> {
+    const TARGET_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    // The viewport registers its texture with egui, so it needs an egui renderer even here
+    let renderer = eframe::egui_wgpu::Renderer::new(&device, TARGET_FORMAT, None, 1);
+    let render_state = RenderState {
+        adapter,
+        device,
+        queue,
+        target_format: TARGET_FORMAT,
+        renderer: std::sync::Arc::new(egui::mutex::RwLock::new(renderer)),
+    };
 
-        for y in (bottom..=top).filter(|&y| (y % step) == 0) {
-            for x in (left..=right).filter(|&x| (x % step) == 0) {
-                builder.fill(
-                    Fill::NonZero,
-                    Affine::translate((x as f64, y as f64)),
-                    color,
-                    None,
-                    &rect,
-                );
-            }
-        }
+    let mut viewport = Viewport::create(&render_state, width, height);
+    render_state.batch(Some("Headless viewport"), |encoder| {
+        viewport.draw_base(
+            &render_state,
+            encoder,
+            Some(circuit),
+            offset,
+            zoom,
+            true,
+            None,
+            grid,
+            colors,
+        );
+
+        // Only copies the base target into the render target since no circuit is passed
+        viewport.encode_overlay(
+            &render_state,
+            encoder,
+            None,
+            colors.selected_component_color,
+        );
+    });
+
+    viewport.capture_png(&render_state)
+}
+
+/// The area of the circuit that is visible on screen, in logical coordinates
+fn visible_rect(resolution: Vec2f, offset: Vec2f, zoom: f32) -> Rectangle {
+    let grid_width = resolution.x / (zoom * BASE_ZOOM);
+    let grid_height = resolution.y / (zoom * BASE_ZOOM);
+
+    Rectangle {
+        top: offset.y + (grid_height * 0.5),
+        bottom: offset.y - (grid_height * 0.5),
+        left: offset.x - (grid_width * 0.5),
+        right: offset.x + (grid_width * 0.5),
     }
 }
 
-fn draw_wires(builder: &mut vello::SceneBuilder, circuit: &Circuit) {
+fn draw_wires(
+    builder: &mut vello::SceneBuilder,
+    circuit: &Circuit,
+    width_conflicts: Option<&HashSet<usize>>,
+    colors: &ViewportColors,
+    wire_endpoints: &HashMap<Vec2i, usize>,
+    cull_rect: &Rectangle,
+) {
     let stroke = Stroke::new((2.0 * LOGICAL_PIXEL_SIZE) as f64)
         .with_join(Join::Miter)
         .with_caps(Cap::Round);
 
     for (i, segment) in circuit.wire_segments().iter().enumerate() {
+        if !segment.bounding_box().intersects(cull_rect) {
+            continue;
+        }
         let stroke_color = if circuit.selection().contains_wire_segment(i) {
-            Color::rgb8(80, 80, 255)
+            colors.selected_wire_color
+        } else if width_conflicts.is_some_and(|conflicts| conflicts.contains(&i)) {
+            colors.conflict_wire_color
         } else {
             match circuit.sim_state() {
                 SimState::Conflict {
                     conflict_segments, ..
-                } if conflict_segments.contains(&i) => Color::rgb8(192, 0, 0),
-                _ => Color::BLUE,
+                } if conflict_segments.contains(&i) => colors.conflict_wire_color,
+                _ => match circuit.wire_display_state(i) {
+                    WireDisplayState::None => colors.wire_color,
+                    WireDisplayState::Logic0 => Color::rgb8(0, 64, 0),
+                    WireDisplayState::Logic1 => Color::rgb8(0, 192, 0),
+                    WireDisplayState::HighZ | WireDisplayState::Undefined => {
+                        Color::rgb8(128, 128, 128)
+                    }
+                    WireDisplayState::Mixed => Color::rgb8(0, 128, 192),
+                },
             }
         };
 
@@ -289,7 +643,7 @@ fn draw_wires(builder: &mut vello::SceneBuilder, circuit: &Circuit) {
         builder.fill(
             Fill::NonZero,
             Affine::IDENTITY,
-            Color::BLUE,
+            colors.wire_color,
             None,
             &anchor_a,
         );
@@ -297,26 +651,82 @@ fn draw_wires(builder: &mut vello::SceneBuilder, circuit: &Circuit) {
         builder.fill(
             Fill::NonZero,
             Affine::IDENTITY,
-            Color::BLUE,
+            colors.wire_color,
             None,
             &anchor_b,
         );
     }
+
+    // Wires only connect at their endpoints, so crossings without a dot are not connected
+    for (&position, _) in wire_endpoints.iter().filter(|(_, &count)| count >= 3) {
+        let position = position.to_vec2f();
+        if !cull_rect.contains(position) {
+            continue;
+        }
+
+        let junction = Circle::new(
+            (position.x as f64, position.y as f64),
+            (LOGICAL_PIXEL_SIZE * 4.0) as f64,
+        );
+
+        builder.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            colors.wire_color,
+            None,
+            &junction,
+        );
+    }
+}
+
+fn logic_bit_color(bit_state: gsim::LogicBitState) -> Color {
+    match bit_state {
+        gsim::LogicBitState::HighZ => Color {
+            r: 128,
+            g: 128,
+            b: 128,
+            a: 255,
+        },
+        gsim::LogicBitState::Undefined => Color {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        },
+        gsim::LogicBitState::Logic0 => Color {
+            r: 0,
+            g: 64,
+            b: 0,
+            a: 255,
+        },
+        gsim::LogicBitState::Logic1 => Color {
+            r: 0,
+            g: 192,
+            b: 0,
+            a: 255,
+        },
+    }
 }
 
 fn draw_components(
     builder: &mut vello::SceneBuilder,
     circuit: &Circuit,
     colors: &ViewportColors,
-    geometry: &GeometryStore,
+    geometry_store: &GeometryStore,
+    wire_endpoints: &HashMap<Vec2i, usize>,
+    cull_rect: &Rectangle,
 ) {
     use crate::app::component::*;
 
     let stroke = Stroke::new((2.0 * LOGICAL_PIXEL_SIZE) as f64)
         .with_join(Join::Miter)
         .with_caps(Cap::Butt);
+    let pin_stroke = Stroke::new(LOGICAL_PIXEL_SIZE as f64);
 
     for (i, component) in circuit.components().iter().enumerate() {
+        if !component.bounding_box().intersects(cull_rect) {
+            continue;
+        }
         let transform = Affine::scale_non_uniform(if component.mirrored { -1.0 } else { 1.0 }, 1.0)
             .then_rotate(component.rotation.radians())
             .then_translate((component.position().x as f64, component.position().y as f64).into());
@@ -327,18 +737,48 @@ fn draw_components(
             colors.component_color
         };
 
-        let geometry = match component.kind {
-            ComponentKind::Input { .. } | ComponentKind::ClockInput { .. } => {
-                &geometry.input_geometry
+        let splitter_geometry;
+        let geometry = match &component.kind {
+            ComponentKind::Input { .. }
+            | ComponentKind::ClockInput { .. }
+            | ComponentKind::PushButton { .. }
+            | ComponentKind::Port { .. }
+            | ComponentKind::Constant { .. } => &geometry_store.input_geometry,
+            ComponentKind::Output { .. } | ComponentKind::Led { .. } => {
+                &geometry_store.output_geometry
+            }
+            ComponentKind::SevenSegment { .. } => &geometry_store.seven_segment_geometry,
+            ComponentKind::Splitter { ranges, .. } => {
+                splitter_geometry = build_splitter_geometry(ranges.len());
+                &splitter_geometry
             }
-            ComponentKind::Output { .. } => &geometry.output_geometry,
-            ComponentKind::Splitter { .. } => todo!(),
-            ComponentKind::AndGate { .. } => &geometry.and_gate_geometry,
-            ComponentKind::OrGate { .. } => &geometry.or_gate_geometry,
-            ComponentKind::XorGate { .. } => &geometry.xor_gate_geometry,
-            ComponentKind::NandGate { .. } => &geometry.nand_gate_geometry,
-            ComponentKind::NorGate { .. } => &geometry.nor_gate_geometry,
-            ComponentKind::XnorGate { .. } => &geometry.xnor_gate_geometry,
+            ComponentKind::Tunnel { .. } => &geometry_store.tunnel_geometry,
+            ComponentKind::AndGate {
+                inverted: false, ..
+            } => &geometry_store.and_gate_geometry,
+            ComponentKind::OrGate {
+                inverted: false, ..
+            } => &geometry_store.or_gate_geometry,
+            ComponentKind::XorGate {
+                inverted: false, ..
+            } => &geometry_store.xor_gate_geometry,
+            ComponentKind::AndGate { inverted: true, .. } | ComponentKind::NandGate { .. } => {
+                &geometry_store.nand_gate_geometry
+            }
+            ComponentKind::OrGate { inverted: true, .. } | ComponentKind::NorGate { .. } => {
+                &geometry_store.nor_gate_geometry
+            }
+            ComponentKind::XorGate { inverted: true, .. } | ComponentKind::XnorGate { .. } => {
+                &geometry_store.xnor_gate_geometry
+            }
+            ComponentKind::Buffer {
+                inverted: false, ..
+            } => &geometry_store.buffer_geometry,
+            ComponentKind::Buffer { inverted: true, .. } | ComponentKind::NotGate { .. } => {
+                &geometry_store.not_gate_geometry
+            }
+            ComponentKind::TriStateBuffer { .. } => &geometry_store.tri_state_buffer_geometry,
+            ComponentKind::DFlipFlop { .. } => &geometry_store.flip_flop_geometry,
         };
 
         let fill_color = match (circuit.sim_state(), &component.kind) {
@@ -354,6 +794,12 @@ fn draw_components(
                     width, sim_wire, ..
                 },
             )
+            | (
+                SimState::Unstable { sim, .. },
+                ComponentKind::Input {
+                    width, sim_wire, ..
+                },
+            )
             | (
                 SimState::Active { sim, .. },
                 ComponentKind::Output {
@@ -365,36 +811,27 @@ fn draw_components(
                 ComponentKind::Output {
                     width, sim_wire, ..
                 },
-            ) if width.value.get() == 1 => {
-                let state = sim.get_wire_state(*sim_wire).unwrap();
-                let bit_state = state.get_bit_state(0);
-                match bit_state {
-                    gsim::LogicBitState::HighZ => Color {
-                        r: 128,
-                        g: 128,
-                        b: 128,
-                        a: 255,
-                    },
-                    gsim::LogicBitState::Undefined => Color {
-                        r: 255,
-                        g: 0,
-                        b: 0,
-                        a: 255,
-                    },
-                    gsim::LogicBitState::Logic0 => Color {
-                        r: 0,
-                        g: 64,
-                        b: 0,
-                        a: 255,
-                    },
-                    gsim::LogicBitState::Logic1 => Color {
-                        r: 0,
-                        g: 192,
-                        b: 0,
-                        a: 255,
-                    },
-                }
-            }
+            )
+            | (
+                SimState::Unstable { sim, .. },
+                ComponentKind::Output {
+                    width, sim_wire, ..
+                },
+            ) if width.value.get() == 1 => sim
+                .get_wire_state(*sim_wire)
+                .map_or(colors.background_color, |state| {
+                    logic_bit_color(state.get_bit_state(0))
+                }),
+            (SimState::Active { sim, .. }, ComponentKind::Led { sim_wire })
+            | (SimState::Conflict { sim, .. }, ComponentKind::Led { sim_wire })
+            | (SimState::Unstable { sim, .. }, ComponentKind::Led { sim_wire })
+            | (SimState::Active { sim, .. }, ComponentKind::PushButton { sim_wire, .. })
+            | (SimState::Conflict { sim, .. }, ComponentKind::PushButton { sim_wire, .. })
+            | (SimState::Unstable { sim, .. }, ComponentKind::PushButton { sim_wire, .. }) => sim
+                .get_wire_state(*sim_wire)
+                .map_or(colors.background_color, |state| {
+                    logic_bit_color(state.get_bit_state(0))
+                }),
             _ => colors.background_color,
         };
 
@@ -413,12 +850,46 @@ fn draw_components(
             geometry.stroke_path(),
         );
 
+        // Inputs beyond the body's corners need the input side extended
+        if let Some(input_count) = component.kind.input_count().filter(|&n| n > 3) {
+            builder.stroke(
+                &stroke,
+                transform,
+                stroke_color,
+                None,
+                &build_gate_input_extension(input_count),
+            );
+        }
+
+        if let ComponentKind::SevenSegment { sim_wire } = &component.kind {
+            let state = match circuit.sim_state() {
+                SimState::None => None,
+                SimState::Active { sim, .. }
+                | SimState::Conflict { sim, .. }
+                | SimState::Unstable { sim, .. } => sim.get_wire_state(*sim_wire).ok(),
+            };
+
+            for (bit, segment) in geometry_store.seven_segment_segments.iter().enumerate() {
+                let lit = state.as_ref().is_some_and(|state| {
+                    matches!(state.get_bit_state(bit as u8), gsim::LogicBitState::Logic1)
+                });
+
+                let segment_color = if lit {
+                    Color::rgb8(255, 32, 32)
+                } else {
+                    Color::rgb8(96, 32, 32)
+                };
+
+                builder.fill(Fill::NonZero, transform, segment_color, None, segment);
+            }
+        }
+
         for anchor in component.anchors() {
             let color = match anchor.kind {
-                AnchorKind::Input => Color::LIME,
-                AnchorKind::Output => Color::RED,
-                AnchorKind::BiDirectional => Color::YELLOW,
-                AnchorKind::Passive => Color::BLUE,
+                AnchorKind::Input => colors.input_anchor_color,
+                AnchorKind::Output => colors.output_anchor_color,
+                AnchorKind::BiDirectional => colors.bidirectional_anchor_color,
+                AnchorKind::Passive => colors.passive_anchor_color,
             };
 
             let shape = Circle::new(
@@ -426,7 +897,12 @@ fn draw_components(
                 (LOGICAL_PIXEL_SIZE * 2.0) as f64,
             );
 
-            builder.fill(Fill::NonZero, Affine::IDENTITY, color, None, &shape);
+            // Unconnected pins are drawn hollow so dangling anchors stand out
+            if wire_endpoints.contains_key(&anchor.position) {
+                builder.fill(Fill::NonZero, Affine::IDENTITY, color, None, &shape);
+            } else {
+                builder.stroke(&pin_stroke, Affine::IDENTITY, color, None, &shape);
+            }
         }
     }
 }
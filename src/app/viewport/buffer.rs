@@ -145,11 +145,17 @@ impl<T: Pod> StaticBuffer<T> {
 unsafe impl<T: Pod> Send for StaticBuffer<T> {}
 unsafe impl<T: Pod> Sync for StaticBuffer<T> {}
 
+/// Number of consecutive calls to `DynamicBuffer::maybe_shrink` with low usage before the buffer
+/// is actually shrunk, so it doesn't get reallocated back and forth
+const SHRINK_DELAY: u32 = 120;
+
 pub struct DynamicBuffer<T: Pod> {
     label: Option<String>,
     usage: BufferUsages,
+    min_capacity: usize,
     capacity: usize,
     len: usize,
+    low_usage_count: u32,
     buffer: RawBuffer,
     _t: PhantomData<*mut T>,
 }
@@ -168,8 +174,10 @@ impl<T: Pod> DynamicBuffer<T> {
         Self {
             label,
             usage,
+            min_capacity: capacity,
             capacity,
             len: 0,
+            low_usage_count: 0,
             buffer,
             _t: PhantomData,
         }
@@ -189,8 +197,10 @@ impl<T: Pod> DynamicBuffer<T> {
         Self {
             label,
             usage,
+            min_capacity: len,
             capacity: len,
             len,
+            low_usage_count: 0,
             buffer,
             _t: PhantomData,
         }
@@ -223,6 +233,26 @@ impl<T: Pod> DynamicBuffer<T> {
         self.buffer.write(queue, bytemuck::cast_slice(data));
     }
 
+    /// Reallocates the buffer with a smaller capacity if less than a quarter of it has been used
+    /// for a while. Meant to be called once per frame.
+    /// If the buffer gets reallocated its contents are discarded.
+    pub fn maybe_shrink(&mut self, device: &Device) {
+        if (self.len >= self.capacity / 4) || (self.capacity <= self.min_capacity) {
+            self.low_usage_count = 0;
+            return;
+        }
+
+        self.low_usage_count += 1;
+        if self.low_usage_count >= SHRINK_DELAY {
+            self.capacity = (self.len * 2).max(self.min_capacity);
+            self.len = 0;
+            self.low_usage_count = 0;
+
+            let min_size = size_of!(T) * self.capacity;
+            self.buffer = RawBuffer::create(device, self.label.as_deref(), self.usage, min_size);
+        }
+    }
+
     #[inline]
     pub fn slice(&self) -> BufferSlice<'_> {
         let len = size_of!(T) * self.len;
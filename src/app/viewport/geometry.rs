@@ -79,6 +79,57 @@ fn build_output_geometry() -> Geometry {
     Geometry::Same(path)
 }
 
+fn build_seven_segment_geometry() -> Geometry {
+    let mut path = BezPath::new();
+    path.move_to((-2.0, -3.0));
+    path.line_to((-2.0, 3.0));
+    path.line_to((2.0, 3.0));
+    path.line_to((2.0, -3.0));
+    path.close_path();
+
+    Geometry::Same(path)
+}
+
+/// Segments `a` to `g` followed by the decimal point, in the order of their input bits
+fn build_seven_segment_segments() -> [BezPath; 8] {
+    const HALF_THICKNESS: f64 = 0.15;
+    const X_OFFSET: f64 = -0.25;
+
+    fn horizontal(y: f64) -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to((X_OFFSET - 0.8, y - HALF_THICKNESS));
+        path.line_to((X_OFFSET - 0.8, y + HALF_THICKNESS));
+        path.line_to((X_OFFSET + 0.8, y + HALF_THICKNESS));
+        path.line_to((X_OFFSET + 0.8, y - HALF_THICKNESS));
+        path.close_path();
+        path
+    }
+
+    fn vertical(x: f64, y: f64) -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to((X_OFFSET + x - HALF_THICKNESS, y + 0.2));
+        path.line_to((X_OFFSET + x - HALF_THICKNESS, y + 1.8));
+        path.line_to((X_OFFSET + x + HALF_THICKNESS, y + 1.8));
+        path.line_to((X_OFFSET + x + HALF_THICKNESS, y + 0.2));
+        path.close_path();
+        path
+    }
+
+    let mut decimal_point = BezPath::new();
+    decimal_point.circle((X_OFFSET + 1.5, -2.0), 0.2);
+
+    [
+        horizontal(2.0),
+        vertical(1.0, 0.0),
+        vertical(1.0, -2.0),
+        horizontal(-2.0),
+        vertical(-1.0, -2.0),
+        vertical(-1.0, 0.0),
+        horizontal(0.0),
+        decimal_point,
+    ]
+}
+
 fn build_and_gate_geometry() -> Geometry {
     let mut path = BezPath::new();
     path.move_to((-2.0, -2.0));
@@ -178,15 +229,121 @@ fn build_xnor_gate_geometry() -> Geometry {
     Geometry::Different(fill_path, stroke_path)
 }
 
+fn build_buffer_geometry() -> Geometry {
+    let mut path = BezPath::new();
+    path.move_to((-2.0, -2.0));
+    path.line_to((0.0, 2.0));
+    path.line_to((2.0, -2.0));
+    path.close_path();
+
+    Geometry::Same(path)
+}
+
+fn build_not_gate_geometry() -> Geometry {
+    let mut path = BezPath::new();
+    path.circle((0.0, 2.5), 0.5);
+    path.move_to((-2.0, -2.0));
+    path.line_to((0.0, 2.0));
+    path.line_to((2.0, -2.0));
+    path.close_path();
+
+    Geometry::Same(path)
+}
+
+fn build_tri_state_buffer_geometry() -> Geometry {
+    let mut fill_path = BezPath::new();
+    fill_path.move_to((-2.0, -2.0));
+    fill_path.line_to((0.0, 2.0));
+    fill_path.line_to((2.0, -2.0));
+    fill_path.close_path();
+
+    // Enable input entering the side of the triangle
+    let mut stroke_path = fill_path.clone();
+    stroke_path.move_to((-2.0, 0.0));
+    stroke_path.line_to((-1.0, 0.0));
+
+    Geometry::Different(fill_path, stroke_path)
+}
+
+fn build_flip_flop_geometry() -> Geometry {
+    let mut fill_path = BezPath::new();
+    fill_path.move_to((-2.0, -2.0));
+    fill_path.line_to((-2.0, 2.0));
+    fill_path.line_to((2.0, 2.0));
+    fill_path.line_to((2.0, -2.0));
+    fill_path.close_path();
+
+    // Edge-triggered clock marker
+    let mut stroke_path = fill_path.clone();
+    stroke_path.move_to((0.5, -2.0));
+    stroke_path.line_to((1.0, -1.25));
+    stroke_path.line_to((1.5, -2.0));
+
+    Geometry::Different(fill_path, stroke_path)
+}
+
+fn build_tunnel_geometry() -> Geometry {
+    let mut path = BezPath::new();
+    path.move_to((0.0, -1.0));
+    path.line_to((-2.0, -0.25));
+    path.line_to((-2.0, 1.0));
+    path.line_to((2.0, 1.0));
+    path.line_to((2.0, -0.25));
+    path.close_path();
+
+    Geometry::Same(path)
+}
+
+pub(super) fn build_splitter_geometry(tap_count: usize) -> Geometry {
+    let bar_right = (tap_count.saturating_sub(1) * 2) as f64 + 0.5;
+
+    let mut fill_path = BezPath::new();
+    fill_path.move_to((-0.5, -0.25));
+    fill_path.line_to((-0.5, 0.25));
+    fill_path.line_to((bar_right, 0.25));
+    fill_path.line_to((bar_right, -0.25));
+    fill_path.close_path();
+
+    let mut stroke_path = fill_path.clone();
+    stroke_path.move_to((0.0, -1.0));
+    stroke_path.line_to((0.0, -0.25));
+    for i in 0..tap_count {
+        let x = (i * 2) as f64;
+        stroke_path.move_to((x, 0.25));
+        stroke_path.line_to((x, 1.0));
+    }
+
+    Geometry::Different(fill_path, stroke_path)
+}
+
+/// Extends the input side of a gate body so all of its inputs have something to attach to
+pub(super) fn build_gate_input_extension(input_count: u8) -> BezPath {
+    let half_width = (input_count as f64) - 1.0;
+
+    let mut path = BezPath::new();
+    path.move_to((-half_width, -2.0));
+    path.line_to((-2.0, -2.0));
+    path.move_to((2.0, -2.0));
+    path.line_to((half_width, -2.0));
+    path
+}
+
 pub(super) struct GeometryStore {
     pub(super) input_geometry: Geometry,
     pub(super) output_geometry: Geometry,
+    pub(super) seven_segment_geometry: Geometry,
+    pub(super) seven_segment_segments: [BezPath; 8],
     pub(super) and_gate_geometry: Geometry,
     pub(super) or_gate_geometry: Geometry,
     pub(super) xor_gate_geometry: Geometry,
     pub(super) nand_gate_geometry: Geometry,
     pub(super) nor_gate_geometry: Geometry,
     pub(super) xnor_gate_geometry: Geometry,
+    pub(super) buffer_geometry: Geometry,
+    pub(super) not_gate_geometry: Geometry,
+    pub(super) tri_state_buffer_geometry: Geometry,
+    pub(super) flip_flop_geometry: Geometry,
+    pub(super) tunnel_geometry: Geometry,
 }
 
 impl GeometryStore {
@@ -194,12 +351,19 @@ impl GeometryStore {
         Self {
             input_geometry: build_input_geometry(),
             output_geometry: build_output_geometry(),
+            seven_segment_geometry: build_seven_segment_geometry(),
+            seven_segment_segments: build_seven_segment_segments(),
             and_gate_geometry: build_and_gate_geometry(),
             or_gate_geometry: build_or_gate_geometry(),
             xor_gate_geometry: build_xor_gate_geometry(),
             nand_gate_geometry: build_nand_gate_geometry(),
             nor_gate_geometry: build_nor_gate_geometry(),
             xnor_gate_geometry: build_xnor_gate_geometry(),
+            buffer_geometry: build_buffer_geometry(),
+            not_gate_geometry: build_not_gate_geometry(),
+            tri_state_buffer_geometry: build_tri_state_buffer_geometry(),
+            flip_flop_geometry: build_flip_flop_geometry(),
+            tunnel_geometry: build_tunnel_geometry(),
         }
     }
 }
@@ -0,0 +1,175 @@
+use super::buffer::*;
+use super::pass::*;
+use super::{GridSettings, GridStyle, BASE_ZOOM, MAX_GRID_SPACING_UNITS, MIN_GRID_SPACING_UNITS};
+use crate::app::math::*;
+use bytemuck::{Pod, Zeroable};
+use eframe::egui_wgpu::RenderState;
+use vello::peniko::Color;
+use wgpu::*;
+
+/// Minimum on-screen distance between grid points, in pixels
+const MIN_GRID_SPACING: f32 = 20.0;
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+struct Globals {
+    background_color: [f32; 4],
+    minor_color: [f32; 4],
+    major_color: [f32; 4],
+    resolution: Vec2f,
+    offset: Vec2f,
+    zoom: f32,
+    step: f32,
+    /// 0 if the major grid is hidden
+    major_step: f32,
+    style: u32,
+}
+
+/// Draws the grid behind everything already in the render target, so it has to run after the
+/// circuit has been rendered with a transparent background
+pub struct GridPass {
+    _shader: ShaderModule,
+    global_buffer: StaticBuffer<Globals>,
+    _bind_group_layout: BindGroupLayout,
+    bind_group: BindGroup,
+    _pipeline_layout: PipelineLayout,
+    pipeline: RenderPipeline,
+}
+
+impl GridPass {
+    pub fn create(render_state: &RenderState) -> Self {
+        let shader = shader!(render_state.device, "grid");
+
+        let global_buffer = StaticBuffer::create(
+            &render_state.device,
+            Some("Viewport grid globals"),
+            BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            1,
+        );
+
+        let bind_group_layout =
+            render_state
+                .device
+                .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: ShaderStages::VERTEX_FRAGMENT,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: Some(global_buffer.byte_size()),
+                        },
+                        count: None,
+                    }],
+                });
+
+        let bind_group = render_state.device.create_bind_group(&BindGroupDescriptor {
+            label: None,
+            layout: &bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: global_buffer.as_binding(),
+            }],
+        });
+
+        // Vello stores colors with straight alpha, so this blends the grid below the circuit
+        let component = BlendComponent {
+            src_factor: BlendFactor::OneMinusDstAlpha,
+            dst_factor: BlendFactor::DstAlpha,
+            operation: BlendOperation::Add,
+        };
+        let alpha = BlendComponent {
+            src_factor: BlendFactor::OneMinusDstAlpha,
+            dst_factor: BlendFactor::One,
+            operation: BlendOperation::Add,
+        };
+
+        let (pipeline_layout, pipeline) = create_pipeline(
+            &render_state.device,
+            "grid",
+            &shader,
+            &bind_group_layout,
+            &[],
+            Some(BlendState {
+                color: component,
+                alpha,
+            }),
+        );
+
+        Self {
+            _shader: shader,
+            global_buffer,
+            _bind_group_layout: bind_group_layout,
+            bind_group,
+            _pipeline_layout: pipeline_layout,
+            pipeline,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &mut self,
+        render_state: &RenderState,
+        encoder: &mut CommandEncoder,
+        render_target: &TextureView,
+        resolution: Vec2f,
+        offset: Vec2f,
+        zoom: f32,
+        settings: &GridSettings,
+        background_color: Color,
+        grid_color: Color,
+    ) {
+        // Logical size of one screen pixel
+        let pixel = 1.0 / (zoom * BASE_ZOOM);
+
+        // Skip minor grid points until they are far enough apart on screen
+        let minor_spacing = settings
+            .minor_spacing
+            .clamp(MIN_GRID_SPACING_UNITS, MAX_GRID_SPACING_UNITS);
+        let mut step = minor_spacing;
+        while ((step as f32) / pixel) < MIN_GRID_SPACING {
+            step *= 2;
+        }
+
+        let major_step = minor_spacing * settings.major_spacing;
+        let major_step = if ((major_step as f32) / pixel) >= MIN_GRID_SPACING {
+            major_step
+        } else {
+            0
+        };
+
+        let opacity = settings.opacity.clamp(0.0, 1.0);
+        let mut minor_color = convert_color(grid_color);
+        minor_color[3] *= opacity;
+        let mut major_color = convert_color(grid_color);
+        major_color[3] *= (opacity * 2.0).min(1.0);
+
+        let style = match settings.style {
+            GridStyle::Dots => 0,
+            GridStyle::Lines => 1,
+            GridStyle::Crosses => 2,
+        };
+
+        self.global_buffer.write(
+            &render_state.queue,
+            &[Globals {
+                background_color: convert_color(background_color),
+                minor_color,
+                major_color,
+                resolution,
+                offset,
+                zoom: zoom * BASE_ZOOM,
+                step: step as f32,
+                major_step: major_step as f32,
+                style,
+            }],
+        );
+
+        encoder.render_pass(render_target, None, None, |pass, _| {
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.draw(0..6, 0..1);
+        });
+    }
+}
@@ -219,6 +219,55 @@ pub(super) trait RenderStateEx {
     fn resolve_pass(&self, view: &TextureView, resolve_target: &TextureView) {
         self.render_pass(view, Some(resolve_target), None, |_, _| {});
     }
+
+    /// Records all passes issued by `f` into a single command encoder and submits them at once
+    fn batch<F>(&self, label: Option<&str>, f: F)
+    where
+        F: FnOnce(&mut CommandEncoder);
+}
+
+pub(super) trait CommandEncoderEx {
+    fn render_pass<'env, F>(
+        &mut self,
+        view: &TextureView,
+        resolve_target: Option<&TextureView>,
+        clear_color: Option<Color>,
+        f: F,
+    ) where
+        // To restrict the lifetime of the closure in a way the compiler understands,
+        // this weird double reference is necessary.
+        for<'pass> F: FnOnce(&mut RenderPass<'pass>, &'pass &'env ());
+}
+
+impl CommandEncoderEx for CommandEncoder {
+    fn render_pass<'env, F>(
+        &mut self,
+        view: &TextureView,
+        resolve_target: Option<&TextureView>,
+        clear_color: Option<Color>,
+        f: F,
+    ) where
+        for<'pass> F: FnOnce(&mut RenderPass<'pass>, &'pass &'env ()),
+    {
+        let mut pass = self.begin_render_pass(&RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view,
+                resolve_target,
+                ops: Operations {
+                    load: if let Some(clear_color) = clear_color {
+                        LoadOp::Clear(clear_color)
+                    } else {
+                        LoadOp::Load
+                    },
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+
+        f(&mut pass, &&());
+    }
 }
 
 impl RenderStateEx for RenderState {
@@ -265,31 +314,21 @@ impl RenderStateEx for RenderState {
         f: F,
     ) where
         for<'pass> F: FnOnce(&mut RenderPass<'pass>, &'pass &'env ()),
+    {
+        self.batch(None, |encoder| {
+            encoder.render_pass(view, resolve_target, clear_color, f);
+        });
+    }
+
+    fn batch<F>(&self, label: Option<&str>, f: F)
+    where
+        F: FnOnce(&mut CommandEncoder),
     {
         let mut encoder = self
             .device
-            .create_command_encoder(&CommandEncoderDescriptor::default());
-
-        {
-            let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
-                label: None,
-                color_attachments: &[Some(RenderPassColorAttachment {
-                    view,
-                    resolve_target,
-                    ops: Operations {
-                        load: if let Some(clear_color) = clear_color {
-                            LoadOp::Clear(clear_color)
-                        } else {
-                            LoadOp::Load
-                        },
-                        store: true,
-                    },
-                })],
-                depth_stencil_attachment: None,
-            });
+            .create_command_encoder(&CommandEncoderDescriptor { label });
 
-            f(&mut pass, &&());
-        }
+        f(&mut encoder);
 
         self.queue.submit([encoder.finish()]);
     }
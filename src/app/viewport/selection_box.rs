@@ -125,6 +125,7 @@ impl SelectionBoxPass {
     pub fn draw(
         &mut self,
         render_state: &RenderState,
+        encoder: &mut CommandEncoder,
         render_target: &TextureView,
         resolution: Vec2f,
         offset: Vec2f,
@@ -197,7 +198,7 @@ impl SelectionBoxPass {
 
         self.vertex_buffer.write(&render_state.queue, &vertices);
 
-        render_state.render_pass(render_target, None, None, |pass, _| {
+        encoder.render_pass(render_target, None, None, |pass, _| {
             pass.set_pipeline(&self.pipeline);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.set_vertex_buffer(0, self.vertex_buffer.slice());
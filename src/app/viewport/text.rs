@@ -65,7 +65,7 @@ pub struct TextPass {
     global_buffer: StaticBuffer<Globals>,
     _bind_group_layout: BindGroupLayout,
     bind_group: BindGroup,
-    vertex_buffer: StaticBuffer<Vertex>,
+    vertex_buffer: DynamicBuffer<Vertex>,
     index_buffer: StaticBuffer<u16>,
     _pipeline_layout: PipelineLayout,
     pipeline: RenderPipeline,
@@ -97,7 +97,7 @@ impl TextPass {
             1,
         );
 
-        let vertex_buffer = StaticBuffer::create(
+        let vertex_buffer = DynamicBuffer::create(
             &render_state.device,
             Some("Viewport text vertices"),
             BufferUsages::VERTEX | BufferUsages::COPY_DST,
@@ -191,18 +191,28 @@ impl TextPass {
         }
     }
 
-    fn draw_batch(&mut self, render_state: &RenderState, texture_view: &TextureView) {
+    /// Draws all collected vertices in a single pass. Since the vertex buffer is only written
+    /// once before the commands are submitted, batches are selected using the base vertex.
+    fn draw_batches(
+        &mut self,
+        render_state: &RenderState,
+        encoder: &mut CommandEncoder,
+        texture_view: &TextureView,
+    ) {
         self.vertex_buffer
-            .write(&render_state.queue, &self.vertices);
+            .write(&render_state.device, &render_state.queue, &self.vertices);
 
-        render_state.render_pass(texture_view, None, None, |pass, _| {
+        encoder.render_pass(texture_view, None, None, |pass, _| {
             pass.set_pipeline(&self.pipeline);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.set_vertex_buffer(0, self.vertex_buffer.slice());
             pass.set_index_buffer(self.index_buffer.slice(), IndexFormat::Uint16);
 
-            let index_count = ((self.vertices.len() / 4) * 6) as u32;
-            pass.draw_indexed(0..index_count, 0, 0..1);
+            for (i, batch) in self.vertices.chunks(MAX_VERTEX_COUNT).enumerate() {
+                let index_count = ((batch.len() / 4) * 6) as u32;
+                let base_vertex = (i * MAX_VERTEX_COUNT) as i32;
+                pass.draw_indexed(0..index_count, base_vertex, 0..1);
+            }
         });
 
         self.vertices.clear();
@@ -210,8 +220,6 @@ impl TextPass {
 
     fn draw_text(
         &mut self,
-        render_state: &RenderState,
-        texture_view: &TextureView,
         text: &str,
         selected: bool,
         position: Vec2f,
@@ -254,10 +262,6 @@ impl TextPass {
 
                 rel_x += glyph.x_advance + kerning;
                 prev = Some(c);
-
-                if self.vertices.len() >= MAX_VERTEX_COUNT {
-                    self.draw_batch(render_state, texture_view);
-                }
             }
         }
     }
@@ -266,15 +270,28 @@ impl TextPass {
     pub fn draw(
         &mut self,
         render_state: &RenderState,
+        encoder: &mut CommandEncoder,
         render_target: &TextureView,
         circuit: &Circuit,
         resolution: Vec2f,
         offset: Vec2f,
         zoom: f32,
+        cull_rect: &Rectangle,
         colors: &ViewportColors,
     ) {
-        // TODO: cull the text to the visible area
-        // TODO: don't draw text that is unreadably small
+        // Font sizes are in grid units
+        const NAME_FONT_SIZE: f32 = 1.0;
+        const VALUE_FONT_SIZE: f32 = 0.8;
+        const VALUE_MARGIN: f32 = 0.5;
+        const NAME_MARGIN: f32 = 0.25;
+
+        self.vertex_buffer.maybe_shrink(&render_state.device);
+
+        // Text smaller than this many pixels is unreadable anyway
+        const MIN_FONT_PIXEL_SIZE: f32 = 4.0;
+        if (NAME_FONT_SIZE * zoom * BASE_ZOOM) < MIN_FONT_PIXEL_SIZE {
+            return;
+        }
 
         self.global_buffer.write(
             &render_state.queue,
@@ -288,10 +305,11 @@ impl TextPass {
             }],
         );
 
-        // Font sizes are in grid units
-        const NAME_FONT_SIZE: f32 = 1.0;
-
         for (i, component) in circuit.components().iter().enumerate() {
+            if !component.bounding_box().intersects(cull_rect) {
+                continue;
+            }
+
             let label = component.kind.label();
             let name = component.kind.name();
             let selected = circuit.selection().contains_component(i);
@@ -302,8 +320,6 @@ impl TextPass {
                     Vec2f::new(name_width, self.atlas.line_height) * NAME_FONT_SIZE * 0.5;
 
                 self.draw_text(
-                    render_state,
-                    render_target,
                     &label,
                     selected,
                     component.position().to_vec2f() - name_offset,
@@ -311,13 +327,48 @@ impl TextPass {
                 );
             }
 
-            if !name.is_empty() {
-                // TODO: draw name next to component
+            let name_dir = component
+                .kind
+                .name_direction()
+                .filter(|_| !name.is_empty())
+                .map(|dir| component.transform_direction(dir));
+
+            if let Some(dir) = name_dir {
+                let bb = component.bounding_box();
+                let center = bb.center();
+                let half_size = Vec2f::new(self.atlas.measure_text(name), self.atlas.line_height)
+                    * NAME_FONT_SIZE
+                    * 0.5;
+
+                // Rotations are multiples of 90°, so the direction is always axis aligned
+                let edge = center + (dir * Vec2f::new(bb.width(), bb.height()) * 0.5);
+                let name_center = edge + (dir * NAME_MARGIN) + (dir * half_size);
+
+                self.draw_text(name, selected, name_center - half_size, NAME_FONT_SIZE);
+            }
+
+            if let Some(value) = circuit.component_value_text(i) {
+                let bb = component.bounding_box();
+                let value_offset = Vec2f::new(0.0, self.atlas.line_height) * VALUE_FONT_SIZE * 0.5;
+
+                // Move out of the way of a name on the right side
+                let value_x = if name_dir.is_some_and(|dir| dir.x > 0.0) {
+                    bb.left - VALUE_MARGIN - (self.atlas.measure_text(&value) * VALUE_FONT_SIZE)
+                } else {
+                    bb.right + VALUE_MARGIN
+                };
+
+                self.draw_text(
+                    &value,
+                    selected,
+                    Vec2f::new(value_x, bb.center().y) - value_offset,
+                    VALUE_FONT_SIZE,
+                );
             }
         }
 
         if !self.vertices.is_empty() {
-            self.draw_batch(render_state, render_target);
+            self.draw_batches(render_state, encoder, render_target);
         }
     }
 }
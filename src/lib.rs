@@ -1,5 +1,10 @@
 mod app;
-pub use app::App;
+#[cfg(not(target_arch = "wasm32"))]
+pub use app::render_circuit_png;
+pub use app::{
+    App, Circuit, Color, Component, ComponentKind, GridSettings, GridStyle, ImportNetlistError,
+    LoadCircuitError, Rotation, StartSimError, Vec2f, Vec2i, ViewportColors, WireSegment,
+};
 
 macro_rules! size_of {
     ($t:ty) => {